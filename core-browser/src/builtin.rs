@@ -3,6 +3,7 @@ use std::sync::Mutex;
 use komodo::{
     builtin::komodo_assert,
     env::Environment,
+    exec::EvalError,
     object::{ExternFunction, Function, Object},
 };
 
@@ -10,20 +11,20 @@ pub static STDOUT: Mutex<String> = Mutex::new(String::new());
 pub static STDIN: Mutex<Vec<String>> = Mutex::new(vec![]);
 
 pub fn standard_env() -> Environment {
-    fn komodo_println(args: &[Object]) -> Object {
+    fn komodo_println(args: &[Object]) -> Result<Object, EvalError> {
         let mut guard = STDOUT.lock().unwrap();
         guard.push_str(&args[0].to_string());
         guard.push('\n');
 
-        Object::empty_tuple()
+        Ok(Object::empty_tuple())
     }
 
-    fn komodo_getln(_args: &[Object]) -> Object {
+    fn komodo_getln(_args: &[Object]) -> Result<Object, EvalError> {
         let mut guard = STDIN.lock().unwrap();
 
         let res = guard.pop().unwrap_or_default();
 
-        Object::String(res.into())
+        Ok(Object::String(res.into()))
     }
 
     let mut env = Environment::default();
@@ -0,0 +1,330 @@
+use std::num::NonZeroU64;
+
+use bigdecimal::{BigDecimal, One, Signed, ToPrimitive, Zero};
+use num_bigint::BigInt;
+use num_integer::Integer as _;
+use num_rational::BigRational;
+
+use crate::{
+    exec::EvalError,
+    numeric,
+    object::{Decimal, Integer, List, Object, Precision},
+};
+
+/// Terms of an irrational number given by `continuedFraction` and
+/// `convergents` when no count is given.
+const DEFAULT_TERMS: usize = 10;
+
+/// Past this many digits, the terms of an irrational number that are still
+/// uncertain are left out.
+const MAX_DIGITS: u64 = 2000;
+
+fn positive(func: &str, obj: &Object) -> Result<BigInt, EvalError> {
+    match obj.as_rational() {
+        Some(val) if val.is_integer() && val.is_positive() => Ok(val.to_integer()),
+        _ => Err(EvalError::bad_argument(func, "positive Integer", obj)),
+    }
+}
+
+fn count(func: &str, obj: &Object) -> Result<usize, EvalError> {
+    positive(func, obj)?
+        .to_usize()
+        .ok_or_else(|| EvalError::bad_argument(func, "smaller Integer", obj))
+}
+
+/// `val*10**-scale`, as a fraction.
+fn ratio(val: BigInt, scale: i64) -> BigRational {
+    let power = BigInt::from(10).pow(scale.unsigned_abs() as u32);
+
+    match scale.is_negative() {
+        true => BigRational::from_integer(val * power),
+        false => BigRational::new(val, power),
+    }
+}
+
+/// The exact value of a rational number or a decimal.
+pub fn exact(obj: &Object) -> Option<BigRational> {
+    match obj {
+        Object::Decimal(_) => {
+            let (val, scale) = obj.as_decimal()?.as_bigint_and_exponent();
+            Some(ratio(val, scale))
+        }
+        obj => obj.as_rational(),
+    }
+}
+
+/// The value of a number or an expression without variables, with
+/// `digits` significant digits.
+fn approximation(obj: &Object, digits: NonZeroU64) -> Option<BigDecimal> {
+    let current = Precision::current();
+    let precision = Precision::new(digits, current.rounding);
+
+    precision.install();
+    let res = numeric::value(obj);
+    current.install();
+
+    Some(precision.round(res?))
+}
+
+/// Up to `limit` terms of the continued fraction of a rational number.
+fn rational_terms(val: &BigRational, limit: usize) -> Vec<BigInt> {
+    let mut terms = vec![];
+    let mut val = val.to_owned();
+
+    while terms.len() < limit {
+        let term = val.floor();
+        val -= &term;
+        terms.push(term.to_integer());
+
+        if val.is_zero() {
+            break;
+        }
+        val = val.recip();
+    }
+
+    terms
+}
+
+/// The first terms of the continued fractions of every number in
+/// `[lo, hi]`, up to `limit` of them.
+fn common_terms(mut lo: BigRational, mut hi: BigRational, limit: usize) -> Vec<BigInt> {
+    let mut terms = vec![];
+
+    while terms.len() < limit {
+        let term = lo.floor();
+        if term != hi.floor() {
+            break;
+        }
+
+        lo -= &term;
+        hi -= &term;
+        terms.push(term.to_integer());
+
+        if lo.is_zero() {
+            break;
+        }
+        (lo, hi) = (hi.recip(), lo.recip());
+    }
+
+    terms
+}
+
+/// Up to `limit` terms of the continued fraction of an expression without
+/// variables. It is approximated with more and more digits, and only the
+/// terms that a small error can't change are kept.
+fn irrational_terms(obj: &Object, limit: usize) -> Option<Vec<BigInt>> {
+    let error = BigInt::from(100);
+    let mut digits = 2 * limit as u64 + 20;
+
+    loop {
+        let (val, scale) = approximation(obj, NonZeroU64::new(digits)?)?.as_bigint_and_exponent();
+        let terms = common_terms(
+            ratio(&val - &error, scale),
+            ratio(&val + &error, scale),
+            limit,
+        );
+
+        if terms.len() == limit || digits >= MAX_DIGITS {
+            return Some(terms);
+        }
+        digits *= 2;
+    }
+}
+
+/// The terms of the continued fraction of the first argument. Rational
+/// numbers and decimals are expanded exactly, up to the count in the second
+/// argument if there is one.
+fn terms_of(func: &str, args: &[Object]) -> Result<Vec<BigInt>, EvalError> {
+    let limit = args.get(1).map(|obj| count(func, obj)).transpose()?;
+
+    match exact(&args[0]) {
+        Some(val) => Ok(rational_terms(&val, limit.unwrap_or(usize::MAX))),
+        None => irrational_terms(&args[0], limit.unwrap_or(DEFAULT_TERMS))
+            .ok_or_else(|| EvalError::bad_argument(func, "Number", &args[0])),
+    }
+}
+
+/// The convergents of a continued fraction, from its terms.
+fn convergents_of(terms: &[BigInt]) -> Vec<BigRational> {
+    let (mut prev_numer, mut prev_denom) = (BigInt::zero(), BigInt::one());
+    let (mut numer, mut denom) = (BigInt::one(), BigInt::zero());
+
+    terms
+        .iter()
+        .map(|term| {
+            (prev_numer, numer) = (numer.to_owned(), term * &numer + &prev_numer);
+            (prev_denom, denom) = (denom.to_owned(), term * &denom + &prev_denom);
+            BigRational::new(numer.to_owned(), denom.to_owned())
+        })
+        .collect()
+}
+
+/// The closest fraction to `val` with a denominator up to `bound`. It is
+/// either the last convergent that fits, or the best semiconvergent after
+/// it.
+fn best_approximation(val: &BigRational, bound: &BigInt) -> BigRational {
+    if val.denom() <= bound {
+        return val.to_owned();
+    }
+
+    let (mut prev_numer, mut prev_denom) = (BigInt::zero(), BigInt::one());
+    let (mut numer, mut denom) = (BigInt::one(), BigInt::zero());
+    let mut rest = val.to_owned();
+
+    loop {
+        let term = rest.floor();
+        let next_denom = term.numer() * &denom + &prev_denom;
+        if next_denom > *bound {
+            break;
+        }
+
+        (prev_numer, numer) = (numer.to_owned(), term.numer() * &numer + &prev_numer);
+        (prev_denom, denom) = (denom, next_denom);
+        rest = (rest - term).recip();
+    }
+
+    let steps = (bound - &prev_denom).div_floor(&denom);
+    let semiconvergent =
+        BigRational::new(&prev_numer + &steps * &numer, &prev_denom + &steps * &denom);
+    let convergent = BigRational::new(numer, denom);
+
+    match (&convergent - val).abs() <= (&semiconvergent - val).abs() {
+        true => convergent,
+        false => semiconvergent,
+    }
+}
+
+fn int_list(terms: Vec<BigInt>) -> Object {
+    Object::List(List::from(
+        terms
+            .into_iter()
+            .map(|term| Object::Integer(Integer::from(term)))
+            .collect::<Vec<_>>(),
+    ))
+}
+
+pub fn to_decimal(args: &[Object]) -> Result<Object, EvalError> {
+    let digits = match args.get(1) {
+        Some(obj) => positive("toDecimal", obj)?
+            .to_u64()
+            .and_then(NonZeroU64::new)
+            .ok_or_else(|| EvalError::bad_argument("toDecimal", "smaller Integer", obj))?,
+        None => Precision::current().digits,
+    };
+
+    match approximation(&args[0], digits) {
+        Some(val) => Ok(Object::Decimal(Decimal::unrounded(val))),
+        None => Err(EvalError::bad_argument("toDecimal", "Number", &args[0])),
+    }
+}
+
+/// The exact value of a decimal as a fraction, or the closest fraction with
+/// a denominator up to the second argument. Expressions without variables
+/// are approximated with the current precision first.
+pub fn to_fraction(args: &[Object]) -> Result<Object, EvalError> {
+    let val = exact(&args[0])
+        .or_else(|| {
+            let (val, scale) =
+                approximation(&args[0], Precision::current().digits)?.as_bigint_and_exponent();
+            Some(ratio(val, scale))
+        })
+        .ok_or_else(|| EvalError::bad_argument("toFraction", "Number", &args[0]))?;
+
+    let res = match args.get(1) {
+        Some(obj) => best_approximation(&val, &positive("toFraction", obj)?),
+        None => val,
+    };
+
+    Ok(Object::from_rational(res))
+}
+
+pub fn continued_fraction(args: &[Object]) -> Result<Object, EvalError> {
+    Ok(int_list(terms_of("continuedFraction", args)?))
+}
+
+/// The convergents of a number, or of a continued fraction given as a list
+/// of its terms.
+pub fn convergents(args: &[Object]) -> Result<Object, EvalError> {
+    let terms = match &args[0] {
+        Object::List(list) => list
+            .list
+            .iter()
+            .enumerate()
+            .map(|(i, obj)| match obj.as_rational() {
+                Some(val) if val.is_integer() && (i == 0 || val.is_positive()) => {
+                    Ok(val.to_integer())
+                }
+                _ => Err(EvalError::bad_argument(
+                    "convergents",
+                    "continued fraction",
+                    &args[0],
+                )),
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        _ => terms_of("convergents", args)?,
+    };
+
+    Ok(Object::List(List::from(
+        convergents_of(&terms)
+            .into_iter()
+            .map(Object::from_rational)
+            .collect::<Vec<_>>(),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frac(numer: i32, denom: i32) -> BigRational {
+        BigRational::new(numer.into(), denom.into())
+    }
+
+    fn ints(vals: &[i32]) -> Vec<BigInt> {
+        vals.iter().map(|&val| BigInt::from(val)).collect()
+    }
+
+    #[test]
+    fn rational_expansion() {
+        assert_eq!(
+            rational_terms(&frac(415, 93), usize::MAX),
+            ints(&[4, 2, 6, 7])
+        );
+        assert_eq!(rational_terms(&frac(-7, 2), usize::MAX), ints(&[-4, 2]));
+        assert_eq!(rational_terms(&frac(415, 93), 2), ints(&[4, 2]));
+        assert_eq!(
+            convergents_of(&ints(&[4, 2, 6, 7])),
+            vec![frac(4, 1), frac(9, 2), frac(58, 13), frac(415, 93)]
+        );
+    }
+
+    #[test]
+    fn decimal_is_exact() {
+        let val = Object::Decimal(Decimal::new("0", "125"));
+
+        assert_eq!(exact(&val), Some(frac(1, 8)));
+    }
+
+    #[test]
+    fn best_approximations() {
+        let pi = ratio(BigInt::from(314159265358979_u64), 14);
+
+        assert_eq!(best_approximation(&pi, &BigInt::from(10)), frac(22, 7));
+        assert_eq!(best_approximation(&pi, &BigInt::from(1000)), frac(355, 113));
+        // 1/3 and 1/2 are as close to 5/12, and the convergent wins
+        assert_eq!(
+            best_approximation(&frac(5, 12), &BigInt::from(3)),
+            frac(1, 2)
+        );
+    }
+
+    #[test]
+    fn uncertain_terms_are_left_out() {
+        // The fifth term is 2 for 1.414, and 3 for 1.415
+        assert_eq!(
+            common_terms(frac(1414, 1000), frac(1415, 1000), 10),
+            ints(&[1, 2, 2, 2])
+        );
+        assert_eq!(common_terms(frac(29, 10), frac(31, 10), 10), vec![]);
+    }
+}
@@ -77,7 +77,7 @@ impl Hash for ASTNode {
 
 impl PartialOrd for ASTNode {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.kind.cmp(&other.kind))
+        Some(self.cmp(other))
     }
 }
 
@@ -100,15 +100,18 @@ pub enum ASTNodeKind {
         called: Box<ASTNode>,
         args: Vec<ASTNode>,
     },
+    /// Each arm has a pattern, maybe a guard, and a result.
     Case {
         expr: Box<ASTNode>,
-        pairs: Vec<(ASTNode, ASTNode)>,
+        arms: Vec<(ASTNode, Option<ASTNode>, ASTNode)>,
     },
     Char(char),
+    /// Each generator has a pattern, an iterable and maybe a guard. In
+    /// dictionary comprehensions, `element` is the key of `value`.
     Comprehension {
         element: Box<ASTNode>,
-        variable: String,
-        iterator: Box<ASTNode>,
+        value: Option<Box<ASTNode>>,
+        generators: Vec<(ASTNode, ASTNode, Option<ASTNode>)>,
         kind: ComprehensionKind,
     },
     IndexNotation {
@@ -147,6 +150,7 @@ pub enum ASTNodeKind {
         positive: Box<ASTNode>,
         negative: Box<ASTNode>,
     },
+    Imaginary(Box<ASTNode>),
     ImportFrom {
         source: String,
         values: Vec<(String, Position)>,
@@ -160,6 +164,16 @@ pub enum ASTNodeKind {
         literal: String,
         radix: Radix,
     },
+    /// Declarations that only hold inside `body`, from `let ... in` and
+    /// `where`.
+    Local {
+        declarations: Vec<ASTNode>,
+        body: Box<ASTNode>,
+    },
+    Modular {
+        val: Box<ASTNode>,
+        modulus: Box<ASTNode>,
+    },
     // Declaration {
     //     left: Box<ASTNode>,
     //     right: Box<ASTNode>,
@@ -192,6 +206,11 @@ pub enum ASTNodeKind {
         list: Vec<ASTNode>,
     },
     Wildcard,
+    /// `body`, with `digits` significant digits for `Decimal` operations.
+    WithPrecision {
+        digits: Box<ASTNode>,
+        body: Box<ASTNode>,
+    },
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -211,11 +230,13 @@ pub enum Declaration {
     Function {
         name: String,
         params: Vec<ASTNode>,
+        guard: Option<Box<ASTNode>>,
         result: Box<ASTNode>,
     },
     MemoizedFunction {
         name: String,
         params: Vec<ASTNode>,
+        guard: Option<Box<ASTNode>>,
         result: Box<ASTNode>,
     },
 }
@@ -242,6 +263,12 @@ pub mod tests {
         ASTNode::new(ASTNodeKind::Fraction { numer, denom }, position)
     }
 
+    pub fn modular(val: ASTNode, modulus: ASTNode, position: Position) -> ASTNode {
+        let val = Box::new(val);
+        let modulus = Box::new(modulus);
+        ASTNode::new(ASTNodeKind::Modular { val, modulus }, position)
+    }
+
     pub fn symbol(name: &str, position: Position) -> ASTNode {
         let name = name.to_string();
         ASTNode::new(ASTNodeKind::Symbol { name }, position)
@@ -296,12 +323,12 @@ pub mod tests {
         position: Position,
     ) -> ASTNode {
         let element = Box::new(element);
-        let iterator = Box::new(iterator);
+        let variable = symbol(&variable, iterator.position);
         ASTNode::new(
             ASTNodeKind::Comprehension {
                 element,
-                variable,
-                iterator,
+                value: None,
+                generators: vec![(variable, iterator, None)],
                 kind,
             },
             position,
@@ -314,6 +341,10 @@ pub mod tests {
         ASTNode::new(ASTNodeKind::Decimal { int, dec }, position)
     }
 
+    pub fn imaginary(val: ASTNode, position: Position) -> ASTNode {
+        ASTNode::new(ASTNodeKind::Imaginary(Box::new(val)), position)
+    }
+
     pub fn extension_set(list: Vec<ASTNode>, position: Position) -> ASTNode {
         ASTNode::new(ASTNodeKind::Set { list }, position)
     }
@@ -357,6 +388,7 @@ pub mod tests {
             ASTNodeKind::Declaration(Declaration::Function {
                 name,
                 params,
+                guard: None,
                 result,
             }),
             position,
@@ -375,6 +407,7 @@ pub mod tests {
             ASTNodeKind::Declaration(Declaration::MemoizedFunction {
                 name,
                 params,
+                guard: None,
                 result,
             }),
             position,
@@ -481,9 +514,13 @@ pub mod tests {
         )
     }
 
-    pub fn case(expr: ASTNode, pairs: Vec<(ASTNode, ASTNode)>, position: Position) -> ASTNode {
+    pub fn case(
+        expr: ASTNode,
+        arms: Vec<(ASTNode, Option<ASTNode>, ASTNode)>,
+        position: Position,
+    ) -> ASTNode {
         let expr = Box::new(expr);
 
-        ASTNode::new(ASTNodeKind::Case { expr, pairs }, position)
+        ASTNode::new(ASTNodeKind::Case { expr, arms }, position)
     }
 }
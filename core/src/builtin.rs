@@ -1,37 +1,112 @@
 use crate::{
+    approximation, calculus, complex, digits, elementary,
     env::{Environment, ExecContext},
-    exec::truthy,
-    object::{ExternFunction, FailedAssertion, Function, MyString, Object, Tuple},
+    exec::{truthy, EvalError},
+    factor, interval, matrix, numtheory,
+    object::{
+        ExternFunction, FailedAssertion, Function, Integer, Kind, Modular, MyString, Object,
+        Precision, Rounding, Tuple,
+    },
+    polynomial, random, series,
+    simplify::simplify,
+    solve, stream, sums, symbolic,
 };
 
-use std::io::{stdin, BufRead};
+use std::{
+    io::{stdin, BufRead},
+    num::NonZeroU64,
+};
+
+use bigdecimal::ToPrimitive;
+use num_bigint::BigInt;
 
-fn komodo_println(args: &[Object]) -> Object {
+fn komodo_println(args: &[Object]) -> Result<Object, EvalError> {
     let str = args[0].to_string();
     println!("{str}");
-    Object::Tuple(Tuple::from(vec![]))
+    Ok(Object::Tuple(Tuple::from(vec![])))
 }
 
-fn komodo_print(args: &[Object]) -> Object {
+fn komodo_print(args: &[Object]) -> Result<Object, EvalError> {
     let str = args[0].to_string();
     print!("{str}");
-    Object::Tuple(Tuple::from(vec![]))
+    Ok(Object::Tuple(Tuple::from(vec![])))
 }
 
-fn komodo_getln(_args: &[Object]) -> Object {
+fn komodo_getln(_args: &[Object]) -> Result<Object, EvalError> {
     let mut line = String::new();
     stdin().lock().read_line(&mut line).unwrap();
 
     line.pop();
 
-    Object::String(MyString::from(line.as_str()))
+    Ok(Object::String(MyString::from(line.as_str())))
+}
+
+pub fn komodo_assert(args: &[Object]) -> Result<Object, EvalError> {
+    match (truthy(&args[0])?, args.len()) {
+        (false, len) if len > 1 => Ok(Object::Error(FailedAssertion(Some(args[1].to_string())))),
+        (false, _) => Ok(Object::Error(FailedAssertion(None))),
+        _ => Ok(Object::empty_tuple()),
+    }
+}
+
+fn komodo_simplify(args: &[Object]) -> Result<Object, EvalError> {
+    Ok(simplify(&args[0]))
 }
 
-pub fn komodo_assert(args: &[Object]) -> Object {
-    match (truthy(&args[0]), args.len()) {
-        (false, len) if len > 1 => Object::Error(FailedAssertion(Some(args[1].to_string()))),
-        (false, _) => Object::Error(FailedAssertion(None)),
-        _ => Object::empty_tuple(),
+/// The number of digits in `obj`, which must be a positive integer.
+pub fn precision_digits(obj: &Object, func: &str) -> Result<NonZeroU64, EvalError> {
+    match obj {
+        Object::Integer(_) => obj
+            .as_rational()
+            .and_then(|val| val.to_integer().to_u64())
+            .and_then(NonZeroU64::new),
+        _ => None,
+    }
+    .ok_or_else(|| EvalError::bad_argument(func, "positive Integer", obj))
+}
+
+fn komodo_set_precision(args: &[Object]) -> Result<Object, EvalError> {
+    let bad_argument =
+        |expected: &str, obj: &Object| EvalError::bad_argument("setPrecision", expected, obj);
+
+    let digits = precision_digits(&args[0], "setPrecision")?;
+
+    let rounding = match args.get(1) {
+        None => Precision::current().rounding,
+        Some(obj @ Object::String(name)) => Rounding::from_name(&name.to_string())
+            .ok_or_else(|| bad_argument("rounding mode", obj))?,
+        Some(obj) => return Err(bad_argument("String", obj)),
+    };
+
+    Precision::new(digits, rounding).install();
+    Ok(Object::Tuple(Tuple::from(vec![])))
+}
+
+fn komodo_precision(_args: &[Object]) -> Result<Object, EvalError> {
+    Ok(Object::Integer(Integer::from(BigInt::from(
+        Precision::current().digits.get(),
+    ))))
+}
+
+/// `Mod(n)` is the function that takes an integer to the integers modulo `n`.
+fn komodo_mod(args: &[Object]) -> Result<Object, EvalError> {
+    match &args[0] {
+        Object::Integer(int) if int.is_positive() => Ok(Object::Function(Function::Extern(
+            ExternFunction::bound(modular, vec![args[0].to_owned()], 1),
+        ))),
+        obj => Err(EvalError::bad_argument("Mod", "positive Integer", obj)),
+    }
+}
+
+fn modular(args: &[Object]) -> Result<Object, EvalError> {
+    match (&args[1], &args[0]) {
+        (Object::Integer(val), Object::Integer(modulus)) => {
+            Ok(Object::Modular(Modular::new(val, modulus)))
+        }
+        (val, modulus) => Err(EvalError::BadModular {
+            val_kind: val.kind(),
+            modulus_kind: modulus.kind(),
+        }),
     }
 }
 
@@ -45,6 +120,123 @@ fn env_with(assets: Vec<(&str, Object)>, ctx: ExecContext) -> Environment {
     env
 }
 
+/// Modules implemented in Rust, which are imported like the ones written
+/// in Komodo. They share the context of the environment that imports them,
+/// so loading one never brings back the default precision.
+pub fn native_module(name: &str, ctx: &ExecContext) -> Option<Environment> {
+    match name {
+        "numtheory" => Some(env_with(
+            vec![
+                (
+                    "isPrime",
+                    Object::Function(Function::Extern(ExternFunction::new(
+                        numtheory::is_prime_extern,
+                        1,
+                    ))),
+                ),
+                (
+                    "gcd",
+                    Object::Function(Function::Extern(ExternFunction::new(numtheory::gcd, 2))),
+                ),
+                (
+                    "lcm",
+                    Object::Function(Function::Extern(ExternFunction::new(numtheory::lcm, 2))),
+                ),
+                (
+                    "modpow",
+                    Object::Function(Function::Extern(ExternFunction::new(numtheory::modpow, 3))),
+                ),
+                (
+                    "modinv",
+                    Object::Function(Function::Extern(ExternFunction::new(numtheory::modinv, 2))),
+                ),
+                (
+                    "totient",
+                    Object::Function(Function::Extern(ExternFunction::new(numtheory::totient, 1))),
+                ),
+                (
+                    "divisors",
+                    Object::Function(Function::Extern(ExternFunction::new(
+                        numtheory::divisors_extern,
+                        1,
+                    ))),
+                ),
+                (
+                    "jacobi",
+                    Object::Function(Function::Extern(ExternFunction::new(numtheory::jacobi, 2))),
+                ),
+            ],
+            ctx.clone(),
+        )),
+        "cmath" => Some(env_with(
+            vec![
+                (
+                    "sqrt",
+                    Object::Function(Function::Extern(ExternFunction::new(complex::sqrt, 1))),
+                ),
+                (
+                    "exp",
+                    Object::Function(Function::Extern(ExternFunction::new(complex::exp, 1))),
+                ),
+                (
+                    "ln",
+                    Object::Function(Function::Extern(ExternFunction::new(complex::ln, 1))),
+                ),
+                (
+                    "sin",
+                    Object::Function(Function::Extern(ExternFunction::new(complex::sin, 1))),
+                ),
+                (
+                    "cos",
+                    Object::Function(Function::Extern(ExternFunction::new(complex::cos, 1))),
+                ),
+                (
+                    "tan",
+                    Object::Function(Function::Extern(ExternFunction::new(complex::tan, 1))),
+                ),
+                (
+                    "pow",
+                    Object::Function(Function::Extern(ExternFunction::new(complex::pow, 2))),
+                ),
+            ],
+            ctx.clone(),
+        )),
+        "random" => Some(env_with(
+            vec![
+                (
+                    "seed",
+                    Object::Function(Function::Extern(ExternFunction::new(random::seed, 1))),
+                ),
+                (
+                    "randInt",
+                    Object::Function(Function::Extern(ExternFunction::new(random::rand_int, 1))),
+                ),
+                (
+                    "choice",
+                    Object::Function(Function::Extern(ExternFunction::new(random::choice, 1))),
+                ),
+                (
+                    "sample",
+                    Object::Function(Function::Extern(ExternFunction::new(random::sample, 2))),
+                ),
+                (
+                    "shuffle",
+                    Object::Function(Function::Extern(ExternFunction::new(random::shuffle, 1))),
+                ),
+                (
+                    "randFraction",
+                    Object::Function(Function::Extern(ExternFunction::new(
+                        random::rand_fraction,
+                        1,
+                    ))),
+                ),
+            ],
+            ctx.clone(),
+        )),
+        _ => None,
+    }
+}
+
 pub fn standard_env(ctx: ExecContext) -> Environment {
     env_with(
         vec![
@@ -64,6 +256,223 @@ pub fn standard_env(ctx: ExecContext) -> Environment {
                 "assert",
                 Object::Function(Function::Extern(ExternFunction::new(komodo_assert, 1))),
             ),
+            (
+                "simplify",
+                Object::Function(Function::Extern(ExternFunction::new(komodo_simplify, 1))),
+            ),
+            (
+                "diff",
+                Object::Function(Function::Extern(ExternFunction::new(calculus::diff, 2))),
+            ),
+            (
+                "subs",
+                Object::Function(Function::Extern(ExternFunction::new(symbolic::subs, 2))),
+            ),
+            (
+                "poly",
+                Object::Function(Function::Extern(ExternFunction::new(polynomial::poly, 1))),
+            ),
+            (
+                "degree",
+                Object::Function(Function::Extern(ExternFunction::new(polynomial::degree, 1))),
+            ),
+            (
+                "coeffs",
+                Object::Function(Function::Extern(ExternFunction::new(polynomial::coeffs, 1))),
+            ),
+            (
+                "gcd",
+                Object::Function(Function::Extern(ExternFunction::new(polynomial::gcd, 2))),
+            ),
+            (
+                "factor",
+                Object::Function(Function::Extern(ExternFunction::new(factor::factor, 1))),
+            ),
+            (
+                "solve",
+                Object::Function(Function::Extern(ExternFunction::new(solve::solve, 2))),
+            ),
+            (
+                "summation",
+                Object::Function(Function::Extern(ExternFunction::new(sums::summation, 3))),
+            ),
+            (
+                "product",
+                Object::Function(Function::Extern(ExternFunction::new(sums::product_of, 3))),
+            ),
+            (
+                "setPrecision",
+                Object::Function(Function::Extern(ExternFunction::new(
+                    komodo_set_precision,
+                    1,
+                ))),
+            ),
+            (
+                "precision",
+                Object::Function(Function::Extern(ExternFunction::new(komodo_precision, 0))),
+            ),
+            (
+                "toDecimal",
+                Object::Function(Function::Extern(ExternFunction::new(
+                    approximation::to_decimal,
+                    1,
+                ))),
+            ),
+            (
+                "toFraction",
+                Object::Function(Function::Extern(ExternFunction::new(
+                    approximation::to_fraction,
+                    1,
+                ))),
+            ),
+            (
+                "continuedFraction",
+                Object::Function(Function::Extern(ExternFunction::new(
+                    approximation::continued_fraction,
+                    1,
+                ))),
+            ),
+            (
+                "convergents",
+                Object::Function(Function::Extern(ExternFunction::new(
+                    approximation::convergents,
+                    1,
+                ))),
+            ),
+            (
+                "toBase",
+                Object::Function(Function::Extern(ExternFunction::new(digits::to_base, 2))),
+            ),
+            (
+                "digits",
+                Object::Function(Function::Extern(ExternFunction::new(digits::digits, 1))),
+            ),
+            (
+                "fromDigits",
+                Object::Function(Function::Extern(ExternFunction::new(
+                    digits::from_digits,
+                    1,
+                ))),
+            ),
+            (
+                "interval",
+                Object::Function(Function::Extern(ExternFunction::new(interval::interval, 1))),
+            ),
+            (
+                "lower",
+                Object::Function(Function::Extern(ExternFunction::new(interval::lower, 1))),
+            ),
+            (
+                "upper",
+                Object::Function(Function::Extern(ExternFunction::new(interval::upper, 1))),
+            ),
+            ("unknown", interval::unknown()),
+            (
+                "take",
+                Object::Function(Function::Extern(ExternFunction::new(stream::take, 2))),
+            ),
+            (
+                "drop",
+                Object::Function(Function::Extern(ExternFunction::new(stream::drop, 2))),
+            ),
+            (
+                "takeWhile",
+                Object::Function(Function::Extern(ExternFunction::new(stream::take_while, 2))),
+            ),
+            (
+                "iterate",
+                Object::Function(Function::Extern(ExternFunction::new(stream::iterate, 2))),
+            ),
+            (
+                "sequence",
+                Object::Function(Function::Extern(ExternFunction::new(stream::sequence, 1))),
+            ),
+            (
+                "limit",
+                Object::Function(Function::Extern(ExternFunction::new(series::limit, 3))),
+            ),
+            (
+                "series",
+                Object::Function(Function::Extern(ExternFunction::new(series::series, 4))),
+            ),
+            (
+                "re",
+                Object::Function(Function::Extern(ExternFunction::new(complex::re, 1))),
+            ),
+            (
+                "im",
+                Object::Function(Function::Extern(ExternFunction::new(complex::im, 1))),
+            ),
+            (
+                "conj",
+                Object::Function(Function::Extern(ExternFunction::new(complex::conj, 1))),
+            ),
+            (
+                "matrix",
+                Object::Function(Function::Extern(ExternFunction::new(matrix::matrix, 1))),
+            ),
+            (
+                "identity",
+                Object::Function(Function::Extern(ExternFunction::new(matrix::identity, 1))),
+            ),
+            (
+                "det",
+                Object::Function(Function::Extern(ExternFunction::new(matrix::det, 1))),
+            ),
+            (
+                "inverse",
+                Object::Function(Function::Extern(ExternFunction::new(matrix::inverse, 1))),
+            ),
+            (
+                "rank",
+                Object::Function(Function::Extern(ExternFunction::new(matrix::rank, 1))),
+            ),
+            (
+                "transpose",
+                Object::Function(Function::Extern(ExternFunction::new(matrix::transpose, 1))),
+            ),
+            (
+                "rref",
+                Object::Function(Function::Extern(ExternFunction::new(matrix::rref, 1))),
+            ),
+            (
+                "nullspace",
+                Object::Function(Function::Extern(ExternFunction::new(matrix::nullspace, 1))),
+            ),
+            (
+                "Mod",
+                Object::Function(Function::Extern(ExternFunction::new(komodo_mod, 1))),
+            ),
+            ("infinity", series::infinity()),
+            ("pi", elementary::pi()),
+            (
+                "sin",
+                Object::Function(Function::Extern(ExternFunction::new(elementary::sin, 1))),
+            ),
+            (
+                "cos",
+                Object::Function(Function::Extern(ExternFunction::new(elementary::cos, 1))),
+            ),
+            (
+                "tan",
+                Object::Function(Function::Extern(ExternFunction::new(elementary::tan, 1))),
+            ),
+            (
+                "atan",
+                Object::Function(Function::Extern(ExternFunction::new(elementary::atan, 1))),
+            ),
+            (
+                "exp",
+                Object::Function(Function::Extern(ExternFunction::new(elementary::exp, 1))),
+            ),
+            (
+                "ln",
+                Object::Function(Function::Extern(ExternFunction::new(elementary::ln, 1))),
+            ),
+            (
+                "sqrt",
+                Object::Function(Function::Extern(ExternFunction::new(elementary::sqrt, 1))),
+            ),
         ],
         ctx,
     )
@@ -0,0 +1,237 @@
+use crate::{
+    elementary,
+    exec::EvalError,
+    object::{Object, Symbol},
+    simplify::simplify,
+    symbolic::{self, Expression},
+};
+
+fn sum(terms: Vec<Object>) -> Object {
+    Object::Expression(Expression::Sum(terms))
+}
+
+fn product(factors: Vec<Object>) -> Object {
+    Object::Expression(Expression::Product(factors))
+}
+
+fn power(base: Object, exp: Object) -> Object {
+    Object::Expression(Expression::Power(Box::new(base), Box::new(exp)))
+}
+
+pub fn depends_on(expr: &Object, var: &Symbol) -> bool {
+    match expr {
+        Object::Symbol(symbol) => symbol == var,
+        Object::Expression(Expression::Sum(objs))
+        | Object::Expression(Expression::Product(objs))
+        | Object::Expression(Expression::Call(_, objs)) => {
+            objs.iter().any(|obj| depends_on(obj, var))
+        }
+        Object::Expression(Expression::Power(base, exp)) => {
+            depends_on(base, var) || depends_on(exp, var)
+        }
+        Object::Expression(Expression::Remainder(lhs, rhs))
+        | Object::Expression(Expression::Equation(lhs, rhs))
+        | Object::Expression(Expression::Inequation(lhs, rhs))
+        | Object::Expression(Expression::Range(lhs, rhs)) => {
+            depends_on(lhs, var) || depends_on(rhs, var)
+        }
+        _ => false,
+    }
+}
+
+fn outer_derivative(name: &str, arg: &Object) -> Option<Object> {
+    let res = match name {
+        "sin" => elementary::apply("cos", arg),
+        "cos" => product(vec![Object::int(-1), elementary::apply("sin", arg)]),
+        "tan" => sum(vec![
+            Object::int(1),
+            power(elementary::apply("tan", arg), Object::int(2)),
+        ]),
+        "atan" => power(
+            sum(vec![Object::int(1), power(arg.to_owned(), Object::int(2))]),
+            Object::int(-1),
+        ),
+        "exp" => elementary::apply("exp", arg),
+        "ln" => power(arg.to_owned(), Object::int(-1)),
+        _ => return None,
+    };
+
+    Some(res)
+}
+
+fn derive(expr: &Object, var: &Symbol) -> Object {
+    if !depends_on(expr, var) {
+        return Object::int(0);
+    }
+
+    match expr {
+        Object::Expression(Expression::Sum(terms)) => {
+            sum(terms.iter().map(|term| derive(term, var)).collect())
+        }
+        Object::Expression(Expression::Product(factors)) => sum((0..factors.len())
+            .map(|i| {
+                let mut factors = factors.to_owned();
+                factors[i] = derive(&factors[i], var);
+                product(factors)
+            })
+            .collect()),
+        Object::Expression(Expression::Power(base, exp)) if !depends_on(exp, var) => product(vec![
+            *exp.to_owned(),
+            power(
+                *base.to_owned(),
+                sum(vec![*exp.to_owned(), Object::int(-1)]),
+            ),
+            derive(base, var),
+        ]),
+        Object::Expression(Expression::Power(base, exp)) => product(vec![
+            expr.to_owned(),
+            sum(vec![
+                product(vec![derive(exp, var), elementary::apply("ln", base)]),
+                product(vec![
+                    *exp.to_owned(),
+                    derive(base, var),
+                    power(*base.to_owned(), Object::int(-1)),
+                ]),
+            ]),
+        ]),
+        Object::Expression(Expression::Call(name, args)) => match (args.as_slice(), name) {
+            ([arg], name) => match outer_derivative(name, arg) {
+                Some(outer) => product(vec![outer, derive(arg, var)]),
+                None => unevaluated(expr, var),
+            },
+            _ => unevaluated(expr, var),
+        },
+        Object::Expression(Expression::Remainder(..)) => unevaluated(expr, var),
+        Object::Expression(Expression::Equation(lhs, rhs)) => Object::Expression(
+            Expression::Equation(Box::new(derive(lhs, var)), Box::new(derive(rhs, var))),
+        ),
+        _ => Object::int(1),
+    }
+}
+
+fn unevaluated(expr: &Object, var: &Symbol) -> Object {
+    symbolic::call(
+        "diff",
+        vec![expr.to_owned(), Object::Symbol(var.to_owned())],
+    )
+}
+
+/// Differentiates `expr` with respect to `var`, returning the simplified result.
+pub fn derivative(expr: &Object, var: &Symbol) -> Object {
+    simplify(&derive(&simplify(expr), var))
+}
+
+pub fn diff(args: &[Object]) -> Result<Object, EvalError> {
+    let bad_argument =
+        |expected: &str, obj: &Object| EvalError::bad_argument("diff", expected, obj);
+
+    let expr = match &args[0] {
+        obj if obj.is_numeric() || obj.is_symbolic() => obj,
+        obj => return Err(bad_argument("Expression", obj)),
+    };
+
+    let var = match &args[1] {
+        Object::Symbol(var) => var,
+        obj => return Err(bad_argument("Symbol", obj)),
+    };
+
+    let order = match args.get(2) {
+        None => 1,
+        Some(obj) => match obj.as_rational() {
+            Some(val) if val.is_integer() && !obj.is_negative() => {
+                val.to_integer().try_into().unwrap_or(usize::MAX)
+            }
+            _ => return Err(bad_argument("Natural", obj)),
+        },
+    };
+
+    let mut res = expr.to_owned();
+    for _ in 0..order {
+        if res.is_zero() {
+            break;
+        }
+
+        res = derivative(&res, var);
+    }
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        object::{MyString, Symbol},
+        testing::{int, x, y},
+    };
+
+    use super::*;
+
+    fn d(expr: Object) -> String {
+        derivative(&expr, &Symbol::new("x".into(), "Real".into())).to_string()
+    }
+
+    #[test]
+    fn polynomial() {
+        let expr = sum(vec![
+            product(vec![int(3), power(x(), int(2))]),
+            product(vec![y(), x()]),
+            y(),
+        ]);
+
+        assert_eq!(d(expr), "6*x + y");
+    }
+
+    #[test]
+    fn product_rule() {
+        let expr = product(vec![x(), elementary::apply("sin", &x())]);
+
+        assert_eq!(d(expr), "x*cos(x) + sin(x)");
+    }
+
+    #[test]
+    fn quotient_rule() {
+        let expr = product(vec![x(), power(sum(vec![x(), int(1)]), int(-1))]);
+
+        assert_eq!(d(expr), "-x/(x**2 + 2*x + 1) + 1/(x + 1)");
+    }
+
+    #[test]
+    fn chain_rule() {
+        let expr = elementary::apply("exp", &power(x(), int(2)));
+
+        assert_eq!(d(expr), "2*x*exp(x**2)");
+    }
+
+    #[test]
+    fn variable_exponent() {
+        let expr = power(x(), x());
+
+        assert_eq!(d(expr), "x**x*ln(x) + x**x");
+    }
+
+    #[test]
+    fn unknown_function() {
+        let expr = symbolic::call("f", vec![x()]);
+
+        assert_eq!(d(expr), "diff(f(x), x)");
+    }
+
+    #[test]
+    fn higher_order() {
+        let expr = power(x(), int(3));
+
+        assert_eq!(diff(&[expr, x(), int(2)]), Ok(product(vec![int(6), x()])));
+    }
+
+    #[test]
+    fn non_symbol_variable() {
+        assert_eq!(
+            diff(&[x(), Object::String(MyString::from("x"))]),
+            Err(EvalError::BadArgument {
+                func: "diff".into(),
+                expected: "Symbol".into(),
+                actual: "String".into(),
+            })
+        );
+    }
+}
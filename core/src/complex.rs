@@ -0,0 +1,508 @@
+use std::fmt;
+
+use bigdecimal::{BigDecimal, Signed, Zero};
+use num_bigint::BigInt;
+use num_integer::Integer as _;
+use num_rational::BigRational;
+
+use crate::{
+    exec::EvalError,
+    numeric,
+    object::{Decimal, Fraction, InfixOperable, Object, PrefixOperable},
+    symbolic,
+};
+
+/// A complex number `re + im*i`. Both parts are exact, an `Integer` or a
+/// `Fraction`, or both are a `Decimal`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Complex {
+    pub re: Box<Object>,
+    pub im: Box<Object>,
+}
+
+fn decimal(val: BigDecimal) -> Object {
+    Object::Decimal(Decimal::from(val))
+}
+
+// Operations between real numbers can only fail when dividing by zero.
+
+fn sum(lhs: &Object, rhs: &Object) -> Object {
+    lhs.sum(rhs).unwrap()
+}
+
+fn sub(lhs: &Object, rhs: &Object) -> Object {
+    lhs.substraction(rhs).unwrap()
+}
+
+fn mul(lhs: &Object, rhs: &Object) -> Object {
+    lhs.product(rhs).unwrap()
+}
+
+fn div(numer: &Object, denom: &Object) -> Option<Object> {
+    if denom.is_zero() {
+        return None;
+    }
+
+    match (numer.as_rational(), denom.as_rational()) {
+        (Some(numer), Some(denom)) => Some(Object::from_rational(numer / denom)),
+        _ => numer.over(denom),
+    }
+}
+
+/// The square root of a nonnegative rational, when it is rational too.
+fn exact_sqrt(obj: &Object) -> Option<Object> {
+    let val = obj.as_rational().filter(|val| !val.is_negative())?;
+    let root = |n: &BigInt| Some(n.sqrt()).filter(|root| root * root == *n);
+
+    Some(Object::from_rational(BigRational::new(
+        root(val.numer())?,
+        root(val.denom())?,
+    )))
+}
+
+fn decimal_sqrt(obj: &Object) -> Option<Object> {
+    numeric::apply("sqrt", &obj.as_decimal()?)
+}
+
+/// The angle of the point `(x, y)`, in `(-pi, pi]`.
+fn argument(x: &BigDecimal, y: &BigDecimal) -> Option<BigDecimal> {
+    let pi = numeric::pi().as_decimal()?;
+
+    if x.is_zero() {
+        return Some(match y.is_negative() {
+            true => -pi.half(),
+            false if y.is_zero() => BigDecimal::zero(),
+            false => pi.half(),
+        });
+    }
+
+    let slope = div(&decimal(y.to_owned()), &decimal(x.to_owned()))?;
+    let atan = numeric::apply("atan", &slope.as_decimal()?)?.as_decimal()?;
+
+    Some(match (x.is_negative(), y.is_negative()) {
+        (false, _) => atan,
+        (true, false) => atan + pi,
+        (true, true) => atan - pi,
+    })
+}
+
+impl Complex {
+    /// Makes a complex number from two real numbers, which become decimals
+    /// when one of them is.
+    pub fn new(re: Object, im: Object) -> Self {
+        let (re, im) = match (&re, &im) {
+            (Object::Decimal(_), _) | (_, Object::Decimal(_)) => (
+                decimal(re.as_decimal().unwrap()),
+                decimal(im.as_decimal().unwrap()),
+            ),
+            _ => (
+                Object::from_rational(re.as_rational().unwrap()),
+                Object::from_rational(im.as_rational().unwrap()),
+            ),
+        };
+
+        Self {
+            re: Box::new(re),
+            im: Box::new(im),
+        }
+    }
+
+    /// The complex number equal to `obj`, when it is a number.
+    pub fn from_object(obj: &Object) -> Option<Self> {
+        match obj {
+            Object::Complex(z) => Some(z.to_owned()),
+            obj if obj.is_numeric() => Some(Self::new(obj.to_owned(), Object::int(0))),
+            _ => None,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.re.is_zero() && self.im.is_zero()
+    }
+
+    /// The number this is, which is real when the imaginary part is zero.
+    pub fn into_object(self) -> Object {
+        match self.im.is_zero() {
+            true => *self.re,
+            false => Object::Complex(self),
+        }
+    }
+
+    fn to_decimal(&self) -> Self {
+        Self::new(
+            decimal(self.re.as_decimal().unwrap()),
+            decimal(self.im.as_decimal().unwrap()),
+        )
+    }
+
+    fn neg(&self) -> Self {
+        Self::new(self.re.inverse().unwrap(), self.im.inverse().unwrap())
+    }
+
+    fn conjugate(&self) -> Self {
+        Self::new(*self.re.to_owned(), self.im.inverse().unwrap())
+    }
+
+    /// The square of the absolute value.
+    fn norm(&self) -> Object {
+        sum(&mul(&self.re, &self.re), &mul(&self.im, &self.im))
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        Self::new(sum(&self.re, &other.re), sum(&self.im, &other.im))
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        Self::new(sub(&self.re, &other.re), sub(&self.im, &other.im))
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        Self::new(
+            sub(&mul(&self.re, &other.re), &mul(&self.im, &other.im)),
+            sum(&mul(&self.re, &other.im), &mul(&self.im, &other.re)),
+        )
+    }
+
+    fn div(&self, other: &Self) -> Option<Self> {
+        let norm = other.norm();
+        let numer = self.mul(&other.conjugate());
+
+        Some(Self::new(div(&numer.re, &norm)?, div(&numer.im, &norm)?))
+    }
+
+    fn integer_power(&self, exp: &BigInt) -> Option<Self> {
+        let mut base = self.to_owned();
+        let mut n = exp.magnitude().to_owned();
+        let mut res = Self::new(Object::int(1), Object::int(0));
+
+        while !n.is_zero() {
+            if n.is_odd() {
+                res = res.mul(&base);
+            }
+            base = base.mul(&base);
+            n >>= 1;
+        }
+
+        match exp.is_negative() {
+            true => Self::new(Object::int(1), Object::int(0)).div(&res),
+            false => Some(res),
+        }
+    }
+
+    /// The principal value of `self**exp`. Square roots are exact when they
+    /// can be, and any other power is computed as `exp(exp*ln(self))`.
+    fn power(&self, exp: &Self) -> Option<Self> {
+        if exp.im.is_zero() && exp.re.as_rational() == Some(BigRational::new(1.into(), 2.into())) {
+            return self.sqrt();
+        }
+
+        if self.is_zero() {
+            return match exp.re.is_negative() || exp.re.is_zero() {
+                true => None,
+                false => Some(self.to_owned()),
+            };
+        }
+
+        self.ln()?.mul(exp).exp()
+    }
+
+    /// The absolute value and the argument, as decimals.
+    fn polar(&self) -> Option<(BigDecimal, BigDecimal)> {
+        let (re, im) = (self.re.as_decimal()?, self.im.as_decimal()?);
+        let abs = numeric::apply("sqrt", &(&re * &re + &im * &im))?.as_decimal()?;
+
+        Some((abs, argument(&re, &im)?))
+    }
+
+    fn from_polar(abs: &BigDecimal, arg: &BigDecimal) -> Option<Self> {
+        let cos = numeric::apply("cos", arg)?.as_decimal()?;
+        let sin = numeric::apply("sin", arg)?.as_decimal()?;
+
+        Some(Self::new(decimal(abs * cos), decimal(abs * sin)))
+    }
+
+    /// The square root with a nonnegative real part. It is exact when the
+    /// parts of the result are rational.
+    fn sqrt(&self) -> Option<Self> {
+        if self.is_zero() {
+            return Some(self.to_owned());
+        }
+
+        match *self.re {
+            Object::Decimal(_) => self.principal_root(decimal_sqrt),
+            _ => self
+                .principal_root(exact_sqrt)
+                .or_else(|| self.to_decimal().principal_root(decimal_sqrt)),
+        }
+    }
+
+    /// Finds the square root from the one of the absolute value, taking
+    /// square roots of nonnegative reals with `root`. The part with the
+    /// larger magnitude is found first, so that nothing cancels out.
+    fn principal_root(&self, root: fn(&Object) -> Option<Object>) -> Option<Self> {
+        let two = Object::int(2);
+        let abs = root(&self.norm())?;
+
+        if !self.re.is_negative() {
+            let re = root(&div(&sum(&abs, &self.re), &two)?)?;
+            let im = div(&self.im, &mul(&two, &re))?;
+
+            Some(Self::new(re, im))
+        } else {
+            let im = root(&div(&sub(&abs, &self.re), &two)?)?;
+            let im = match self.im.is_negative() {
+                true => im.inverse()?,
+                false => im,
+            };
+            let re = div(&self.im, &mul(&two, &im))?;
+
+            Some(Self::new(re, im))
+        }
+    }
+
+    fn exp(&self) -> Option<Self> {
+        let abs = numeric::apply("exp", &self.re.as_decimal()?)?.as_decimal()?;
+        Self::from_polar(&abs, &self.im.as_decimal()?)
+    }
+
+    fn ln(&self) -> Option<Self> {
+        let (abs, arg) = self.polar()?;
+        Some(Self::new(numeric::apply("ln", &abs)?, decimal(arg)))
+    }
+
+    /// The sine and the cosine, from `exp(i*z)` and `exp(-i*z)`.
+    fn sin_cos(&self) -> Option<(Self, Self)> {
+        let rotated = Self::new(self.im.inverse()?, *self.re.to_owned());
+        let (pos, neg) = (rotated.exp()?, rotated.neg().exp()?);
+
+        let half = |z: Self| {
+            Some(Self::new(
+                div(&z.re, &Object::int(2))?,
+                div(&z.im, &Object::int(2))?,
+            ))
+        };
+        let sin = half(
+            neg.sub(&pos)
+                .mul(&Self::new(Object::int(0), Object::int(1))),
+        )?;
+        let cos = half(pos.add(&neg))?;
+
+        Some((sin, cos))
+    }
+}
+
+/// Applies an elementary function to a complex number, when it is defined
+/// for one.
+pub fn apply(name: &str, z: &Complex) -> Option<Object> {
+    let res = match name {
+        "sqrt" => z.sqrt()?,
+        "exp" => z.exp()?,
+        "ln" => z.ln()?,
+        "sin" => z.sin_cos()?.0,
+        "cos" => z.sin_cos()?.1,
+        "tan" => {
+            let (sin, cos) = z.sin_cos()?;
+            sin.div(&cos)?
+        }
+        _ => return None,
+    };
+
+    Some(res.into_object())
+}
+
+/// Turns a number into a complex one, so it can operate with another one.
+pub fn coerced(obj: Object) -> Option<Object> {
+    Complex::from_object(&obj).map(Object::Complex)
+}
+
+impl InfixOperable for Complex {
+    fn sum(&self, other: &Object) -> Option<Object> {
+        match other {
+            obj if obj.is_symbolic() => symbolic::sum(Object::Complex(self.to_owned()), obj),
+            _ => Some(self.add(&Complex::from_object(other)?).into_object()),
+        }
+    }
+
+    fn substraction(&self, other: &Object) -> Option<Object> {
+        match other {
+            obj if obj.is_symbolic() => {
+                symbolic::substraction(Object::Complex(self.to_owned()), obj)
+            }
+            _ => Some(self.sub(&Complex::from_object(other)?).into_object()),
+        }
+    }
+
+    fn product(&self, other: &Object) -> Option<Object> {
+        match other {
+            obj if obj.is_symbolic() => symbolic::product(Object::Complex(self.to_owned()), obj),
+            _ => Some(self.mul(&Complex::from_object(other)?).into_object()),
+        }
+    }
+
+    fn over(&self, other: &Object) -> Option<Object> {
+        match other {
+            obj if obj.is_symbolic() => symbolic::over(Object::Complex(self.to_owned()), obj),
+            _ => self
+                .div(&Complex::from_object(other)?)
+                .map(Complex::into_object),
+        }
+    }
+
+    fn pow(&self, other: &Object) -> Option<Object> {
+        let res = match other {
+            obj if obj.is_symbolic() => {
+                return symbolic::pow(Object::Complex(self.to_owned()), obj)
+            }
+            Object::Integer(_) => self.integer_power(&other.as_rational()?.to_integer()),
+            other => self.power(&Complex::from_object(other)?),
+        };
+
+        res.map(Complex::into_object)
+    }
+}
+
+impl PrefixOperable for Complex {
+    fn inverse(&self) -> Option<Object> {
+        Some(Object::Complex(self.neg()))
+    }
+}
+
+impl fmt::Display for Complex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (sign, im) = match self.im.is_negative() {
+            true => ("-", self.im.inverse().unwrap()),
+            false => ("+", *self.im.to_owned()),
+        };
+
+        let im = match im {
+            Object::Fraction(Fraction { val }) => format!("{}i/{}", val.numer(), val.denom()),
+            im => format!("{im}i"),
+        };
+
+        match (self.re.is_zero(), sign) {
+            (true, "-") => write!(f, "-{im}"),
+            (true, _) => write!(f, "{im}"),
+            (false, _) => write!(f, "{} {sign} {im}", self.re),
+        }
+    }
+}
+
+fn complex_arg(func: &str, obj: &Object) -> Result<Complex, EvalError> {
+    Complex::from_object(obj).ok_or_else(|| EvalError::bad_argument(func, "Number", obj))
+}
+
+pub fn re(args: &[Object]) -> Result<Object, EvalError> {
+    Ok(*complex_arg("re", &args[0])?.re)
+}
+
+pub fn im(args: &[Object]) -> Result<Object, EvalError> {
+    Ok(*complex_arg("im", &args[0])?.im)
+}
+
+pub fn conj(args: &[Object]) -> Result<Object, EvalError> {
+    Ok(complex_arg("conj", &args[0])?.conjugate().into_object())
+}
+
+macro_rules! complex_function {
+    ($ident:ident) => {
+        pub fn $ident(args: &[Object]) -> Result<Object, EvalError> {
+            let z = complex_arg(stringify!($ident), &args[0])?;
+            apply(stringify!($ident), &z).ok_or_else(|| {
+                EvalError::bad_argument(stringify!($ident), "Number in its domain", &args[0])
+            })
+        }
+    };
+}
+
+// The functions of the `cmath` module, which give complex results for real
+// arguments too, like `sqrt(-4)`.
+complex_function!(sqrt);
+complex_function!(exp);
+complex_function!(ln);
+complex_function!(sin);
+complex_function!(cos);
+complex_function!(tan);
+
+/// The principal value of `z**w`.
+pub fn pow(args: &[Object]) -> Result<Object, EvalError> {
+    let z = complex_arg("pow", &args[0])?;
+    complex_arg("pow", &args[1])?;
+
+    z.pow(&args[1])
+        .ok_or_else(|| EvalError::bad_argument("pow", "Number in its domain", &args[0]))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU64;
+
+    use crate::{
+        object::{Fraction, Precision, Rounding},
+        testing::int,
+    };
+
+    use super::*;
+
+    fn complex(re: Object, im: Object) -> Object {
+        Object::Complex(Complex::new(re, im))
+    }
+
+    fn with_digits(digits: u64) {
+        Precision::new(NonZeroU64::new(digits).unwrap(), Rounding::HalfEven).install();
+    }
+
+    #[test]
+    fn arithmetic() {
+        let z = complex(int(1), int(2));
+        let w = complex(int(3), int(-1));
+
+        assert_eq!(z.sum(&w).unwrap().to_string(), "4 + 1i");
+        assert_eq!(z.product(&w).unwrap().to_string(), "5 + 5i");
+        assert_eq!(z.over(&w).unwrap().to_string(), "1 // 10 + 7i/10");
+        assert_eq!(int(1).substraction(&z).unwrap().to_string(), "-2i");
+        assert_eq!(w.over(&z).unwrap().to_string(), "1 // 5 - 7i/5");
+        assert_eq!(z.over(&complex(int(0), int(0))), None);
+    }
+
+    #[test]
+    fn integer_powers() {
+        let i = complex(int(0), int(1));
+
+        assert_eq!(i.pow(&int(2)).unwrap(), int(-1));
+        assert_eq!(i.pow(&int(-1)).unwrap().to_string(), "-1i");
+        assert_eq!(complex(int(1), int(1)).pow(&int(8)).unwrap(), int(16));
+        assert_eq!(i.pow(&i).unwrap().to_string().find('i'), None);
+    }
+
+    #[test]
+    fn square_roots() {
+        let half = Object::Fraction(Fraction::_new(1, 2));
+
+        assert_eq!(
+            complex(int(-4), int(0)).pow(&half).unwrap(),
+            complex(int(0), int(2))
+        );
+        assert_eq!(
+            apply("sqrt", &Complex::new(int(3), int(-4))).unwrap(),
+            complex(int(2), int(-1))
+        );
+
+        with_digits(10);
+        assert_eq!(
+            apply("sqrt", &Complex::new(int(-2), int(0)))
+                .unwrap()
+                .to_string(),
+            "1.414213562i"
+        );
+    }
+
+    #[test]
+    fn euler_identity() {
+        with_digits(20);
+        let pi = numeric::pi().as_decimal().unwrap();
+        let res = Complex::new(int(0), decimal(pi)).exp().unwrap();
+
+        assert_eq!(res.re.to_string(), "-1.0000000000000000000");
+        assert!(res.im.as_decimal().unwrap().abs() < "1E-19".parse().unwrap());
+    }
+}
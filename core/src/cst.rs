@@ -8,7 +8,9 @@ use crate::{
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Precedence {
     Lowest,
+    Where,
     Assignment,
+    Guard,
     In,
     Correspondence,
     Range,
@@ -18,6 +20,7 @@ pub enum Precedence {
     BitwiseXor,
     BitwiseAnd,
     Shift,
+    Modular,
     Addition,
     Multiplication,
     Exponentiation,
@@ -44,11 +47,13 @@ pub enum InfixOperator {
     Fraction,
     Greater,
     GreaterEqual,
+    Guard,
     In,
     LeftShift,
     Less,
     LessEqual,
     LogicAnd,
+    Modular,
     Or,
     Rem,
     NotEquality,
@@ -57,6 +62,7 @@ pub enum InfixOperator {
     RightShift,
     Substraction,
     Sum,
+    Where,
 }
 
 impl InfixOperator {
@@ -71,6 +77,7 @@ impl InfixOperator {
             TokenType::Lparen => Some(Self::Call),
             TokenType::Greater => Some(Self::Greater),
             TokenType::GreaterEqual => Some(Self::GreaterEqual),
+            TokenType::If => Some(Self::Guard),
             TokenType::LeftShift => Some(Self::LeftShift),
             TokenType::RightShift => Some(Self::RightShift),
             TokenType::Lbrack => Some(Self::Element),
@@ -78,6 +85,7 @@ impl InfixOperator {
             TokenType::LessEqual => Some(Self::LessEqual),
             TokenType::LogicAnd => Some(Self::LogicAnd),
             TokenType::LogicOr => Some(Self::Or),
+            TokenType::Mod => Some(Self::Modular),
             TokenType::In => Some(Self::In),
             TokenType::Percent => Some(Self::Rem),
             TokenType::Over => Some(Self::Division),
@@ -89,6 +97,7 @@ impl InfixOperator {
             TokenType::Arrow => Some(Self::Correspondence),
             TokenType::Equals => Some(Self::Equality),
             TokenType::NotEqual => Some(Self::NotEquality),
+            TokenType::Where => Some(Self::Where),
             _ => None,
         }
     }
@@ -109,11 +118,13 @@ impl InfixOperator {
             Self::Fraction => Precedence::Multiplication,
             Self::Greater => Precedence::Comparison,
             Self::GreaterEqual => Precedence::Comparison,
+            Self::Guard => Precedence::Guard,
             Self::In => Precedence::In,
             Self::LeftShift => Precedence::Shift,
             Self::Less => Precedence::Comparison,
             Self::LessEqual => Precedence::Comparison,
             Self::LogicAnd => Precedence::LogicAnd,
+            Self::Modular => Precedence::Modular,
             Self::Or => Precedence::LogicOr,
             Self::Rem => Precedence::Multiplication,
             Self::NotEquality => Precedence::Comparison,
@@ -122,6 +133,7 @@ impl InfixOperator {
             Self::RightShift => Precedence::Shift,
             Self::Substraction => Precedence::Addition,
             Self::Sum => Precedence::Addition,
+            Self::Where => Precedence::Where,
         }
     }
 }
@@ -173,6 +185,7 @@ impl Hash for CSTNode {
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum ComprehensionKind {
+    Dictionary,
     List,
     Set,
 }
@@ -194,11 +207,12 @@ pub enum CSTNodeKind {
         pairs: Vec<(CSTNode, CSTNode)>,
     },
     Char(char),
+    /// Every generator is like `pattern in iterable`, maybe with a guard.
     Comprehension {
         kind: ComprehensionKind,
         element: Box<CSTNode>,
-        variable: String,
-        iterator: Box<CSTNode>,
+        value: Option<Box<CSTNode>>,
+        generators: Vec<CSTNode>,
     },
     Dictionary {
         pairs: Vec<(CSTNode, CSTNode)>,
@@ -208,6 +222,7 @@ pub enum CSTNodeKind {
     ExtensionSet(Vec<CSTNode>),
     For(String, Box<CSTNode>, Vec<CSTNode>),
     If(Box<CSTNode>, Box<CSTNode>, Box<CSTNode>),
+    Imaginary(String),
     Import {
         name: Box<CSTNode>,
         alias: Option<Box<CSTNode>>,
@@ -219,6 +234,11 @@ pub enum CSTNodeKind {
     Infix(InfixOperator, Box<CSTNode>, Box<CSTNode>),
     Integer(String, Radix),
     Declaration(Box<CSTNode>, DeclarationKind),
+    /// `let a := ... in body`.
+    LetIn {
+        declaration: Box<CSTNode>,
+        body: Box<CSTNode>,
+    },
     Prefix(PrefixOperator, Box<CSTNode>),
     Cons(Box<CSTNode>, Box<CSTNode>),
     SetCons {
@@ -229,6 +249,11 @@ pub enum CSTNodeKind {
     Symbol(String),
     Tuple(Vec<CSTNode>),
     Wildcard,
+    /// `with precision digits do body`.
+    WithPrecision {
+        digits: Box<CSTNode>,
+        body: Box<CSTNode>,
+    },
 }
 
 pub fn infix(op: InfixOperator, lhs: CSTNode, rhs: CSTNode, position: Position) -> CSTNode {
@@ -252,19 +277,19 @@ pub fn extension_set(list: Vec<CSTNode>, position: Position) -> CSTNode {
 
 pub fn comprehension(
     element: CSTNode,
-    variable: String,
-    iterator: CSTNode,
+    value: Option<CSTNode>,
+    generators: Vec<CSTNode>,
     kind: ComprehensionKind,
     position: Position,
 ) -> CSTNode {
     let element = Box::new(element);
-    let iterator = Box::new(iterator);
+    let value = value.map(Box::new);
 
     CSTNode::new(
         CSTNodeKind::Comprehension {
             element,
-            variable,
-            iterator,
+            value,
+            generators,
             kind,
         },
         position,
@@ -339,6 +364,10 @@ pub mod tests {
         CSTNode::new(CSTNodeKind::Integer(int.into(), radix), position)
     }
 
+    pub fn imaginary(int: &str, position: Position) -> CSTNode {
+        CSTNode::new(CSTNodeKind::Imaginary(int.into()), position)
+    }
+
     pub fn ad_infinitum(position: Position) -> CSTNode {
         CSTNode::new(CSTNodeKind::AdInfinitum, position)
     }
@@ -0,0 +1,113 @@
+use bigdecimal::{Signed, ToPrimitive, Zero};
+use num_bigint::{BigInt, Sign};
+
+use crate::{
+    exec::EvalError,
+    object::{Integer, List, MyString, Object},
+};
+
+fn integer(func: &str, obj: &Object) -> Result<BigInt, EvalError> {
+    match obj {
+        Object::Integer(_) => Ok(obj.as_rational().unwrap().to_integer()),
+        obj => Err(EvalError::bad_argument(func, "Integer", obj)),
+    }
+}
+
+/// The base in the second argument, which is 10 if there is none. Digits
+/// past 9 are written with letters, so it goes up to 36.
+fn base(func: &str, args: &[Object]) -> Result<u32, EvalError> {
+    match args.get(1) {
+        Some(obj) => integer(func, obj)?
+            .to_u32()
+            .filter(|base| (2..=36).contains(base))
+            .ok_or_else(|| EvalError::bad_argument(func, "base between 2 and 36", obj)),
+        None => Ok(10),
+    }
+}
+
+/// The digits of `n` in `base`, most significant first. Zero has a single
+/// digit.
+fn digits_of(n: &BigInt, base: u32) -> Vec<u8> {
+    n.magnitude().to_radix_be(base)
+}
+
+fn from_digits_of(digits: &[u8], base: u32) -> BigInt {
+    BigInt::from_radix_be(Sign::Plus, digits, base).unwrap_or_default()
+}
+
+pub fn to_base(args: &[Object]) -> Result<Object, EvalError> {
+    let n = integer("toBase", &args[0])?;
+    let base = base("toBase", args)?;
+
+    Ok(Object::String(MyString::from(n.to_str_radix(base))))
+}
+
+pub fn digits(args: &[Object]) -> Result<Object, EvalError> {
+    let n = integer("digits", &args[0])?;
+    if n.is_negative() {
+        return Err(EvalError::bad_argument(
+            "digits",
+            "nonnegative Integer",
+            &args[0],
+        ));
+    }
+
+    Ok(Object::List(List::from(
+        digits_of(&n, base("digits", args)?)
+            .into_iter()
+            .map(|digit| Object::Integer(Integer::from(BigInt::from(digit))))
+            .collect::<Vec<_>>(),
+    )))
+}
+
+/// The integer with a list of digits, most significant first.
+pub fn from_digits(args: &[Object]) -> Result<Object, EvalError> {
+    let base = base("fromDigits", args)?;
+
+    let digits = match &args[0] {
+        Object::List(list) => list
+            .list
+            .iter()
+            .map(|obj| {
+                integer("fromDigits", obj)?
+                    .to_u8()
+                    .filter(|&digit| u32::from(digit) < base)
+                    .ok_or_else(|| EvalError::bad_argument("fromDigits", "digit", obj))
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        obj => return Err(EvalError::bad_argument("fromDigits", "List", obj)),
+    };
+
+    let res = match digits.is_empty() {
+        true => BigInt::zero(),
+        false => from_digits_of(&digits, base),
+    };
+
+    Ok(Object::Integer(Integer::from(res)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::int;
+
+    use super::*;
+
+    #[test]
+    fn base_conversion() {
+        assert_eq!(to_base(&[int(255), int(16)]).unwrap().to_string(), "ff");
+        assert_eq!(to_base(&[int(-5), int(2)]).unwrap().to_string(), "-101");
+        assert_eq!(to_base(&[int(35), int(36)]).unwrap().to_string(), "z");
+        assert!(to_base(&[int(35), int(37)]).is_err());
+    }
+
+    #[test]
+    fn digit_lists() {
+        assert_eq!(digits(&[int(1203)]).unwrap().to_string(), "[1, 2, 0, 3]");
+        assert_eq!(digits(&[int(0), int(2)]).unwrap().to_string(), "[0]");
+        assert_eq!(digits(&[int(6), int(2)]).unwrap().to_string(), "[1, 1, 0]");
+
+        let list = Object::List(List::from(vec![int(1), int(1), int(0)]));
+        assert_eq!(from_digits(&[list.clone(), int(2)]).unwrap(), int(6));
+        assert!(from_digits(&[list, int(1)]).is_err());
+    }
+}
@@ -0,0 +1,288 @@
+use bigdecimal::{One, ToPrimitive};
+use num_rational::BigRational;
+
+use crate::{
+    complex,
+    exec::EvalError,
+    interval, numeric,
+    object::{Fraction, Object, Symbol},
+    simplify::simplify,
+    symbolic::{self, Expression},
+};
+
+pub fn pi() -> Object {
+    Object::Symbol(Symbol::new("pi".into(), "Pi".into()))
+}
+
+/// The rational `q` when `obj` is `q*pi`.
+fn pi_multiple(obj: &Object) -> Option<BigRational> {
+    if !obj.is_symbolic() {
+        return None;
+    }
+
+    match simplify(obj) {
+        obj if obj == pi() => Some(BigRational::one()),
+        Object::Expression(Expression::Product(factors)) if factors.len() == 2 => {
+            match (&factors[0], &factors[1]) {
+                (coefficient, obj) if *obj == pi() => coefficient.as_rational(),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The sine of `q*pi` when it is one of the known values.
+fn exact_sine(q: &BigRational) -> Option<Object> {
+    let two = BigRational::from_integer(2.into());
+    let q = q - &two * (q / &two).floor();
+
+    if q >= BigRational::one() {
+        let res = exact_sine(&(q - BigRational::one()))?;
+        return Some(simplify(&symbolic::negation(res)));
+    }
+
+    let complement = BigRational::one() - &q;
+    let q = q.min(complement);
+    let root = |val: i32| apply("sqrt", &Object::int(val));
+
+    // In twelfths of pi, from 0 to pi/2
+    let twelfths = q * BigRational::from_integer(12.into());
+    if !twelfths.is_integer() {
+        return None;
+    }
+
+    let res = match twelfths.to_integer().to_u8()? {
+        0 => Object::int(0),
+        2 => Object::Fraction(Fraction::_new(1, 2)),
+        3 => root(2).over(&Object::int(2))?,
+        4 => root(3).over(&Object::int(2))?,
+        6 => Object::int(1),
+        _ => return None,
+    };
+
+    Some(simplify(&res))
+}
+
+/// The exact value of a trigonometric function at a rational multiple of
+/// `pi`, when it is known.
+fn exact_trigonometric(name: &str, arg: &Object) -> Option<Object> {
+    let q = pi_multiple(arg)?;
+    let half = BigRational::new(1.into(), 2.into());
+
+    match name {
+        "sin" => exact_sine(&q),
+        "cos" => exact_sine(&(q + half)),
+        "tan" => {
+            let cos = exact_sine(&(&q + half))?;
+            match cos.is_zero() {
+                true => None,
+                false => Some(simplify(&exact_sine(&q)?.over(&cos)?)),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The arctangent of `arg` when it is one of the known values, which are
+/// rational multiples of `pi`.
+fn exact_arctangent(arg: &Object) -> Option<Object> {
+    let root = apply("sqrt", &Object::int(3));
+    let known = [
+        (Object::int(1), 4),
+        (root.to_owned(), 3),
+        (root.over(&Object::int(3))?, 6),
+        (Object::int(1).over(&root)?, 6),
+    ];
+
+    for (val, denom) in known {
+        for (val, numer) in [(val.to_owned(), 1), (symbolic::negation(val), -1)] {
+            if symbolic::equation(arg.to_owned(), val) == Some(true.into()) {
+                return Object::Fraction(Fraction::_new(numer, denom)).product(&pi());
+            }
+        }
+    }
+
+    None
+}
+
+fn bad_operand(func: &str, arg: &Object) -> EvalError {
+    EvalError::bad_argument(func, "Number", arg)
+}
+
+fn out_of_domain(func: &str, arg: &Object) -> EvalError {
+    EvalError::OutOfDomain {
+        func: func.into(),
+        arg: arg.to_string(),
+    }
+}
+
+fn check_operand(func: &str, arg: &Object) -> Result<(), EvalError> {
+    if !arg.is_numeric() && !arg.is_symbolic() {
+        return Err(bad_operand(func, arg));
+    }
+
+    match func {
+        "ln" if arg.is_numeric() && (arg.is_zero() || arg.is_negative()) => {
+            Err(out_of_domain(func, arg))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Applies an elementary function, evaluating it only when the result is exact.
+pub fn apply(name: &str, arg: &Object) -> Object {
+    if let Object::Decimal(_) = arg {
+        if let Some(res) = numeric::apply(name, &arg.as_decimal().unwrap()) {
+            return res;
+        }
+    }
+
+    if let Some(res) = exact_trigonometric(name, arg) {
+        return res;
+    }
+
+    if name == "atan" {
+        if let Some(res) = exact_arctangent(arg) {
+            return res;
+        }
+    }
+
+    match name {
+        "sin" | "tan" | "atan" if arg.is_zero() => Object::int(0),
+        "cos" | "exp" if arg.is_zero() => Object::int(1),
+        "ln" if arg.is_one() => Object::int(0),
+        "ln" => match arg {
+            Object::Expression(Expression::Call(name, args)) if name == "exp" => args[0].to_owned(),
+            _ => symbolic::call(name, vec![arg.to_owned()]),
+        },
+        "sqrt" => simplify(&Object::Expression(Expression::Power(
+            Box::new(arg.to_owned()),
+            Box::new(Object::Fraction(Fraction::_new(1, 2))),
+        ))),
+        _ => symbolic::call(name, vec![arg.to_owned()]),
+    }
+}
+
+macro_rules! elementary_function {
+    ($ident:ident) => {
+        pub fn $ident(args: &[Object]) -> Result<Object, EvalError> {
+            match &args[0] {
+                Object::Complex(z) => {
+                    return complex::apply(stringify!($ident), z)
+                        .ok_or_else(|| out_of_domain(stringify!($ident), &args[0]))
+                }
+                Object::Interval(x) => {
+                    return interval::apply(stringify!($ident), x)
+                        .map(Object::Interval)
+                        .ok_or_else(|| out_of_domain(stringify!($ident), &args[0]))
+                }
+                _ => (),
+            }
+
+            check_operand(stringify!($ident), &args[0])?;
+            Ok(apply(stringify!($ident), &args[0]))
+        }
+    };
+}
+
+elementary_function!(sin);
+elementary_function!(cos);
+elementary_function!(tan);
+elementary_function!(atan);
+elementary_function!(exp);
+elementary_function!(ln);
+elementary_function!(sqrt);
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        object::{Decimal, MyString},
+        testing::{int, x},
+    };
+
+    use super::*;
+
+    #[test]
+    fn exact_values() {
+        assert_eq!(sin(&[int(0)]), Ok(int(0)));
+        assert_eq!(exp(&[int(0)]), Ok(int(1)));
+        assert_eq!(sqrt(&[int(9)]), Ok(int(3)));
+    }
+
+    #[test]
+    fn multiples_of_pi() {
+        let third = pi().over(&int(3)).unwrap();
+
+        assert_eq!(sin(&[pi()]), Ok(int(0)));
+        assert_eq!(cos(&[third]), Ok(Object::Fraction(Fraction::_new(1, 2))));
+        assert_eq!(
+            sin(&[int(5).product(&pi()).unwrap().over(&int(4)).unwrap()])
+                .unwrap()
+                .to_string(),
+            "-sqrt(2)/2"
+        );
+    }
+
+    #[test]
+    fn exact_arctangent() {
+        let sixth = pi().over(&int(6)).unwrap();
+
+        assert_eq!(atan(&[int(1)]).unwrap().to_string(), "pi/4");
+        assert_eq!(
+            atan(&[sqrt(&[int(3)]).unwrap().over(&int(3)).unwrap()]),
+            Ok(sixth)
+        );
+    }
+
+    #[test]
+    fn logarithm_domain() {
+        assert_eq!(
+            ln(&[Object::Decimal(Decimal::new("0", "0"))]),
+            Err(EvalError::OutOfDomain {
+                func: "ln".into(),
+                arg: "0.0".into(),
+            })
+        );
+        assert!(ln(&[int(-2)]).is_err());
+    }
+
+    #[test]
+    fn interval_domain() {
+        let x = interval::coerced(int(-1)).unwrap();
+
+        assert_eq!(
+            ln(&[x]),
+            Err(EvalError::OutOfDomain {
+                func: "ln".into(),
+                arg: "interval(-1, -1)".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn decimal_argument() {
+        let res = exp(&[Object::Decimal(Decimal::new("0", "5"))]).unwrap();
+
+        assert!(matches!(res, Object::Decimal(_)));
+        assert!(res.to_string().starts_with("1.6487212707001281468"));
+    }
+
+    #[test]
+    fn unevaluated_call() {
+        assert_eq!(cos(&[x()]).unwrap().to_string(), "cos(x)");
+        assert_eq!(sqrt(&[int(2)]).unwrap().to_string(), "sqrt(2)");
+    }
+
+    #[test]
+    fn bad_argument() {
+        assert_eq!(
+            ln(&[Object::String(MyString::from("e"))]),
+            Err(EvalError::BadArgument {
+                func: "ln".into(),
+                expected: "Number".into(),
+                actual: "String".into(),
+            })
+        );
+    }
+}
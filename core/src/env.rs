@@ -1,6 +1,11 @@
 use std::{collections::BTreeMap, path::PathBuf};
 
-use crate::object::Object;
+use crate::{
+    object::{
+        ActivePrecision, ActiveRecursionLimit, Object, Precision, RecursionLimit, SharedPrecision,
+    },
+    random::{ActiveGenerator, SharedGenerator},
+};
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum EnvResponse<'a> {
@@ -21,7 +26,7 @@ pub struct Scope {
 }
 
 impl Scope {
-    fn get(&mut self, name: &str) -> EnvResponse {
+    fn get(&mut self, name: &str) -> EnvResponse<'_> {
         match self.dict.get_mut(name) {
             Some((ValueKind::Inmutable, value)) => EnvResponse::Inmutable(value),
             Some((ValueKind::Mutable, value)) => EnvResponse::Mutable(value),
@@ -43,12 +48,63 @@ impl Scope {
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
 pub struct ExecContext {
     pub reference_path: PathBuf,
+    /// The precision `Decimal` operations use while this context is
+    /// active. Scripts can change it with `setPrecision`.
+    pub precision: SharedPrecision,
+    /// The random numbers drawn while this context is active. Scripts can
+    /// start them again with `seed`.
+    pub generator: SharedGenerator,
+    /// How many function calls can be nested, apart from the ones in tail
+    /// position.
+    pub recursion_limit: RecursionLimit,
 }
 
 impl ExecContext {
     pub fn new(reference_path: PathBuf) -> Self {
-        Self { reference_path }
+        Self {
+            reference_path,
+            precision: SharedPrecision::default(),
+            generator: SharedGenerator::default(),
+            recursion_limit: RecursionLimit::default(),
+        }
+    }
+
+    pub fn with_precision(self, precision: Precision) -> Self {
+        Self {
+            precision: SharedPrecision::new(precision),
+            ..self
+        }
+    }
+
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self {
+            generator: SharedGenerator::seeded(seed),
+            ..self
+        }
     }
+
+    pub fn with_recursion_limit(self, limit: usize) -> Self {
+        Self {
+            recursion_limit: RecursionLimit(limit),
+            ..self
+        }
+    }
+
+    /// Makes the settings of this context the ones in use until the
+    /// returned guard is dropped.
+    pub fn activate(&self) -> ActiveContext {
+        ActiveContext {
+            _precision: self.precision.activate(),
+            _generator: self.generator.activate(),
+            _recursion_limit: self.recursion_limit.activate(),
+        }
+    }
+}
+
+pub struct ActiveContext {
+    _precision: ActivePrecision,
+    _generator: ActiveGenerator,
+    _recursion_limit: ActiveRecursionLimit,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -121,6 +121,7 @@ fn found_a(tok: &TokenType) -> String {
         TokenType::GreaterEqual => "an operator: `>=`".into(),
         TokenType::Ident(ident) => format!("a symbol: `{ident}`"),
         TokenType::If => "the `if` keyword".into(),
+        TokenType::Imaginary(val) => format!("an imaginary number: `{val}i`"),
         TokenType::In => "the `in` keyword".into(),
         TokenType::Integer(val, _) => format!("an integer: `{val}`"),
         TokenType::Lbrace => "a left brace: `{`".into(),
@@ -133,6 +134,7 @@ fn found_a(tok: &TokenType) -> String {
         TokenType::LogicOr => "a double pipe symbol: `||`".into(),
         TokenType::Lparen => "a left parenthesis: `(`".into(),
         TokenType::Memoize => "the `memoize` keyword".into(),
+        TokenType::Mod => "the `mod` keyword".into(),
         TokenType::Minus => "a minus operator: `-`".into(),
         TokenType::Newline => "a newline character".into(),
         TokenType::Percent => "a remainder operator: `%`".into(),
@@ -157,6 +159,8 @@ fn found_a(tok: &TokenType) -> String {
         TokenType::Dedent => "the end of an indentation block".into(),
         TokenType::Indent => "the beggining of an indentation block".into(),
         TokenType::Var => "the `var` keyword".into(),
+        TokenType::Where => "the `where` keyword".into(),
+        TokenType::With => "the `with` keyword".into(),
     }
 }
 
@@ -205,6 +209,11 @@ fn weeder_error_msg(err: &WeederError) -> String {
             The dot is allowed for decimal numbers and calls only.
             At the right of the dot there should be a function call or a decimal number",
         ),
+        WeederError::BadGenerator => unindent(
+            "
+            Comprehensions take values from generators like `pattern in iterable`.
+            Replace this with a generator",
+        ),
         WeederError::BadImportOrigin => {
             "The module from where you want to import can only be represented with a name".into()
         }
@@ -236,32 +245,73 @@ fn weeder_error_msg(err: &WeederError) -> String {
         WeederError::MemoizedNonFunctionDeclaration => {
             "You can only memoize functions. Remove the `memoize` from the declaration".into()
         }
+        WeederError::MisplacedWhere => unindent(
+            "
+            A `where` can only follow the value of a declaration.
+            Use `let ... in ...` to bind values inside an expression",
+        ),
+        WeederError::MisplacedGuard => unindent(
+            "
+            Guards can only follow the parameters of a function declaration or a pattern in a `case`.
+            Use `if ... then ... else ...` instead",
+        ),
     }
 }
 
 fn exec_error_msg(err: &EvalError) -> String {
     match err {
+        EvalError::BadArgument {
+            func,
+            expected,
+            actual,
+        } => bad_argument(func, expected, actual),
         EvalError::BadFraction {
             numer_kind,
             denom_kind,
         } => bad_fraction(numer_kind, denom_kind),
         EvalError::BadMatch => "The pattern did not match its assigned value".into(),
+        EvalError::BadModular {
+            val_kind,
+            modulus_kind,
+        } => bad_modular(val_kind, modulus_kind),
+        EvalError::BadOpenRange { start_kind } => {
+            format!("An open range starts with an `Integer`, not with `{start_kind}`")
+        }
         EvalError::DenominatorZero => "Division by zero".into(),
+        EvalError::EverySolution(var) => {
+            format!("The equation holds for every value of `{var}`, so there is no set of solutions to give")
+        }
         EvalError::FailedAssertion(msg) => failed_assertion(msg),
+        EvalError::FactorZero => "`0` can't be factored, since every number divides it".into(),
         EvalError::MissingFunctionArguments { expected, actual } => {
             missing_func_arguments(*expected, *actual)
         }
+        EvalError::MultivariateFactor(poly) => format!(
+            "`{poly}` has a factor in several variables, and `factor` only splits polynomials into factors of one variable"
+        ),
         EvalError::NonCallableObject(kind) => non_callable_object(kind),
+        EvalError::NonInvertible(val) => format!("`{val}` has no multiplicative inverse"),
+        EvalError::OutOfDomain { func, arg } => format!("`{func}` is not defined at `{arg}`"),
         EvalError::NonIterableObject(kind) => non_iterable_object(kind),
+        EvalError::NonPositiveModulus => "The modulus must be a positive integer".into(),
         EvalError::NonPrependableObject(kind) => non_prependable_object(kind),
         EvalError::NonExistentPrefixOperation { op, rhs } => non_existent_prefix(op, rhs),
         EvalError::NonExistentInfixOperation { op, lhs, rhs } => non_existent_infix(op, lhs, rhs),
         EvalError::IndexingNonContainer { kind } => indexing_non_container(kind),
         EvalError::ListIndexOutOfBounds => "List index out of bounds".into(),
+        EvalError::MisplacedAdInfinitum => {
+            "`..` can only end an open range, like `1..`, or a list pattern, like `[x, ..]`".into()
+        }
         EvalError::InvalidIndex { kind } => invalid_index(kind),
         EvalError::NonExistentKey { key } => non_existent_key(key),
+        EvalError::RecursionDepthExceeded => {
+            "Recursion depth exceeded; only calls in tail position can nest without limit".into()
+        }
+        EvalError::UnknownCondition => "The condition is `unknown`, so it can't be decided".into(),
         EvalError::UnknownValue(value) => unknown_value(value),
+        EvalError::InfiniteStream => "A stream has no end, so it can't be collected".into(),
         EvalError::InmutableAssign(value) => inmutable_assign(value),
+        EvalError::Inner(err) => error_msg(err).0,
         EvalError::UnmatchedCall => {
             "None of the patterns in the function matched the arguments of this call".into()
         }
@@ -296,10 +346,18 @@ fn failed_assertion(msg: &Option<String>) -> String {
     }
 }
 
+fn bad_argument(func: &str, expected: &str, actual: &str) -> String {
+    format!("`{func}` expected `{expected}` as an argument, but got `{actual}`")
+}
+
 fn bad_fraction(numer_kind: &str, denom_kind: &str) -> String {
     format!("Cannot create a fraction from `{numer_kind}` and `{denom_kind}`")
 }
 
+fn bad_modular(val_kind: &str, modulus_kind: &str) -> String {
+    format!("Cannot create a modular integer from `{val_kind}` and `{modulus_kind}`")
+}
+
 fn non_prependable_object(kind: &str) -> String {
     format!("Cannot prepend elements to `{kind}`")
 }
@@ -365,6 +423,7 @@ fn unexpected_token(expected_msgs: Vec<String>, actual_msg: String) -> String {
 fn import_error_msg(err: &ImportError) -> String {
     match err {
         ImportError::SymbolNotFound { module, symbol } => symbol_not_found(module, symbol),
+        ImportError::ModuleNotFound(module) => module_not_found(module),
     }
 }
 
@@ -372,6 +431,10 @@ fn symbol_not_found(module: &str, symbol: &str) -> String {
     format!("`{symbol}` was not found in the `{module}` module")
 }
 
+fn module_not_found(module: &str) -> String {
+    format!("The `{module}` module was not found")
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct ErrorMessage(pub String, pub Position);
 
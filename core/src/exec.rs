@@ -1,42 +1,72 @@
 use std::cell::RefCell;
 use std::collections::{BTreeMap, BTreeSet};
+use std::iter::zip;
 use std::rc::Rc;
 
+use crate::complex::Complex;
 use crate::error::{Error, Position};
 use crate::lexer::Radix;
 use crate::matcher::{match_, Match};
 use crate::object::{
     self, AnonFunction, Decimal, Dictionary, FailedAssertion, Fraction, Function,
-    FunctionPatternKind, Kind, List, PatternFunction, Range,
+    FunctionPatternKind, Kind, List, Modular, PatternFunction, Precision, Range,
 };
 
 use crate::ast::{ASTNode, ASTNodeKind, Declaration, InfixOperator};
+use crate::builtin::precision_digits;
 use crate::cst::{ComprehensionKind, PrefixOperator};
 use crate::env::{EnvResponse, Environment, ValueKind};
 use crate::object::{Bool, Char, Integer, MyString, Object, Set, Symbol, Tuple};
 use crate::run;
+use crate::stream::{self, Elements, Stream};
+use crate::symbolic;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum EvalError {
+    BadArgument {
+        func: String,
+        expected: String,
+        actual: String,
+    },
     BadFraction {
         numer_kind: String,
         denom_kind: String,
     },
     BadMatch,
+    BadModular {
+        val_kind: String,
+        modulus_kind: String,
+    },
+    BadOpenRange {
+        start_kind: String,
+    },
     DenominatorZero,
+    /// An equation that holds for every value of the named symbol.
+    EverySolution(String),
     FailedAssertion(Option<String>),
+    /// `factor` called with zero, which has no factorization.
+    FactorZero,
     IndexingNonContainer {
         kind: String,
     },
+    InfiniteStream,
     InmutableAssign(String),
+    /// An error in a function that an extern function called, which keeps
+    /// its own position.
+    Inner(Box<Error>),
     InvalidIndex {
         kind: String,
     },
     ListIndexOutOfBounds,
+    /// A `..` that doesn't end an open range or a list pattern.
+    MisplacedAdInfinitum,
     MissingFunctionArguments {
         expected: usize,
         actual: usize,
     },
+    /// A polynomial with a factor in several variables, which `factor`
+    /// can't split.
+    MultivariateFactor(String),
     NonCallableObject(String),
     NonExistentKey {
         key: String,
@@ -50,20 +80,50 @@ pub enum EvalError {
         lhs: String,
         rhs: String,
     },
+    NonInvertible(String),
+    /// A function called with a number it isn't defined for.
+    OutOfDomain {
+        func: String,
+        arg: String,
+    },
     NonIterableObject(String),
+    NonPositiveModulus,
     NonPrependableObject(String),
+    RecursionDepthExceeded,
+    UnknownCondition,
     UnknownValue(String),
     UnmatchedCall,
     UnmatchedExpression,
 }
 
-pub fn truthy(val: &Object) -> bool {
+impl EvalError {
+    /// An argument of `func` that isn't of the kind it expected.
+    pub fn bad_argument(func: &str, expected: &str, obj: &Object) -> Self {
+        EvalError::BadArgument {
+            func: func.into(),
+            expected: expected.into(),
+            actual: obj.kind(),
+        }
+    }
+}
+
+/// Whether a condition holds. An unknown truth value, or an equation that
+/// couldn't be decided, can't decide anything.
+pub fn truthy(val: &Object) -> Result<bool, EvalError> {
     match val {
-        Object::Boolean(boolean) => boolean.value(),
-        _ => false,
+        Object::Boolean(boolean) => boolean.value().ok_or(EvalError::UnknownCondition),
+        Object::Expression(
+            symbolic::Expression::Equation(..) | symbolic::Expression::Inequation(..),
+        ) => Err(EvalError::UnknownCondition),
+        _ => Ok(false),
     }
 }
 
+/// Whether the condition in `node`, which evaluated to `val`, holds.
+pub fn holds(val: &Object, node: &ASTNode) -> Result<bool, Error> {
+    truthy(val).map_err(|err| Error::new(err.into(), node.position))
+}
+
 pub fn list(l: &[ASTNode], env: &mut Environment) -> Result<Vec<Object>, Error> {
     l.iter().map(|node| exec(node, env)).collect()
 }
@@ -80,6 +140,11 @@ pub fn exec(node: &ASTNode, env: &mut Environment) -> Result<Object, Error> {
         ASTNodeKind::Set { list } => extension_set(list, env),
         ASTNodeKind::Integer { literal, radix } => integer(literal, *radix),
         ASTNodeKind::Function { params, result } => function(params, result, env),
+        ASTNodeKind::Infix {
+            op: InfixOperator::Range,
+            lhs,
+            rhs,
+        } if rhs.kind == ASTNodeKind::AdInfinitum => open_range(exec(lhs, env)?, node.position),
         ASTNodeKind::Infix { op, lhs, rhs } => infix(
             op.clone(),
             &exec(lhs, env)?,
@@ -93,17 +158,22 @@ pub fn exec(node: &ASTNode, env: &mut Environment) -> Result<Object, Error> {
             cond,
             positive,
             negative,
-        } => if_(exec(cond, env)?, positive, negative, env),
+        } => if_(cond, positive, negative, env, exec),
         ASTNodeKind::Prefix { op, val } => prefix(*op, exec(val, env)?, node.position),
         ASTNodeKind::String { str } => string(str),
         ASTNodeKind::Tuple { list: values } => tuple(values, env),
         ASTNodeKind::For { val, iter, proc } => for_(val, iter, proc, env),
         ASTNodeKind::List { list } => extension_list(list, env),
         ASTNodeKind::Wildcard => unimplemented!(),
-        ASTNodeKind::AdInfinitum => unimplemented!(),
+        ASTNodeKind::AdInfinitum => Err(Error::new(
+            EvalError::MisplacedAdInfinitum.into(),
+            node.position,
+        )),
         ASTNodeKind::Cons { first, tail } => cons(exec(first, env)?, tail, env),
         ASTNodeKind::Decimal { int, dec } => decimal(int, dec),
+        ASTNodeKind::Imaginary(val) => imaginary(val, env),
         ASTNodeKind::Fraction { numer, denom } => fraction(numer, denom, node.position, env),
+        ASTNodeKind::Modular { val, modulus } => modular(val, modulus, node.position, env),
         ASTNodeKind::Dictionary { pairs, complete: _ } => dictionary(pairs, env),
         ASTNodeKind::IndexNotation { container, index } => {
             let container_obj = exec(container, env)?;
@@ -125,13 +195,15 @@ pub fn exec(node: &ASTNode, env: &mut Environment) -> Result<Object, Error> {
             }
         }
         ASTNodeKind::SetCons { some, most } => set_cons(exec(some, env)?, most, env),
-        ASTNodeKind::ImportFrom { source, values } => import_from(source, values, env),
+        ASTNodeKind::ImportFrom { source, values } => {
+            import_from(source, values, env, node.position)
+        }
         ASTNodeKind::Comprehension {
             element,
-            variable,
-            iterator,
+            value,
+            generators,
             kind,
-        } => comprehension(element, variable, iterator, *kind, env),
+        } => comprehension(element, value.as_deref(), generators, *kind, env),
         ASTNodeKind::Pattern {
             exp: _,
             constraint: _,
@@ -139,7 +211,9 @@ pub fn exec(node: &ASTNode, env: &mut Environment) -> Result<Object, Error> {
         ASTNodeKind::Block(exprs) => block(exprs, env),
         ASTNodeKind::Assignment { left, right } => assignment(left, right, env),
         ASTNodeKind::Declaration(decl) => declaration(decl, env),
-        ASTNodeKind::Case { expr, pairs } => case(expr, pairs, env),
+        ASTNodeKind::Case { expr, arms } => case(expr, arms, env, exec),
+        ASTNodeKind::Local { declarations, body } => local(declarations, body, env, exec),
+        ASTNodeKind::WithPrecision { digits, body } => with_precision(digits, body, env),
     };
 
     if let Ok(Object::Error(FailedAssertion(msg))) = res {
@@ -152,25 +226,75 @@ pub fn exec(node: &ASTNode, env: &mut Environment) -> Result<Object, Error> {
     }
 }
 
-fn case(
+/// The result of evaluating an expression in tail position of a function
+/// clause, where a call to a pattern function is left for the caller to make.
+pub enum Tail {
+    Value(Object),
+    Call(TailCall),
+}
+
+/// A call in tail position to the function called `name`. The function is
+/// `None` when the clause can't see it, like a function declared after the
+/// one the clause belongs to, so the caller looks it up when making the call.
+pub struct TailCall {
+    pub name: String,
+    pub func: Option<PatternFunction>,
+    pub args: Vec<Object>,
+    pub name_pos: Position,
+    pub call_pos: Position,
+}
+
+/// Evaluates an expression in tail position of a function clause.
+pub fn exec_tail(node: &ASTNode, env: &mut Environment) -> Result<Tail, Error> {
+    match &node.kind {
+        ASTNodeKind::Call { called, args } => tail_call(called, args, env, node.position),
+        ASTNodeKind::If {
+            cond,
+            positive,
+            negative,
+        } => if_(cond, positive, negative, env, exec_tail),
+        ASTNodeKind::Case { expr, arms } => case(expr, arms, env, exec_tail),
+        ASTNodeKind::Local { declarations, body } => local(declarations, body, env, exec_tail),
+        ASTNodeKind::Block(exprs) => match exprs.split_last() {
+            Some((last, init)) => {
+                block(init, env)?;
+                exec_tail(last, env)
+            }
+            None => block(exprs, env).map(Tail::Value),
+        },
+        _ => exec(node, env).map(Tail::Value),
+    }
+}
+
+fn case<T>(
     expr: &ASTNode,
-    pairs: &[(ASTNode, ASTNode)],
+    arms: &[(ASTNode, Option<ASTNode>, ASTNode)],
     env: &mut Environment,
-) -> Result<Object, Error> {
+    eval: fn(&ASTNode, &mut Environment) -> Result<T, Error>,
+) -> Result<T, Error> {
     let expr_obj = exec(expr, env)?;
 
-    for (pattern, res) in pairs {
+    for (pattern, guard, res) in arms {
         if let Some(Match(map)) = match_(pattern, &expr_obj) {
             env.push_scope();
             for (key, val) in map {
                 env.set_inmutable(&key, val);
             }
 
-            let res = exec(res, env);
+            let res = match guard
+                .as_ref()
+                .map(|guard| exec(guard, env).and_then(|cond| holds(&cond, guard)))
+            {
+                Some(Ok(false)) => None,
+                Some(Err(err)) => Some(Err(err)),
+                _ => Some(eval(res, env)),
+            };
 
             env.pop_scope();
 
-            return res;
+            if let Some(res) = res {
+                return res;
+            }
         }
     }
 
@@ -180,6 +304,42 @@ fn case(
     ))
 }
 
+fn local<T>(
+    declarations: &[ASTNode],
+    body: &ASTNode,
+    env: &mut Environment,
+    eval: fn(&ASTNode, &mut Environment) -> Result<T, Error>,
+) -> Result<T, Error> {
+    env.push_scope();
+
+    let res = declarations
+        .iter()
+        .try_for_each(|decl| exec(decl, env).map(|_| ()))
+        .and_then(|_| eval(body, env));
+
+    env.pop_scope();
+
+    res
+}
+
+/// Evaluates `body` with `digits` significant digits, and puts the precision
+/// back afterwards.
+fn with_precision(
+    digits: &ASTNode,
+    body: &ASTNode,
+    env: &mut Environment,
+) -> Result<Object, Error> {
+    let digits = precision_digits(&exec(digits, env)?, "with precision")
+        .map_err(|err| Error::new(err.into(), digits.position))?;
+
+    let current = Precision::current();
+    Precision::new(digits, current.rounding).install();
+    let res = exec(body, env);
+    current.install();
+
+    res
+}
+
 fn declaration(decl: &Declaration, env: &mut Environment) -> Result<Object, Error> {
     match decl {
         Declaration::Symbolic { name, constraint } => let_without_value(name, constraint, env),
@@ -190,13 +350,29 @@ fn declaration(decl: &Declaration, env: &mut Environment) -> Result<Object, Erro
         Declaration::Function {
             name,
             params,
+            guard,
             result,
-        } => let_function(name, params, result, FunctionPatternKind::NotMemoized, env),
+        } => let_function(
+            name,
+            params,
+            guard.as_deref(),
+            result,
+            FunctionPatternKind::NotMemoized,
+            env,
+        ),
         Declaration::MemoizedFunction {
             name,
             params,
+            guard,
             result,
-        } => let_function(name, params, result, FunctionPatternKind::Memoized, env),
+        } => let_function(
+            name,
+            params,
+            guard.as_deref(),
+            result,
+            FunctionPatternKind::Memoized,
+            env,
+        ),
     }
 }
 
@@ -294,8 +470,9 @@ fn import_from(
     module: &str,
     values: &[(String, Position)],
     env: &mut Environment,
+    position: Position,
 ) -> Result<Object, Error> {
-    run::import_from(module, values, env)?;
+    run::import_from(module, values, env, position)?;
     Ok(Object::empty_tuple())
 }
 
@@ -342,6 +519,11 @@ fn decimal(int: &str, dec: &str) -> Result<Object, Error> {
     Ok(Object::Decimal(Decimal::new(int, dec)))
 }
 
+fn imaginary(val: &ASTNode, env: &mut Environment) -> Result<Object, Error> {
+    let im = exec(val, env)?;
+    Ok(Complex::new(Object::Integer(Integer::from(0)), im).into_object())
+}
+
 fn cons(first: Object, most: &ASTNode, env: &mut Environment) -> Result<Object, Error> {
     match exec(most, env)? {
         Object::List(lst) => {
@@ -433,7 +615,7 @@ fn extension_set(l: &[ASTNode], env: &mut Environment) -> Result<Object, Error>
     list(l, env).map(|lst| Object::Set(Set::from(lst)))
 }
 
-fn symbol(str: &str, env: &mut Environment, position: Position) -> Result<Object, Error> {
+pub fn symbol(str: &str, env: &mut Environment, position: Position) -> Result<Object, Error> {
     match env.get(str) {
         EnvResponse::Inmutable(obj) => Ok(obj.clone()),
         EnvResponse::Mutable(obj) => Ok(obj.clone()),
@@ -444,44 +626,135 @@ fn symbol(str: &str, env: &mut Environment, position: Position) -> Result<Object
     }
 }
 
+/// A generator of a comprehension, with its pattern, its iterable and its
+/// guard.
+type Generator = (ASTNode, ASTNode, Option<ASTNode>);
+
 fn comprehension(
     element: &ASTNode,
-    variable: &str,
-    iterator: &ASTNode,
+    value: Option<&ASTNode>,
+    generators: &[Generator],
     kind: ComprehensionKind,
     env: &mut Environment,
 ) -> Result<Object, Error> {
-    let iterator = get_iterable(iterator, env)?;
+    let (first, rest) = generators.split_first().unwrap();
+    let source = exec(&first.1, env)?;
+
+    if let Object::Stream(stream) = source {
+        return match kind {
+            ComprehensionKind::List => Ok(Object::Stream(Stream::Comprehension {
+                source: Box::new(stream),
+                element: Box::new(element.to_owned()),
+                generators: generators.to_owned(),
+                env: env.clone(),
+            })),
+            ComprehensionKind::Set | ComprehensionKind::Dictionary => Err(Error::new(
+                EvalError::InfiniteStream.into(),
+                first.1.position,
+            )),
+        };
+    }
 
-    match kind {
-        ComprehensionKind::List => {
-            let mut new_list = vec![];
-            env.push_scope();
+    let mut keys = vec![];
+    let mut values = vec![];
 
-            for val in iterator {
-                env.set_inmutable(variable, val);
-                new_list.push(exec(element, env)?);
-            }
+    generate(source, first, rest, env, &mut |env| {
+        keys.push(exec(element, env)?);
+        if let Some(value) = value {
+            values.push(exec(value, env)?);
+        }
+
+        Ok(())
+    })?;
 
-            Ok(Object::List(List::from(new_list)))
+    match kind {
+        ComprehensionKind::List => Ok(Object::List(List::from(keys))),
+        ComprehensionKind::Set => Ok(Object::Set(Set::from(keys))),
+        ComprehensionKind::Dictionary => {
+            let mut dict = Dictionary::default();
+            dict.dict.extend(zip(keys, values));
+
+            Ok(Object::Dictionary(dict))
         }
-        ComprehensionKind::Set => {
-            let mut new_set = BTreeSet::new();
-            env.push_scope();
+    }
+}
 
-            for val in iterator {
-                env.set_inmutable(variable, val);
-                new_set.insert(exec(element, env)?);
-            }
+/// Runs `body` for every value of `source` that matches the pattern of
+/// `generator` and satisfies its guard, going through the `rest` of the
+/// generators in turn. Values that don't match are skipped. Only `source`
+/// can be a stream, since the rest would never end.
+fn generate(
+    source: Object,
+    (pattern, iterable, guard): &Generator,
+    rest: &[Generator],
+    env: &mut Environment,
+    body: &mut dyn FnMut(&mut Environment) -> Result<(), Error>,
+) -> Result<(), Error> {
+    for val in elements(source, iterable.position)? {
+        let Some(Match(map)) = match_(pattern, &val?) else {
+            continue;
+        };
 
-            Ok(Object::Set(Set::from(new_set)))
+        env.push_scope();
+        for (name, val) in map {
+            env.set_inmutable(&name, val);
         }
+
+        let res = match guard
+            .as_ref()
+            .map(|guard| exec(guard, env).and_then(|cond| holds(&cond, guard)))
+            .transpose()
+        {
+            Ok(Some(false)) => Ok(()),
+            Ok(_) => match rest.split_first() {
+                Some((next, rest)) => exec(&next.1, env).and_then(|source| match source {
+                    Object::Stream(_) => Err(Error::new(
+                        EvalError::InfiniteStream.into(),
+                        next.1.position,
+                    )),
+                    source => generate(source, next, rest, env, body),
+                }),
+                None => body(env),
+            },
+            Err(err) => Err(err),
+        };
+
+        env.pop_scope();
+        res?;
     }
+
+    Ok(())
+}
+
+/// The elements of a list comprehension that come from one value of the
+/// stream its first generator goes over.
+pub fn comprehension_step(
+    val: Object,
+    element: &ASTNode,
+    generators: &[Generator],
+    env: &mut Environment,
+) -> Result<Vec<Object>, Error> {
+    let (first, rest) = generators.split_first().unwrap();
+    let mut vals = vec![];
+
+    generate(
+        Object::List(List::from(vec![val])),
+        first,
+        rest,
+        env,
+        &mut |env| {
+            vals.push(exec(element, env)?);
+            Ok(())
+        },
+    )?;
+
+    Ok(vals)
 }
 
 fn let_function(
     name: &str,
     args: &[ASTNode],
+    guard: Option<&ASTNode>,
     value: &ASTNode,
     kind: FunctionPatternKind,
     env: &mut Environment,
@@ -504,7 +777,7 @@ fn let_function(
         _ => unimplemented!(),
     };
 
-    function.add_pattern(args, value, kind);
+    function.add_pattern(args, guard, value, kind);
     function
         .env
         .borrow_mut()
@@ -513,16 +786,17 @@ fn let_function(
     Ok(Object::Function(Function::Pattern(function.clone())))
 }
 
-fn if_(
-    cond: Object,
+fn if_<T>(
+    cond: &ASTNode,
     first: &ASTNode,
     second: &ASTNode,
     env: &mut Environment,
-) -> Result<Object, Error> {
-    if truthy(&cond) {
-        exec(first, env)
+    eval: fn(&ASTNode, &mut Environment) -> Result<T, Error>,
+) -> Result<T, Error> {
+    if holds(&exec(cond, env)?, cond)? {
+        eval(first, env)
     } else {
-        exec(second, env)
+        eval(second, env)
     }
 }
 
@@ -537,7 +811,7 @@ fn for_(
     env.push_scope();
 
     for val in iter {
-        env.set_inmutable(symbol, val.clone());
+        env.set_inmutable(symbol, val?);
 
         for step in proc {
             exec(step, env)?;
@@ -549,19 +823,14 @@ fn for_(
     Ok(Object::empty_tuple())
 }
 
-fn get_iterable(
-    node: &ASTNode,
-    env: &mut Environment,
-) -> Result<Box<dyn Iterator<Item = Object>>, Error> {
-    match exec(node, env)? {
-        Object::Set(set) => Ok(Box::new(set.set.into_iter())),
-        Object::List(list) => Ok(Box::new(list.list.into_iter())),
-        Object::Range(range) => Ok(Box::new(range.into_iter())),
-        obj => Err(Error(
-            EvalError::NonIterableObject(obj.kind()).into(),
-            node.position,
-        )),
-    }
+fn get_iterable(node: &ASTNode, env: &mut Environment) -> Result<Elements, Error> {
+    elements(exec(node, env)?, node.position)
+}
+
+fn elements(obj: Object, position: Position) -> Result<Elements, Error> {
+    let kind = obj.kind();
+
+    stream::elements(obj).ok_or_else(|| Error(EvalError::NonIterableObject(kind).into(), position))
 }
 
 fn call(
@@ -569,35 +838,92 @@ fn call(
     args: &[ASTNode],
     env: &mut Environment,
     call_pos: Position,
+) -> Result<Object, Error> {
+    let func = exec(func_node, env)?;
+    call_object(func, func_node, args, env, call_pos)
+}
+
+/// A call in tail position, which is left for the caller to make when it
+/// calls a pattern function.
+fn tail_call(
+    func_node: &ASTNode,
+    args: &[ASTNode],
+    env: &mut Environment,
+    call_pos: Position,
+) -> Result<Tail, Error> {
+    let ASTNodeKind::Symbol { name } = &func_node.kind else {
+        return call(func_node, args, env, call_pos).map(Tail::Value);
+    };
+
+    let func = match env.get(name) {
+        EnvResponse::NotFound => None,
+        _ => match exec(func_node, env)? {
+            Object::Function(Function::Pattern(f)) if args.len() >= f.param_number() => Some(f),
+            func => return call_object(func, func_node, args, env, call_pos).map(Tail::Value),
+        },
+    };
+
+    Ok(Tail::Call(TailCall {
+        name: name.to_owned(),
+        func,
+        args: list(args, env)?,
+        name_pos: func_node.position,
+        call_pos,
+    }))
+}
+
+fn call_object(
+    func: Object,
+    func_node: &ASTNode,
+    args: &[ASTNode],
+    env: &mut Environment,
+    call_pos: Position,
 ) -> Result<Object, Error> {
     let func_name = match &func_node.kind {
-        ASTNodeKind::Symbol { name } => Some(name),
+        ASTNodeKind::Symbol { name } => Some(name.as_str()),
         _ => None,
     };
 
-    let func = exec(func_node, env)?;
-    if let Object::Function(ref f) = func {
-        if args.len() < f.param_number() {
-            return Err(Error(
-                EvalError::MissingFunctionArguments {
-                    expected: f.param_number(),
-                    actual: args.len(),
-                }
-                .into(),
-                call_pos,
-            ));
-        }
-    }
+    check_arguments(&func, args.len(), call_pos)?;
+    let func_args = list(args, env)?;
+
+    apply(
+        func,
+        func_name,
+        &func_args,
+        env,
+        call_pos,
+        func_node.position,
+    )
+}
 
-    let mut func_args = vec![];
-    for arg in args {
-        let func_arg = exec(arg, env)?;
-        func_args.push(func_arg);
+pub fn check_arguments(func: &Object, actual: usize, call_pos: Position) -> Result<(), Error> {
+    match func {
+        Object::Function(f) if actual < f.param_number() => Err(Error(
+            EvalError::MissingFunctionArguments {
+                expected: f.param_number(),
+                actual,
+            }
+            .into(),
+            call_pos,
+        )),
+        _ => Ok(()),
     }
+}
 
+/// Calls an object with arguments already evaluated, saving the function
+/// back to `func_name` so it keeps what it memoized.
+pub fn apply(
+    func: Object,
+    func_name: Option<&str>,
+    args: &[Object],
+    env: &mut Environment,
+    call_pos: Position,
+    func_pos: Position,
+) -> Result<Object, Error> {
     match func {
         Object::Function(mut f) => {
-            let res = f.call(&func_args, env, call_pos);
+            let res = f.call(args, env, call_pos);
 
             if let Some(name) = func_name {
                 env.set_mutable(name, Object::Function(f.clone()));
@@ -605,18 +931,43 @@ fn call(
 
             res
         }
+        Object::Symbol(Symbol { name, .. }) => Ok(symbolic::call(&name, args.to_vec())),
+        Object::Polynomial(poly) => poly
+            .evaluate(args)
+            .map_err(|err| Error::new(err.into(), call_pos)),
         obj => Err(Error(
             EvalError::NonCallableObject(obj.kind()).into(),
-            func_node.position,
+            func_pos,
         ))?,
     }
 }
 
+/// A range without an end, like `1..`, which is a stream.
+fn open_range(start: Object, position: Position) -> Result<Object, Error> {
+    match start {
+        Object::Integer(start) => Ok(Object::Stream(Stream::From(start))),
+        start => Err(Error::new(
+            EvalError::BadOpenRange {
+                start_kind: start.kind(),
+            }
+            .into(),
+            position,
+        )),
+    }
+}
+
 fn range(start: &Object, end: &Object) -> Option<Object> {
     match (start, end) {
         (Object::Integer(start), Object::Integer(end)) => {
             Some(Object::Range(Range::new(start, end)))
         }
+        (start, end)
+            if [start, end]
+                .iter()
+                .all(|obj| matches!(obj, Object::Integer(_)) || obj.is_symbolic()) =>
+        {
+            Some(symbolic::range(start.to_owned(), end.to_owned()))
+        }
         _ => None,
     }
 }
@@ -646,6 +997,31 @@ fn fraction(
     }
 }
 
+fn modular(
+    val: &ASTNode,
+    modulus: &ASTNode,
+    position: Position,
+    env: &mut Environment,
+) -> Result<Object, Error> {
+    match (exec(val, env)?, exec(modulus, env)?) {
+        (Object::Integer(_), Object::Integer(int)) if !int.is_positive() => Err(Error::new(
+            EvalError::NonPositiveModulus.into(),
+            modulus.position,
+        )),
+        (Object::Integer(val), Object::Integer(modulus)) => {
+            Ok(Object::Modular(Modular::new(&val, &modulus)))
+        }
+        (val, modulus) => Err(Error::new(
+            EvalError::BadModular {
+                val_kind: val.kind(),
+                modulus_kind: modulus.kind(),
+            }
+            .into(),
+            position,
+        )),
+    }
+}
+
 fn infix(
     op: InfixOperator,
     lhs: &Object,
@@ -659,14 +1035,30 @@ fn infix(
         InfixOperator::BitwiseAnd => lhs.bitwise_and(rhs),
         InfixOperator::BitwiseXor => lhs.bitwise_xor(rhs),
         InfixOperator::Division => {
-            if rhs.is_zero() {
+            if rhs.is_zero() || matches!(rhs, Object::Interval(x) if x.contains_zero()) {
                 return Err(Error::new(EvalError::DenominatorZero.into(), infix_pos));
-            } else {
-                lhs.over(rhs)
+            }
+
+            match Modular::divisor(lhs, rhs) {
+                Some(divisor) if divisor.reciprocal().is_none() => {
+                    return Err(Error::new(
+                        EvalError::NonInvertible(divisor.to_string()).into(),
+                        infix_pos,
+                    ))
+                }
+                _ => lhs.over(rhs),
             }
         }
         InfixOperator::Equality => lhs.equality(rhs),
-        InfixOperator::Exponentiation => lhs.pow(rhs),
+        InfixOperator::Exponentiation => match (lhs, rhs) {
+            (Object::Modular(base), exp) if exp.is_negative() && base.reciprocal().is_none() => {
+                return Err(Error::new(
+                    EvalError::NonInvertible(base.to_string()).into(),
+                    infix_pos,
+                ))
+            }
+            _ => lhs.pow(rhs),
+        },
         InfixOperator::Greater => lhs.greater(rhs),
         InfixOperator::GreaterEqual => lhs.greater_equal(rhs),
         InfixOperator::In => rhs.contains(lhs),
@@ -727,16 +1119,35 @@ mod tests {
 
     use super::*;
     use crate::ast::tests::{
-        _for, _if, assignment, block, boolean, call, case, comprehension, cons, container_element,
-        dec_integer, decimal, extension_list, extension_set, fraction, function,
+        _for, _if, ad_infinitum, assignment, block, boolean, call, case, comprehension, cons,
+        container_element, dec_integer, decimal, extension_list, extension_set, fraction, function,
         function_declaration, infix, let_, memoized_function_declaration, pos, prefix, range,
         set_cons, string, symbol, symbolic_let, tuple, var,
     };
     use crate::cst::tests::dummy_pos;
     use crate::env::EnvResponse;
     use crate::error::ErrorType;
+    use crate::symbolic::Expression;
     use crate::{ast, object::*};
 
+    #[test]
+    fn misplaced_ad_infinitum() {
+        let misplaced = || {
+            Err(Error::new(
+                EvalError::MisplacedAdInfinitum.into(),
+                pos(4, 2),
+            ))
+        };
+        let list = extension_list(
+            vec![dec_integer("1", pos(1, 1)), ad_infinitum(pos(4, 2))],
+            pos(0, 7),
+        );
+        let declaration = let_(symbol("a", pos(0, 1)), ad_infinitum(pos(4, 2)), pos(0, 6));
+
+        assert_eq!(exec(&list, &mut Default::default()), misplaced());
+        assert_eq!(exec(&declaration, &mut Default::default()), misplaced());
+    }
+
     #[test]
     fn unknown_value() {
         let node = symbol("a", dummy_pos());
@@ -816,11 +1227,57 @@ mod tests {
             dummy_pos(),
         );
 
+        let a = Object::Symbol(Symbol::new("a".into(), "Foo".into()));
+        let b = Object::Symbol(Symbol::new("b".into(), "Foo".into()));
+
         let mut env = Environment::default();
-        env.set_inmutable("a", Object::Symbol(Symbol::new("a".into(), "Foo".into())));
-        env.set_inmutable("b", Object::Symbol(Symbol::new("b".into(), "Foo".into())));
+        env.set_inmutable("a", a.to_owned());
+        env.set_inmutable("b", b.to_owned());
 
-        assert_eq!(exec(node, &mut env), Ok(Object::Boolean(false.into())));
+        assert_eq!(
+            exec(node, &mut env),
+            Ok(Object::Expression(Expression::Equation(
+                Box::new(a),
+                Box::new(b)
+            )))
+        );
+    }
+
+    #[test]
+    fn symbolic_sum() {
+        let node = &infix(
+            InfixOperator::Sum,
+            symbol("x", dummy_pos()),
+            dec_integer("1", dummy_pos()),
+            dummy_pos(),
+        );
+
+        let x = Object::Symbol(Symbol::new("x".into(), "Real".into()));
+
+        let mut env = Environment::default();
+        env.set_inmutable("x", x.clone());
+
+        assert_eq!(
+            exec(node, &mut env),
+            Ok(Object::Expression(symbolic::Expression::Sum(vec![
+                x,
+                Object::Integer(Integer::from(1))
+            ])))
+        );
+    }
+
+    #[test]
+    fn call_symbol() {
+        let node = &call(
+            symbol("f", dummy_pos()),
+            vec![dec_integer("2", dummy_pos())],
+            dummy_pos(),
+        );
+
+        let mut env = Environment::default();
+        env.set_inmutable("f", Object::Symbol(Symbol::new("f".into(), "Real".into())));
+
+        assert_eq!(exec(node, &mut env).unwrap().to_string(), "f(2)");
     }
 
     #[test]
@@ -1215,9 +1672,9 @@ mod tests {
         // I do this to test the change of state without assignments
         static ARGS: Mutex<Vec<String>> = Mutex::new(vec![]);
 
-        fn test(args: &[Object]) -> Object {
+        fn test(args: &[Object]) -> Result<Object, EvalError> {
             ARGS.lock().unwrap().push(args[0].to_string());
-            Object::empty_tuple()
+            Ok(Object::empty_tuple())
         }
 
         let mut env = Environment::default();
@@ -1659,11 +2116,11 @@ mod tests {
     fn memoization() {
         static mut CALL_COUNTER: usize = 0;
 
-        fn foo(_: &[Object]) -> Object {
+        fn foo(_: &[Object]) -> Result<Object, EvalError> {
             unsafe {
                 CALL_COUNTER += 1;
             }
-            Object::empty_tuple()
+            Ok(Object::empty_tuple())
         }
 
         let foo_obj = Object::Function(Function::Extern(ExternFunction::new(foo, 1)));
@@ -1706,9 +2163,14 @@ mod tests {
         let node = case(
             dec_integer("5", dummy_pos()),
             vec![
-                (dec_integer("1", dummy_pos()), dec_integer("1", dummy_pos())),
+                (
+                    dec_integer("1", dummy_pos()),
+                    None,
+                    dec_integer("1", dummy_pos()),
+                ),
                 (
                     dec_integer("5", dummy_pos()),
+                    None,
                     dec_integer("10", dummy_pos()),
                 ),
             ],
@@ -1720,4 +2182,94 @@ mod tests {
             Ok(Object::Integer(10.into())),
         );
     }
+
+    #[test]
+    fn local_declarations() {
+        let node = ASTNode::new(
+            ASTNodeKind::Local {
+                declarations: vec![let_(
+                    symbol("a", dummy_pos()),
+                    dec_integer("2", dummy_pos()),
+                    dummy_pos(),
+                )],
+                body: Box::new(infix(
+                    InfixOperator::Product,
+                    symbol("a", dummy_pos()),
+                    symbol("a", dummy_pos()),
+                    dummy_pos(),
+                )),
+            },
+            dummy_pos(),
+        );
+        let mut env = Environment::default();
+
+        assert_eq!(exec(&node, &mut env), Ok(Object::Integer(4.into())));
+        assert_eq!(env.get("a"), EnvResponse::NotFound);
+    }
+
+    #[test]
+    fn guarded_case() {
+        let node = case(
+            dec_integer("5", dummy_pos()),
+            vec![
+                (
+                    symbol("n", dummy_pos()),
+                    Some(infix(
+                        InfixOperator::Greater,
+                        symbol("n", dummy_pos()),
+                        dec_integer("10", dummy_pos()),
+                        dummy_pos(),
+                    )),
+                    dec_integer("1", dummy_pos()),
+                ),
+                (symbol("n", dummy_pos()), None, symbol("n", dummy_pos())),
+            ],
+            dummy_pos(),
+        );
+
+        assert_eq!(
+            exec(&node, &mut Environment::default()),
+            Ok(Object::Integer(5.into())),
+        );
+    }
+
+    #[test]
+    fn tail_call_to_later_function() {
+        let mut env = Environment::default();
+        let first = function_declaration(
+            "first",
+            vec![symbol("x", dummy_pos())],
+            call(
+                symbol("second", dummy_pos()),
+                vec![symbol("x", dummy_pos())],
+                dummy_pos(),
+            ),
+            dummy_pos(),
+        );
+        let second = function_declaration(
+            "second",
+            vec![symbol("x", dummy_pos())],
+            infix(
+                InfixOperator::Sum,
+                symbol("x", dummy_pos()),
+                dec_integer("1", dummy_pos()),
+                dummy_pos(),
+            ),
+            dummy_pos(),
+        );
+        exec(&first, &mut env).unwrap();
+        exec(&second, &mut env).unwrap();
+
+        let node = call(
+            symbol("first", dummy_pos()),
+            vec![dec_integer("1", dummy_pos())],
+            dummy_pos(),
+        );
+
+        assert!(matches!(
+            exec_tail(&node, &mut env),
+            Ok(Tail::Call(TailCall { func: Some(_), .. }))
+        ));
+        assert_eq!(exec(&node, &mut env), Ok(Object::Integer(2.into())));
+    }
 }
@@ -0,0 +1,423 @@
+use bigdecimal::{One, Signed, Zero};
+use num_bigint::BigInt;
+use num_rational::BigRational;
+
+use crate::{
+    exec::EvalError,
+    numtheory::{divisors, integer_factors},
+    object::{Integer, List, Object, Symbol, Tuple},
+    polynomial::Polynomial,
+};
+
+/// Dense integer polynomials, indexed by the power of the variable.
+type Dense = Vec<BigInt>;
+
+fn evaluate(poly: &[BigInt], point: &BigInt) -> BigInt {
+    poly.iter()
+        .rev()
+        .fold(BigInt::zero(), |acc, coefficient| acc * point + coefficient)
+}
+
+/// Expands the Newton form `n_0 + n_1*(x - a_0) + n_2*(x - a_0)*(x - a_1) + ...`.
+fn from_newton(points: &[BigInt], newton: &[BigInt]) -> Dense {
+    let mut res = vec![newton.last().unwrap().to_owned()];
+
+    for (point, coefficient) in points.iter().zip(newton).rev().skip(1) {
+        let mut next = vec![BigInt::zero(); res.len() + 1];
+        for (k, val) in res.iter().enumerate() {
+            next[k + 1] += val;
+            next[k] -= val * point;
+        }
+
+        next[0] += coefficient;
+        res = next;
+    }
+
+    res
+}
+
+fn divide_exact(dividend: &[BigInt], divisor: &[BigInt]) -> Option<Dense> {
+    let divisor_lead = divisor.last()?;
+    let mut rest = dividend.to_vec();
+
+    if rest.len() < divisor.len() {
+        return None;
+    }
+
+    let mut quotient = vec![BigInt::zero(); rest.len() - divisor.len() + 1];
+
+    for i in (0..quotient.len()).rev() {
+        let lead = &rest[i + divisor.len() - 1];
+        if !(lead % divisor_lead).is_zero() {
+            return None;
+        }
+
+        let coefficient = lead / divisor_lead;
+        for (j, divisor_coefficient) in divisor.iter().enumerate() {
+            rest[i + j] -= &coefficient * divisor_coefficient;
+        }
+
+        quotient[i] = coefficient;
+    }
+
+    if rest.iter().all(Zero::is_zero) {
+        Some(quotient)
+    } else {
+        None
+    }
+}
+
+/// Kronecker's method: a factor of degree `degree` is determined by its
+/// values at `degree + 1` points, and those values must divide the values of
+/// the polynomial at the same points.
+fn find_factor(poly: &[BigInt], degree: usize) -> Option<Dense> {
+    let mut candidates = vec![];
+    let mut point = BigInt::zero();
+
+    while candidates.len() < 2 * degree + 2 {
+        let value = evaluate(poly, &point);
+
+        if value.is_zero() {
+            return Some(vec![-point, BigInt::one()]);
+        }
+
+        candidates.push((divisors(&value), point.to_owned()));
+        point = if point.is_positive() {
+            -point
+        } else {
+            -point + 1
+        };
+    }
+
+    candidates.sort_by_key(|(divisors, _)| divisors.len());
+    candidates.truncate(degree + 1);
+
+    let points: Vec<BigInt> = candidates
+        .iter()
+        .map(|(_, point)| point.to_owned())
+        .collect();
+    let choices: Vec<Vec<BigInt>> = candidates
+        .iter()
+        .enumerate()
+        .map(|(i, (divisors, _))| match i {
+            0 => divisors.to_owned(),
+            _ => divisors.iter().flat_map(|d| [d.to_owned(), -d]).collect(),
+        })
+        .collect();
+
+    search(poly, &points, &choices, &[], &mut vec![])
+}
+
+/// Chooses the values of the factor at each point, computing the divided
+/// differences on the way. Those of a polynomial with integer coefficients
+/// are integers, so most choices are discarded early.
+fn search(
+    poly: &[BigInt],
+    points: &[BigInt],
+    choices: &[Vec<BigInt>],
+    previous: &[BigInt],
+    newton: &mut Vec<BigInt>,
+) -> Option<Dense> {
+    let level = newton.len();
+
+    if level == points.len() {
+        let lead = newton.last().unwrap();
+        if lead.is_zero() || !(poly.last().unwrap() % lead).is_zero() {
+            return None;
+        }
+
+        let factor = from_newton(points, newton);
+        return divide_exact(poly, &factor).map(|_| factor);
+    }
+
+    'choices: for value in &choices[level] {
+        let mut row = vec![value.to_owned()];
+
+        for j in 1..=level {
+            let numer = &row[j - 1] - &previous[j - 1];
+            let denom = &points[level] - &points[level - j];
+
+            if !(&numer % &denom).is_zero() {
+                continue 'choices;
+            }
+
+            row.push(numer / denom);
+        }
+
+        newton.push(row[level].to_owned());
+        if let Some(factor) = search(poly, points, choices, &row, newton) {
+            return Some(factor);
+        }
+        newton.pop();
+    }
+
+    None
+}
+
+fn positive_lead(mut poly: Dense) -> Dense {
+    if poly.last().is_some_and(Signed::is_negative) {
+        poly.iter_mut()
+            .for_each(|coefficient| *coefficient = -&*coefficient);
+    }
+
+    poly
+}
+
+/// Splits a square-free primitive polynomial into irreducible factors.
+fn split(poly: Dense) -> Vec<Dense> {
+    let degree = poly.len() - 1;
+
+    for factor_degree in 1..=degree / 2 {
+        if let Some(factor) = find_factor(&poly, factor_degree) {
+            let factor = positive_lead(factor);
+            let rest = divide_exact(&poly, &factor).unwrap();
+
+            let mut res = split(factor);
+            res.extend(split(rest));
+            return res;
+        }
+    }
+
+    vec![poly]
+}
+
+fn to_dense(poly: &Polynomial, var: &Symbol) -> Dense {
+    poly.coefficients_in(var)
+        .iter()
+        .map(|coefficient| coefficient.constant_value().unwrap().to_integer())
+        .collect()
+}
+
+fn from_dense(poly: &[BigInt], var: &Symbol) -> Polynomial {
+    let coefficients: Vec<BigRational> = poly
+        .iter()
+        .map(|coefficient| BigRational::from_integer(coefficient.to_owned()))
+        .collect();
+
+    Polynomial::from_coefficients(var, &coefficients)
+}
+
+/// Yun's square-free decomposition.
+fn square_free(poly: &Polynomial, var: &Symbol) -> Vec<(Polynomial, usize)> {
+    let mut res = vec![];
+
+    let derivative = poly.derivative(var);
+    let common = poly.gcd(&derivative);
+    let mut rest = poly.exact_quotient(&common);
+    let mut difference = &derivative.exact_quotient(&common) - &rest.derivative(var);
+    let mut multiplicity = 1;
+
+    while rest.degree_in(var).is_some_and(|degree| degree > 0) {
+        let factor = rest.gcd(&difference);
+        rest = rest.exact_quotient(&factor);
+        difference = &difference.exact_quotient(&factor) - &rest.derivative(var);
+
+        if factor.degree_in(var).is_some_and(|degree| degree > 0) {
+            res.push((factor, multiplicity));
+        }
+
+        multiplicity += 1;
+    }
+
+    res
+}
+
+/// Factors a polynomial into a rational constant and primitive factors with
+/// integer coefficients. Factors in one variable are irreducible over the
+/// rationals; those in several are only split into their content and
+/// square-free parts.
+pub fn factor_polynomial(poly: &Polynomial) -> (BigRational, Vec<(Polynomial, usize)>) {
+    let (mut constant, primitive) = poly.integer_primitive();
+    let mut res = vec![];
+
+    let vars = primitive.variables();
+    let var = match vars.iter().next_back() {
+        Some(var) => var,
+        None => return (constant, res),
+    };
+
+    let content = primitive.content_in(var);
+    if content.constant_value().is_none() {
+        let (content_constant, content_factors) = factor_polynomial(&content);
+        constant *= content_constant;
+        res.extend(content_factors);
+    }
+
+    let primitive = primitive.exact_quotient(&content);
+
+    for (factor, multiplicity) in square_free(&primitive, var) {
+        let (_, factor) = factor.integer_primitive();
+
+        if factor.variables().len() == 1 {
+            for irreducible in split(to_dense(&factor, var)) {
+                res.push((from_dense(&irreducible, var), multiplicity));
+            }
+        } else {
+            res.push((factor, multiplicity));
+        }
+    }
+
+    res.sort_by(|(lhs, _), (rhs, _)| (lhs.degree(), lhs).cmp(&(rhs.degree(), rhs)));
+
+    (constant, res)
+}
+
+fn pair(factor: Object, multiplicity: usize) -> Object {
+    Object::Tuple(Tuple::from(vec![
+        factor,
+        Object::Integer(Integer::from(multiplicity)),
+    ]))
+}
+
+pub fn factor(args: &[Object]) -> Result<Object, EvalError> {
+    let bad_argument = |obj: &Object| EvalError::bad_argument("factor", "Polynomial", obj);
+
+    let list = match &args[0] {
+        obj if obj.is_zero() => return Err(EvalError::FactorZero),
+        Object::Integer(_) => {
+            let n = args[0].as_rational().unwrap().to_integer();
+            let mut list = vec![];
+
+            if n.is_negative() {
+                list.push(pair(Object::Integer(Integer::from(-1)), 1));
+            }
+
+            for (prime, multiplicity) in integer_factors(&n) {
+                list.push(pair(Object::Integer(Integer::from(prime)), multiplicity));
+            }
+
+            list
+        }
+        obj => {
+            let poly = Polynomial::from_object(obj).ok_or_else(|| bad_argument(obj))?;
+
+            if poly.is_zero() {
+                return Err(EvalError::FactorZero);
+            }
+
+            let (constant, factors) = factor_polynomial(&poly);
+
+            if factors
+                .iter()
+                .any(|(factor, _)| factor.variables().len() > 1)
+            {
+                return Err(EvalError::MultivariateFactor(obj.to_string()));
+            }
+            let mut list = vec![];
+
+            if !constant.is_one() {
+                list.push(pair(Object::from_rational(constant), 1));
+            }
+
+            for (factor, multiplicity) in factors {
+                let factor = match obj {
+                    Object::Polynomial(_) => Object::Polynomial(factor),
+                    _ => factor.to_expression(),
+                };
+
+                list.push(pair(factor, multiplicity));
+            }
+
+            list
+        }
+    };
+
+    Ok(Object::List(List::from(list)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn x() -> Symbol {
+        Symbol::new("x".into(), "Real".into())
+    }
+
+    fn dense(coefficients: &[i32]) -> Polynomial {
+        let coefficients: Vec<BigInt> = coefficients.iter().map(|c| BigInt::from(*c)).collect();
+        from_dense(&coefficients, &x())
+    }
+
+    fn factors(poly: &Polynomial) -> Vec<String> {
+        let (constant, factors) = factor_polynomial(poly);
+        let mut res = vec![constant.to_string()];
+
+        res.extend(
+            factors
+                .iter()
+                .map(|(factor, multiplicity)| format!("{factor}^{multiplicity}")),
+        );
+
+        res
+    }
+
+    #[test]
+    fn prime_factorization() {
+        assert_eq!(
+            integer_factors(&BigInt::from(360)),
+            vec![
+                (BigInt::from(2), 3),
+                (BigInt::from(3), 2),
+                (BigInt::from(5), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn rational_roots() {
+        // 2x**2 - x - 1 = (2x + 1)(x - 1)
+        assert_eq!(
+            factors(&dense(&[-1, -1, 2])),
+            vec!["1", "poly(x - 1)^1", "poly(2*x + 1)^1"]
+        );
+    }
+
+    #[test]
+    fn repeated_factors() {
+        // (x - 1)**2 * (x + 2) = x**3 - 3x + 2
+        assert_eq!(
+            factors(&dense(&[2, -3, 0, 1])),
+            vec!["1", "poly(x - 1)^2", "poly(x + 2)^1"]
+        );
+    }
+
+    #[test]
+    fn irreducible_quadratics() {
+        // x**4 + 4 = (x**2 + 2x + 2)(x**2 - 2x + 2)
+        assert_eq!(
+            factors(&dense(&[4, 0, 0, 0, 1])),
+            vec!["1", "poly(x**2 - 2*x + 2)^1", "poly(x**2 + 2*x + 2)^1"]
+        );
+    }
+
+    #[test]
+    fn rational_constant() {
+        let poly = dense(&[2, 4]).scale(&BigRational::new(1.into(), 3.into()));
+
+        assert_eq!(factors(&poly), vec!["2/3", "poly(2*x + 1)^1"]);
+    }
+
+    #[test]
+    fn multivariate_content() {
+        // x**2*y - y = (x - 1)(x + 1)y
+        let y = Polynomial::variable(Symbol::new("y".into(), "Real".into()));
+        let poly = &dense(&[-1, 0, 1]) * &y;
+
+        assert_eq!(
+            factors(&poly),
+            vec!["1", "poly(x - 1)^1", "poly(x + 1)^1", "poly(y)^1"]
+        );
+    }
+
+    #[test]
+    fn univariate_factors_of_multivariate() {
+        // x*y**2 - x = x(y - 1)(y + 1)
+        let y = Polynomial::variable(Symbol::new("y".into(), "Real".into()));
+        let poly = &(&(&y * &y) - &Polynomial::constant(BigRational::one())) * &dense(&[0, 1]);
+
+        assert_eq!(
+            factors(&poly),
+            vec!["1", "poly(y - 1)^1", "poly(y + 1)^1", "poly(x)^1"]
+        );
+    }
+}
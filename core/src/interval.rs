@@ -0,0 +1,543 @@
+use std::fmt;
+
+use bigdecimal::{BigDecimal, One, RoundingMode, Signed, ToPrimitive, Zero};
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer as _;
+use num_rational::BigRational;
+
+use crate::{
+    exec::EvalError,
+    numeric,
+    object::{Bool, Decimal, InfixOperable, Object, Precision, PrefixOperable},
+    symbolic::Expression,
+};
+
+/// Digits past the precision that approximations are computed with, before
+/// they are widened into an interval.
+const GUARD_DIGITS: u64 = 5;
+
+/// A closed interval of real numbers. Every operation rounds the lower end
+/// down and the upper end up, so the result contains every value that the
+/// exact operation could give.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval {
+    lo: BigDecimal,
+    hi: BigDecimal,
+}
+
+/// The result of a comparison that the intervals can't decide.
+pub fn unknown() -> Object {
+    Object::Boolean(Bool::unknown())
+}
+
+fn decided(holds: bool, fails: bool) -> Object {
+    match (holds, fails) {
+        (true, _) => true.into(),
+        (_, true) => false.into(),
+        _ => unknown(),
+    }
+}
+
+fn directed(val: BigDecimal, mode: RoundingMode) -> BigDecimal {
+    let digits = Precision::current().digits;
+
+    if val.digits() > digits.get() {
+        val.with_precision_round(digits, mode)
+    } else {
+        val
+    }
+}
+
+fn down(val: BigDecimal) -> BigDecimal {
+    directed(val, RoundingMode::Floor)
+}
+
+fn up(val: BigDecimal) -> BigDecimal {
+    directed(val, RoundingMode::Ceiling)
+}
+
+fn rational(val: &BigDecimal) -> BigRational {
+    let (val, scale) = val.as_bigint_and_exponent();
+    let power = BigInt::from(10).pow(scale.unsigned_abs() as u32);
+
+    match scale.is_negative() {
+        true => BigRational::from_integer(val * power),
+        false => BigRational::new(val, power),
+    }
+}
+
+fn digit_count(val: &BigInt) -> i64 {
+    val.magnitude().to_str_radix(10).len() as i64
+}
+
+/// The closest decimals below and above a rational number.
+fn rational_bounds(val: &BigRational) -> (BigDecimal, BigDecimal) {
+    if val.is_integer() {
+        let val = BigDecimal::new(val.to_integer(), 0);
+        return (down(val.to_owned()), up(val));
+    }
+
+    // Enough digits after the point to have the precision, and one more
+    let shift = Precision::current().digits.get() as i64 + 1 + digit_count(val.denom())
+        - digit_count(val.numer());
+    let power = BigRational::from_integer(BigInt::from(10).pow(shift.unsigned_abs() as u32));
+    let scaled = match shift.is_negative() {
+        true => val / power,
+        false => val * power,
+    };
+
+    (
+        down(BigDecimal::new(scaled.floor().to_integer(), shift)),
+        up(BigDecimal::new(scaled.ceil().to_integer(), shift)),
+    )
+}
+
+/// Bounds for a value that `approximation` rounds correctly when it is
+/// computed with a few guard digits.
+fn approximate_bounds(
+    approximation: impl FnOnce() -> Option<BigDecimal>,
+) -> Option<(BigDecimal, BigDecimal)> {
+    let current = Precision::current();
+    let guarded = Precision::new(
+        current.digits.saturating_add(GUARD_DIGITS),
+        current.rounding,
+    );
+
+    guarded.install();
+    let val = approximation();
+    current.install();
+
+    let val = val?;
+    let ulp = BigDecimal::new(
+        BigInt::one(),
+        guarded.digits.get() as i64 - val.digits() as i64 + val.fractional_digit_count(),
+    );
+
+    Some((down(&val - &ulp), up(val + ulp)))
+}
+
+/// `val**exp` for a nonnegative `val`, rounded in the same direction after
+/// every product.
+fn power_bound(val: &BigDecimal, exp: &BigUint, round: fn(BigDecimal) -> BigDecimal) -> BigDecimal {
+    let mut base = val.to_owned();
+    let mut exp = exp.to_owned();
+    let mut res = BigDecimal::one();
+
+    while !exp.is_zero() {
+        if exp.is_odd() {
+            res = round(res * &base);
+        }
+        base = round(&base * &base);
+        exp >>= 1;
+    }
+
+    res
+}
+
+impl Interval {
+    fn new(lo: BigDecimal, hi: BigDecimal) -> Self {
+        Self { lo, hi }
+    }
+
+    fn point(val: BigDecimal) -> Self {
+        Self::new(down(val.to_owned()), up(val))
+    }
+
+    fn pi() -> Self {
+        let (lo, hi) = approximate_bounds(|| numeric::pi().as_decimal()).unwrap();
+        Self::new(lo, hi)
+    }
+
+    /// An interval that contains a number, or the value of an expression
+    /// without variables.
+    pub fn from_object(obj: &Object) -> Option<Self> {
+        match obj {
+            Object::Interval(x) => Some(x.to_owned()),
+            Object::Integer(_) | Object::Decimal(_) => Some(Self::point(obj.as_decimal()?)),
+            Object::Fraction(_) => {
+                let (lo, hi) = rational_bounds(&obj.as_rational()?);
+                Some(Self::new(lo, hi))
+            }
+            Object::Symbol(symbol) if symbol.property == "Pi" => Some(Self::pi()),
+            Object::Expression(Expression::Sum(terms)) => terms
+                .iter()
+                .try_fold(Self::point(BigDecimal::zero()), |acc, term| {
+                    Some(acc.add(&Self::from_object(term)?))
+                }),
+            Object::Expression(Expression::Product(factors)) => factors
+                .iter()
+                .try_fold(Self::point(BigDecimal::one()), |acc, factor| {
+                    Some(acc.mul(&Self::from_object(factor)?))
+                }),
+            Object::Expression(Expression::Power(base, exp)) => Self::from_object(base)?.power(exp),
+            Object::Expression(Expression::Call(name, args)) if args.len() == 1 => {
+                apply(name, &Self::from_object(&args[0])?)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn contains_zero(&self) -> bool {
+        !self.lo.is_positive() && !self.hi.is_negative()
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        Self::new(down(&self.lo + &other.lo), up(&self.hi + &other.hi))
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        Self::new(down(&self.lo - &other.hi), up(&self.hi - &other.lo))
+    }
+
+    fn neg(&self) -> Self {
+        Self::new(-&self.hi, -&self.lo)
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        let products = [
+            &self.lo * &other.lo,
+            &self.lo * &other.hi,
+            &self.hi * &other.lo,
+            &self.hi * &other.hi,
+        ];
+
+        Self::new(
+            down(products.iter().min().unwrap().to_owned()),
+            up(products.iter().max().unwrap().to_owned()),
+        )
+    }
+
+    fn recip(&self) -> Option<Self> {
+        if self.contains_zero() {
+            return None;
+        }
+
+        let (lo, _) = rational_bounds(&rational(&self.hi).recip());
+        let (_, hi) = rational_bounds(&rational(&self.lo).recip());
+
+        Some(Self::new(lo, hi))
+    }
+
+    fn div(&self, other: &Self) -> Option<Self> {
+        Some(self.mul(&other.recip()?))
+    }
+
+    fn integer_power(&self, exp: &BigInt) -> Option<Self> {
+        if exp.is_negative() {
+            return self.recip()?.integer_power(&-exp);
+        }
+
+        let exp = exp.magnitude();
+        let even = exp.is_even();
+
+        let res = if !self.lo.is_negative() {
+            Self::new(
+                power_bound(&self.lo, exp, down),
+                power_bound(&self.hi, exp, up),
+            )
+        } else if !self.hi.is_positive() {
+            match even {
+                true => self.neg().integer_power(&BigInt::from(exp.to_owned()))?,
+                false => self
+                    .neg()
+                    .integer_power(&BigInt::from(exp.to_owned()))?
+                    .neg(),
+            }
+        } else {
+            let left = power_bound(&-&self.lo, exp, up);
+            let right = power_bound(&self.hi, exp, up);
+
+            match even {
+                true => Self::new(BigDecimal::zero(), left.max(right)),
+                false => Self::new(-left, right),
+            }
+        };
+
+        Some(res)
+    }
+
+    fn power(&self, exp: &Object) -> Option<Self> {
+        match exp.as_rational() {
+            Some(exp) if exp.is_integer() => self.integer_power(&exp.to_integer()),
+            Some(exp) if exp == BigRational::new(1.into(), 2.into()) => apply("sqrt", self),
+            _ => {
+                let exp = Self::from_object(exp)?;
+                apply("exp", &apply("ln", self)?.mul(&exp))
+            }
+        }
+    }
+
+    /// Applies a function that never decreases.
+    fn increasing(&self, name: &str) -> Option<Self> {
+        let (lo, _) = approximate_bounds(|| numeric::apply(name, &self.lo)?.as_decimal())?;
+        let (_, hi) = approximate_bounds(|| numeric::apply(name, &self.hi)?.as_decimal())?;
+
+        Some(Self::new(lo, hi))
+    }
+
+    /// The sine is monotonic between its extremes at `pi/2 + k*pi`, so it
+    /// is bounded by its values at the ends and at the extremes that could
+    /// be in the interval.
+    fn sine(&self) -> Option<Self> {
+        let at_lo = self.lo_point().increasing("sin")?;
+        let at_hi = self.hi_point().increasing("sin")?;
+        let mut res = Self::new(at_lo.lo.min(at_hi.lo), at_lo.hi.max(at_hi.hi));
+
+        let pi = Self::pi();
+        let half_pi = pi.mul(&Self::point(BigDecimal::new(5.into(), 1)));
+        let turns = self.sub(&half_pi).div(&pi)?;
+        let first = turns.lo.with_scale_round(0, RoundingMode::Floor);
+        let last = turns.hi.with_scale_round(0, RoundingMode::Ceiling);
+
+        if (&last - &first) > BigDecimal::from(3) {
+            return Some(Self::new(-BigDecimal::one(), BigDecimal::one()));
+        }
+
+        let mut k = first;
+        while k <= last {
+            let extreme = half_pi.add(&pi.mul(&Self::point(k.to_owned())));
+
+            if extreme.lo <= self.hi && self.lo <= extreme.hi {
+                match k.to_i64().is_some_and(|k| k % 2 == 0) {
+                    true => res.hi = BigDecimal::one(),
+                    false => res.lo = -BigDecimal::one(),
+                }
+            }
+            k += BigDecimal::one();
+        }
+
+        res.lo = res.lo.max(-BigDecimal::one());
+        res.hi = res.hi.min(BigDecimal::one());
+        Some(res)
+    }
+
+    fn lo_point(&self) -> Self {
+        Self::new(self.lo.to_owned(), self.lo.to_owned())
+    }
+
+    fn hi_point(&self) -> Self {
+        Self::new(self.hi.to_owned(), self.hi.to_owned())
+    }
+}
+
+/// Applies an elementary function to every number in an interval, or gives
+/// `None` if some of them are outside of its domain.
+pub fn apply(name: &str, x: &Interval) -> Option<Interval> {
+    match name {
+        "sqrt" if !x.lo.is_negative() => {
+            let mut res = x.increasing("sqrt")?;
+            res.lo = res.lo.max(BigDecimal::zero());
+            Some(res)
+        }
+        "ln" if x.lo.is_positive() => x.increasing("ln"),
+        "exp" | "atan" => x.increasing(name),
+        "sin" => x.sine(),
+        "cos" => x
+            .add(&Interval::pi().mul(&Interval::point(BigDecimal::new(5.into(), 1))))
+            .sine(),
+        "tan" => apply("sin", x)?.div(&apply("cos", x)?),
+        _ => None,
+    }
+}
+
+pub fn coerced(obj: Object) -> Option<Object> {
+    Interval::from_object(&obj).map(Object::Interval)
+}
+
+impl InfixOperable for Interval {
+    fn sum(&self, other: &Object) -> Option<Object> {
+        Some(Object::Interval(self.add(&Interval::from_object(other)?)))
+    }
+
+    fn substraction(&self, other: &Object) -> Option<Object> {
+        Some(Object::Interval(self.sub(&Interval::from_object(other)?)))
+    }
+
+    fn product(&self, other: &Object) -> Option<Object> {
+        Some(Object::Interval(self.mul(&Interval::from_object(other)?)))
+    }
+
+    fn over(&self, other: &Object) -> Option<Object> {
+        self.div(&Interval::from_object(other)?)
+            .map(Object::Interval)
+    }
+
+    fn pow(&self, other: &Object) -> Option<Object> {
+        self.power(other).map(Object::Interval)
+    }
+
+    /// Two intervals are surely equal only when both are the same point,
+    /// and surely different when they don't overlap.
+    fn equality(&self, other: &Object) -> Option<Object> {
+        let other = Interval::from_object(other)?;
+        Some(decided(
+            self.lo == self.hi && other.lo == other.hi && self.lo == other.lo,
+            self.hi < other.lo || other.hi < self.lo,
+        ))
+    }
+
+    fn neq(&self, other: &Object) -> Option<Object> {
+        self.equality(other)?.logic_not()
+    }
+
+    fn less(&self, other: &Object) -> Option<Object> {
+        let other = Interval::from_object(other)?;
+        Some(decided(self.hi < other.lo, self.lo >= other.hi))
+    }
+
+    fn less_equal(&self, other: &Object) -> Option<Object> {
+        let other = Interval::from_object(other)?;
+        Some(decided(self.hi <= other.lo, self.lo > other.hi))
+    }
+
+    fn greater(&self, other: &Object) -> Option<Object> {
+        let other = Interval::from_object(other)?;
+        Some(decided(self.lo > other.hi, self.hi <= other.lo))
+    }
+
+    fn greater_equal(&self, other: &Object) -> Option<Object> {
+        let other = Interval::from_object(other)?;
+        Some(decided(self.lo >= other.hi, self.hi < other.lo))
+    }
+
+    fn contains(&self, other: &Object) -> Option<Object> {
+        let other = Interval::from_object(other)?;
+        Some(decided(
+            self.lo <= other.lo && other.hi <= self.hi,
+            other.hi < self.lo || self.hi < other.lo,
+        ))
+    }
+}
+
+impl PrefixOperable for Interval {
+    fn inverse(&self) -> Option<Object> {
+        Some(Object::Interval(self.neg()))
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "interval({}, {})", self.lo, self.hi)
+    }
+}
+
+fn interval_of(func: &str, obj: &Object) -> Result<Interval, EvalError> {
+    Interval::from_object(obj).ok_or_else(|| EvalError::bad_argument(func, "Number", obj))
+}
+
+/// The interval with every number between the two arguments, or an
+/// interval that contains the value of the only one.
+pub fn interval(args: &[Object]) -> Result<Object, EvalError> {
+    let lo = interval_of("interval", &args[0])?;
+
+    let res = match args.get(1) {
+        Some(obj) => {
+            let hi = interval_of("interval", obj)?;
+            if hi.hi < lo.lo {
+                return Err(EvalError::bad_argument(
+                    "interval",
+                    "greater upper bound",
+                    obj,
+                ));
+            }
+
+            Interval::new(lo.lo, hi.hi)
+        }
+        None => lo,
+    };
+
+    Ok(Object::Interval(res))
+}
+
+fn endpoint(
+    func: &str,
+    obj: &Object,
+    end: fn(Interval) -> BigDecimal,
+) -> Result<Object, EvalError> {
+    match obj {
+        Object::Interval(x) => Ok(Object::Decimal(Decimal::unrounded(end(x.to_owned())))),
+        obj => Err(EvalError::bad_argument(func, "Interval", obj)),
+    }
+}
+
+pub fn lower(args: &[Object]) -> Result<Object, EvalError> {
+    endpoint("lower", &args[0], |x| x.lo)
+}
+
+pub fn upper(args: &[Object]) -> Result<Object, EvalError> {
+    endpoint("upper", &args[0], |x| x.hi)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{object::Fraction, testing::int};
+
+    use super::*;
+
+    fn dec(val: &str) -> BigDecimal {
+        val.parse().unwrap()
+    }
+
+    fn between(lo: &str, hi: &str) -> Interval {
+        Interval::new(dec(lo), dec(hi))
+    }
+
+    #[test]
+    fn outward_rounding() {
+        let third = Interval::from_object(&Object::Fraction(Fraction::_new(1, 3))).unwrap();
+
+        assert!(third.lo < third.hi);
+        assert!(rational(&third.lo) < BigRational::new(1.into(), 3.into()));
+        assert!(rational(&third.hi) > BigRational::new(1.into(), 3.into()));
+        assert_eq!(Interval::from_object(&int(2)).unwrap(), between("2", "2"));
+    }
+
+    #[test]
+    fn arithmetic() {
+        let x = between("-1", "2");
+        let y = between("3", "4");
+
+        assert_eq!(x.add(&y), between("2", "6"));
+        assert_eq!(x.sub(&y), between("-5", "-1"));
+        assert_eq!(x.mul(&y), between("-4", "8"));
+        assert_eq!(x.integer_power(&2.into()), Some(between("0", "4")));
+        assert_eq!(x.integer_power(&3.into()), Some(between("-1", "8")));
+        assert_eq!(x.recip(), None);
+    }
+
+    #[test]
+    fn comparisons() {
+        let x = Object::Interval(between("1", "2"));
+
+        assert_eq!(x.less(&int(3)), Some(true.into()));
+        assert_eq!(x.greater(&int(3)), Some(false.into()));
+        assert_eq!(
+            x.less(&Object::Decimal(Decimal::new("1", "5"))),
+            Some(unknown())
+        );
+        assert_eq!(x.less_equal(&int(1)), Some(unknown()));
+    }
+
+    #[test]
+    fn equality() {
+        let x = Object::Interval(between("1", "2"));
+        let point = Object::Interval(between("1", "1"));
+
+        assert_eq!(x.equality(&x), Some(unknown()));
+        assert_eq!(x.equality(&int(3)), Some(false.into()));
+        assert_eq!(x.neq(&int(3)), Some(true.into()));
+        assert_eq!(point.equality(&int(1)), Some(true.into()));
+        assert_eq!(int(1).equality(&point), Some(true.into()));
+    }
+
+    #[test]
+    fn elementary_functions() {
+        let sqrt_two = apply("sqrt", &Interval::from_object(&int(2)).unwrap()).unwrap();
+        let square = sqrt_two.integer_power(&2.into()).unwrap();
+        assert!(square.lo < dec("2") && dec("2") < square.hi);
+
+        let sine = apply("sin", &between("1", "2")).unwrap();
+        assert_eq!(sine.hi, BigDecimal::one());
+        assert!(sine.lo > dec("0.84") && sine.lo < dec("0.842"));
+    }
+}
@@ -75,6 +75,7 @@ pub enum TokenType {
     GreaterEqual,
     Ident(String),
     If,
+    Imaginary(String),
     Import,
     In,
     Indent,
@@ -90,6 +91,7 @@ pub enum TokenType {
     Lparen,
     Memoize,
     Minus,
+    Mod,
     Newline,
     Percent,
     NotEqual,
@@ -108,7 +110,9 @@ pub enum TokenType {
     True,
     Unknown,
     Var,
+    Where,
     Wildcard,
+    With,
 }
 
 pub struct Lexer<'a> {
@@ -116,6 +120,10 @@ pub struct Lexer<'a> {
     cur_pos: usize,
     token_queue: VecDeque<Result<Token, Error>>,
     indent_level: usize,
+    /// Whether the last token was `..`. An open range can end a line, so a
+    /// line break after one becomes a newline even at the top level, where
+    /// lines are otherwise not separated.
+    after_dot_dot: bool,
 }
 
 impl Iterator for Lexer<'_> {
@@ -130,7 +138,10 @@ impl Iterator for Lexer<'_> {
 
         let start = self.cur_pos;
 
-        let res = match self.next_token() {
+        let token = self.next_token();
+        self.after_dot_dot = matches!(token, Some(Ok(TokenType::DotDot)));
+
+        let res = match token {
             Some(Ok(token)) => Some(Ok(Token::new(
                 token,
                 Position::new(start, self.cur_pos - start),
@@ -164,6 +175,7 @@ impl<'a> From<&'a str> for Lexer<'a> {
             cur_pos: 0,
             token_queue: VecDeque::default(),
             indent_level: 0,
+            after_dot_dot: false,
         }
     }
 }
@@ -230,6 +242,11 @@ impl<'a> Lexer<'a> {
                     let indent_res = self.emit_indents();
 
                     match indent_res {
+                        IndentLevel::Zero if self.indent_level == 0 => {
+                            if self.after_dot_dot && self.input.peek().is_some() {
+                                self.push_newline(newline_pos);
+                            }
+                        }
                         IndentLevel::Zero => {
                             self.push_dedents(self.indent_level);
                             self.indent_level = 0;
@@ -404,9 +421,12 @@ impl<'a> Lexer<'a> {
             "in" => Some(TokenType::In),
             "let" => Some(TokenType::Let),
             "memoize" => Some(TokenType::Memoize),
+            "mod" => Some(TokenType::Mod),
             "then" => Some(TokenType::Then),
             "true" => Some(TokenType::True),
             "var" => Some(TokenType::Var),
+            "where" => Some(TokenType::Where),
+            "with" => Some(TokenType::With),
             _ => None,
         }
     }
@@ -463,9 +483,21 @@ impl<'a> Lexer<'a> {
         match (literal.chars().next(), radix) {
             (None, _) => Err(LexerError::EmptyPrefixedInteger),
             (Some('0'), Radix::Decimal) if literal.len() > 1 => Err(LexerError::LeadingZeros),
+            (_, Radix::Decimal) if self.imaginary_suffix() => Ok(TokenType::Imaginary(literal)),
             _ => Ok(TokenType::Integer(literal, radix)),
         }
     }
+
+    /// Consumes the `i` right after a number, unless it starts an identifier.
+    fn imaginary_suffix(&mut self) -> bool {
+        let mut ahead = self.input.clone();
+        if ahead.next() != Some('i') || ahead.next().is_some_and(|c| c.is_alphanumeric()) {
+            return false;
+        }
+
+        self.next_char();
+        true
+    }
 }
 
 #[cfg(test)]
@@ -958,6 +990,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn newline_after_open_range() {
+        assert_eq!(
+            token_types_from("a..\n\nb\n"),
+            Ok(vec![
+                TokenType::Ident("a".into()),
+                TokenType::DotDot,
+                TokenType::Newline,
+                TokenType::Ident("b".into()),
+            ])
+        );
+    }
+
+    #[test]
+    fn no_top_level_newline() {
+        assert_eq!(
+            token_types_from("a\n\nb\n"),
+            Ok(vec![
+                TokenType::Ident("a".into()),
+                TokenType::Ident("b".into())
+            ])
+        );
+        assert_eq!(
+            token_types_from("a..b\nc"),
+            Ok(vec![
+                TokenType::Ident("a".into()),
+                TokenType::DotDot,
+                TokenType::Ident("b".into()),
+                TokenType::Ident("c".into()),
+            ])
+        );
+    }
+
+    #[test]
+    fn indented_open_range() {
+        assert_eq!(
+            token_types_from("a\n    b..\n    c"),
+            Ok(vec![
+                TokenType::Ident("a".into()),
+                TokenType::Indent,
+                TokenType::Ident("b".into()),
+                TokenType::DotDot,
+                TokenType::Newline,
+                TokenType::Ident("c".into()),
+                TokenType::Dedent,
+            ])
+        );
+    }
+
     #[test]
     fn integer_position() {
         let code = "0x8";
@@ -974,6 +1055,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn imaginary() {
+        assert_eq!(
+            token_types_from("3i + 2.5i - 1in"),
+            Ok(vec![
+                TokenType::Imaginary(String::from("3")),
+                TokenType::Plus,
+                TokenType::Integer(String::from("2"), Radix::Decimal),
+                TokenType::Dot,
+                TokenType::Imaginary(String::from("5")),
+                TokenType::Minus,
+                TokenType::Integer(String::from("1"), Radix::Decimal),
+                TokenType::In,
+            ]),
+        );
+    }
+
     #[test]
     fn two_line_indent() {
         let code = &unindent(
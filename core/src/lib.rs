@@ -1,14 +1,35 @@
+mod approximation;
 mod ast;
 pub mod builtin;
+mod calculus;
+mod complex;
 mod cst;
+mod digits;
+mod elementary;
 pub mod env;
 pub mod error;
 pub mod exec;
+mod factor;
+mod interval;
 mod lexer;
 mod matcher;
+mod matrix;
+mod numeric;
+mod numtheory;
 pub mod object;
 mod parser;
+mod polynomial;
+mod properties;
+mod random;
 #[cfg(feature = "repl")]
 pub mod repl;
 pub mod run;
+mod series;
+pub mod simplify;
+mod solve;
+mod stream;
+mod sums;
+pub mod symbolic;
+#[cfg(test)]
+mod testing;
 mod weeder;
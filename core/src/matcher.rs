@@ -91,6 +91,10 @@ pub fn match_(pattern: &ASTNode, val: &Object) -> Option<Match> {
         } => match_range(lhs, rhs, val),
         ASTNodeKind::SetCons { some, most } => set_cons(some, most, val),
         ASTNodeKind::Fraction { numer, denom } => fraction(numer, denom, val),
+        ASTNodeKind::Modular {
+            val: residue,
+            modulus,
+        } => modular(residue, modulus, val),
         _ => match_constant(pattern, val),
     }
 }
@@ -254,6 +258,18 @@ fn fraction(numer: &ASTNode, denom: &ASTNode, val: &Object) -> Option<Match> {
     }
 }
 
+fn modular(val: &ASTNode, modulus: &ASTNode, obj: &Object) -> Option<Match> {
+    match obj {
+        Object::Modular(modular) => {
+            let val_obj = Object::Integer(modular.val.to_owned().into());
+            let modulus_obj = Object::Integer(modular.modulus.to_owned().into());
+
+            join(match_(val, &val_obj), match_(modulus, &modulus_obj))
+        }
+        _ => None,
+    }
+}
+
 fn match_constant(pattern: &ASTNode, val: &Object) -> Option<Match> {
     if isolated_unchecked_exec(pattern) == *val {
         empty_match()
@@ -271,10 +287,10 @@ mod tests {
     use crate::{
         ast::tests::{
             ad_infinitum, cons, dec_integer, dictionary, extension_list, extension_set, fraction,
-            pattern, range, set_cons, string, symbol, wildcard,
+            modular, pattern, range, set_cons, string, symbol, wildcard,
         },
         cst::tests::dummy_pos,
-        object::{Dictionary, Fraction, Integer, Range, Set, Symbol},
+        object::{Dictionary, Fraction, Integer, Modular, Range, Set, Symbol},
     };
 
     use super::*;
@@ -498,6 +514,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn modular_() {
+        let pattern = modular(
+            symbol("val", dummy_pos()),
+            dec_integer("7", dummy_pos()),
+            dummy_pos(),
+        );
+
+        let value = Object::Modular(Modular::new(&10.into(), &7.into()));
+
+        assert_eq!(
+            match_(&pattern, &value),
+            single_match("val", &Object::Integer(3.into()))
+        );
+        assert_eq!(
+            match_(
+                &pattern,
+                &Object::Modular(Modular::new(&3.into(), &5.into()))
+            ),
+            None
+        );
+    }
+
     #[test]
     fn full_set() {
         let pattern = extension_set(
@@ -0,0 +1,605 @@
+use std::fmt;
+
+use bigdecimal::{Signed, ToPrimitive};
+use num_bigint::BigInt;
+
+use crate::{
+    exec::EvalError,
+    object::{InfixOperable, Integer, List, Object, PrefixOperable},
+    simplify::simplify,
+};
+
+/// A matrix with at least one row and one column. Vectors are matrices
+/// with a single column.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Matrix {
+    rows: Vec<Vec<Object>>,
+}
+
+fn is_entry(obj: &Object) -> bool {
+    obj.is_numeric() || obj.is_symbolic()
+}
+
+/// Entries are kept in their simplest form, so that equal matrices look
+/// the same.
+fn entry(obj: Object) -> Object {
+    match obj.as_rational() {
+        Some(val) => Object::from_rational(val),
+        None if obj.is_symbolic() => simplify(&obj),
+        None => obj,
+    }
+}
+
+fn is_zero(obj: &Object) -> bool {
+    obj.is_zero() || (obj.is_symbolic() && simplify(obj).is_zero())
+}
+
+// Operations between entries only fail when dividing by zero.
+
+fn sum(lhs: &Object, rhs: &Object) -> Object {
+    entry(lhs.sum(rhs).unwrap())
+}
+
+fn sub(lhs: &Object, rhs: &Object) -> Object {
+    entry(lhs.substraction(rhs).unwrap())
+}
+
+fn mul(lhs: &Object, rhs: &Object) -> Object {
+    entry(lhs.product(rhs).unwrap())
+}
+
+fn div(numer: &Object, denom: &Object) -> Option<Object> {
+    if is_zero(denom) {
+        return None;
+    }
+
+    match (numer.as_rational(), denom.as_rational()) {
+        (Some(numer), Some(denom)) => Some(Object::from_rational(numer / denom)),
+        _ => Some(entry(numer.over(denom)?)),
+    }
+}
+
+fn neg(obj: &Object) -> Object {
+    entry(obj.inverse().unwrap())
+}
+
+/// The result of Gauss-Jordan elimination.
+struct Reduction {
+    /// The reduced row echelon form.
+    matrix: Matrix,
+    /// The columns with a leading one, in order.
+    pivots: Vec<usize>,
+    /// The product of the pivots before scaling them, with the sign of the
+    /// row swaps. It is the determinant for square, invertible matrices.
+    scale: Object,
+}
+
+impl Matrix {
+    fn new(rows: Vec<Vec<Object>>) -> Self {
+        let rows = rows
+            .into_iter()
+            .map(|row| row.into_iter().map(entry).collect())
+            .collect();
+
+        Self { rows }
+    }
+
+    /// A matrix from a list of rows, or a column vector from a list of
+    /// entries.
+    pub fn from_object(obj: &Object) -> Option<Self> {
+        let list = match obj {
+            Object::Matrix(matrix) => return Some(matrix.to_owned()),
+            Object::List(list) if !list.list.is_empty() => &list.list,
+            _ => return None,
+        };
+
+        let rows: Vec<Vec<Object>> = match &list[0] {
+            Object::List(_) => list
+                .iter()
+                .map(|row| match row {
+                    Object::List(row) => Some(row.list.to_owned()),
+                    _ => None,
+                })
+                .collect::<Option<_>>()?,
+            _ => list.iter().map(|obj| vec![obj.to_owned()]).collect(),
+        };
+
+        let columns = rows[0].len();
+        let valid = columns > 0
+            && rows
+                .iter()
+                .all(|row| row.len() == columns && row.iter().all(is_entry));
+
+        valid.then(|| Self::new(rows))
+    }
+
+    pub fn identity(size: usize) -> Self {
+        let rows = (0..size)
+            .map(|i| (0..size).map(|j| Object::int(i32::from(i == j))).collect())
+            .collect();
+
+        Self { rows }
+    }
+
+    fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn width(&self) -> usize {
+        self.rows[0].len()
+    }
+
+    fn is_square(&self) -> bool {
+        self.height() == self.width()
+    }
+
+    fn is_exact(&self) -> bool {
+        self.rows
+            .iter()
+            .flatten()
+            .all(|entry| entry.as_rational().is_some())
+    }
+
+    fn map(&self, f: impl Fn(&Object) -> Object) -> Self {
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| row.iter().map(&f).collect())
+            .collect();
+
+        Self { rows }
+    }
+
+    pub fn transpose(&self) -> Self {
+        let rows = (0..self.width())
+            .map(|j| self.rows.iter().map(|row| row[j].to_owned()).collect())
+            .collect();
+
+        Self { rows }
+    }
+
+    fn entrywise(&self, other: &Self, op: fn(&Object, &Object) -> Object) -> Option<Self> {
+        if (self.height(), self.width()) != (other.height(), other.width()) {
+            return None;
+        }
+
+        let rows = self
+            .rows
+            .iter()
+            .zip(&other.rows)
+            .map(|(lhs, rhs)| lhs.iter().zip(rhs).map(|(a, b)| op(a, b)).collect())
+            .collect();
+
+        Some(Self { rows })
+    }
+
+    fn mul(&self, other: &Self) -> Option<Self> {
+        if self.width() != other.height() {
+            return None;
+        }
+
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| {
+                (0..other.width())
+                    .map(|j| {
+                        row.iter()
+                            .zip(&other.rows)
+                            .fold(Object::int(0), |acc, (a, other_row)| {
+                                sum(&acc, &mul(a, &other_row[j]))
+                            })
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Some(Self { rows })
+    }
+
+    /// The product with a scalar, on either side.
+    pub fn scaled(&self, scalar: &Object) -> Option<Self> {
+        match is_entry(scalar) {
+            true => Some(self.map(|entry| mul(scalar, entry))),
+            false => None,
+        }
+    }
+
+    fn power(&self, exp: &BigInt) -> Option<Self> {
+        if !self.is_square() {
+            return None;
+        }
+
+        let mut base = match exp.is_negative() {
+            true => self.inverted()?,
+            false => self.to_owned(),
+        };
+        let mut exp = exp.abs().to_u64()?;
+        let mut res = Self::identity(self.height());
+
+        while exp > 0 {
+            if exp % 2 == 1 {
+                res = res.mul(&base)?;
+            }
+            base = base.mul(&base)?;
+            exp /= 2;
+        }
+
+        Some(res)
+    }
+
+    fn reduction(&self) -> Reduction {
+        let mut rows = self.rows.to_owned();
+        let mut pivots = vec![];
+        let mut scale = Object::int(1);
+
+        for col in 0..self.width() {
+            let top = pivots.len();
+            let Some(found) = (top..rows.len()).find(|&i| !is_zero(&rows[i][col])) else {
+                continue;
+            };
+
+            if found != top {
+                rows.swap(found, top);
+                scale = neg(&scale);
+            }
+
+            let pivot = rows[top][col].to_owned();
+            scale = mul(&scale, &pivot);
+            rows[top] = rows[top]
+                .iter()
+                .map(|entry| div(entry, &pivot).unwrap())
+                .collect();
+
+            for i in 0..rows.len() {
+                let factor = rows[i][col].to_owned();
+                if i == top || is_zero(&factor) {
+                    continue;
+                }
+
+                rows[i] = rows[i]
+                    .iter()
+                    .zip(&rows[top])
+                    .map(|(entry, pivot_entry)| sub(entry, &mul(&factor, pivot_entry)))
+                    .collect();
+            }
+
+            pivots.push(col);
+        }
+
+        Reduction {
+            matrix: Self { rows },
+            pivots,
+            scale,
+        }
+    }
+
+    pub fn rref(&self) -> Self {
+        self.reduction().matrix
+    }
+
+    pub fn rank(&self) -> usize {
+        self.reduction().pivots.len()
+    }
+
+    /// The minor without row `i` and column `j`.
+    fn minor(&self, i: usize, j: usize) -> Self {
+        let rows = self
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(row, _)| *row != i)
+            .map(|(_, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(col, _)| *col != j)
+                    .map(|(_, entry)| entry.to_owned())
+                    .collect()
+            })
+            .collect();
+
+        Self { rows }
+    }
+
+    fn cofactor(&self, i: usize, j: usize) -> Object {
+        let det = self.minor(i, j).cofactor_expansion();
+
+        match (i + j) % 2 {
+            0 => det,
+            _ => neg(&det),
+        }
+    }
+
+    /// The determinant by expansion along the first row. Unlike
+    /// elimination, it never divides, so symbolic results stay polynomial.
+    fn cofactor_expansion(&self) -> Object {
+        if self.height() == 1 {
+            return self.rows[0][0].to_owned();
+        }
+
+        (0..self.width()).fold(Object::int(0), |acc, j| {
+            sum(&acc, &mul(&self.rows[0][j], &self.cofactor(0, j)))
+        })
+    }
+
+    pub fn determinant(&self) -> Option<Object> {
+        if !self.is_square() {
+            return None;
+        }
+
+        if !self.is_exact() {
+            return Some(self.cofactor_expansion());
+        }
+
+        let reduction = self.reduction();
+        match reduction.pivots.len() == self.height() {
+            true => Some(reduction.scale),
+            false => Some(Object::int(0)),
+        }
+    }
+
+    pub fn inverted(&self) -> Option<Self> {
+        if !self.is_square() {
+            return None;
+        }
+
+        if !self.is_exact() {
+            let det = self.determinant()?;
+            let size = self.height();
+            let rows = (0..size)
+                .map(|i| {
+                    (0..size)
+                        .map(|j| div(&self.cofactor(j, i), &det))
+                        .collect::<Option<_>>()
+                })
+                .collect::<Option<_>>()?;
+
+            return Some(Self { rows });
+        }
+
+        let size = self.height();
+        let augmented = Self {
+            rows: self
+                .rows
+                .iter()
+                .zip(Self::identity(size).rows)
+                .map(|(row, identity)| row.iter().cloned().chain(identity).collect())
+                .collect(),
+        };
+
+        let reduction = augmented.reduction();
+        if reduction.pivots.iter().copied().take(size).ne(0..size) {
+            return None;
+        }
+
+        let rows = reduction
+            .matrix
+            .rows
+            .into_iter()
+            .map(|row| row[size..].to_vec())
+            .collect();
+
+        Some(Self { rows })
+    }
+
+    /// A basis of the vectors `v` such that `self*v` is zero.
+    pub fn nullspace(&self) -> Vec<Self> {
+        let Reduction { matrix, pivots, .. } = self.reduction();
+
+        (0..self.width())
+            .filter(|col| !pivots.contains(col))
+            .map(|free| {
+                let mut vector = vec![Object::int(0); self.width()];
+                vector[free] = Object::int(1);
+
+                for (row, &pivot) in pivots.iter().enumerate() {
+                    vector[pivot] = neg(&matrix.rows[row][free]);
+                }
+
+                Self {
+                    rows: vector.into_iter().map(|entry| vec![entry]).collect(),
+                }
+            })
+            .collect()
+    }
+}
+
+impl InfixOperable for Matrix {
+    fn sum(&self, other: &Object) -> Option<Object> {
+        match other {
+            Object::Matrix(other) => self.entrywise(other, sum).map(Object::Matrix),
+            _ => None,
+        }
+    }
+
+    fn substraction(&self, other: &Object) -> Option<Object> {
+        match other {
+            Object::Matrix(other) => self.entrywise(other, sub).map(Object::Matrix),
+            _ => None,
+        }
+    }
+
+    fn product(&self, other: &Object) -> Option<Object> {
+        match other {
+            Object::Matrix(other) => self.mul(other).map(Object::Matrix),
+            scalar => self.scaled(scalar).map(Object::Matrix),
+        }
+    }
+
+    fn over(&self, other: &Object) -> Option<Object> {
+        match is_entry(other) {
+            true => Some(Object::Matrix(self.map(|entry| div(entry, other).unwrap()))),
+            false => None,
+        }
+    }
+
+    fn pow(&self, other: &Object) -> Option<Object> {
+        match other {
+            Object::Integer(_) => self
+                .power(&other.as_rational()?.to_integer())
+                .map(Object::Matrix),
+            _ => None,
+        }
+    }
+}
+
+impl PrefixOperable for Matrix {
+    fn inverse(&self) -> Option<Object> {
+        Some(Object::Matrix(self.map(neg)))
+    }
+}
+
+impl fmt::Display for Matrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| {
+                let row = row
+                    .iter()
+                    .map(|entry| entry.to_string())
+                    .collect::<Vec<_>>();
+                format!("[{}]", row.join(", "))
+            })
+            .collect::<Vec<_>>();
+
+        write!(f, "matrix([{}])", rows.join(", "))
+    }
+}
+
+fn matrix_arg(func: &str, obj: &Object) -> Result<Matrix, EvalError> {
+    Matrix::from_object(obj).ok_or_else(|| EvalError::bad_argument(func, "Matrix", obj))
+}
+
+fn square_arg(func: &str, obj: &Object) -> Result<Matrix, EvalError> {
+    match matrix_arg(func, obj)? {
+        matrix if matrix.is_square() => Ok(matrix),
+        _ => Err(EvalError::bad_argument(func, "square Matrix", obj)),
+    }
+}
+
+pub fn matrix(args: &[Object]) -> Result<Object, EvalError> {
+    Ok(Object::Matrix(matrix_arg("matrix", &args[0])?))
+}
+
+pub fn identity(args: &[Object]) -> Result<Object, EvalError> {
+    match args[0].as_rational() {
+        Some(size) if matches!(args[0], Object::Integer(_)) && size.is_positive() => Ok(
+            Object::Matrix(Matrix::identity(size.to_integer().to_usize().unwrap())),
+        ),
+        _ => Err(EvalError::bad_argument(
+            "identity",
+            "positive Integer",
+            &args[0],
+        )),
+    }
+}
+
+pub fn det(args: &[Object]) -> Result<Object, EvalError> {
+    Ok(square_arg("det", &args[0])?.determinant().unwrap())
+}
+
+pub fn inverse(args: &[Object]) -> Result<Object, EvalError> {
+    square_arg("inverse", &args[0])?
+        .inverted()
+        .map(Object::Matrix)
+        .ok_or_else(|| EvalError::bad_argument("inverse", "invertible Matrix", &args[0]))
+}
+
+pub fn rank(args: &[Object]) -> Result<Object, EvalError> {
+    Ok(Object::Integer(Integer::from(
+        matrix_arg("rank", &args[0])?.rank(),
+    )))
+}
+
+pub fn transpose(args: &[Object]) -> Result<Object, EvalError> {
+    Ok(Object::Matrix(
+        matrix_arg("transpose", &args[0])?.transpose(),
+    ))
+}
+
+pub fn rref(args: &[Object]) -> Result<Object, EvalError> {
+    Ok(Object::Matrix(matrix_arg("rref", &args[0])?.rref()))
+}
+
+pub fn nullspace(args: &[Object]) -> Result<Object, EvalError> {
+    let basis = matrix_arg("nullspace", &args[0])?
+        .nullspace()
+        .into_iter()
+        .map(Object::Matrix)
+        .collect::<Vec<_>>();
+
+    Ok(Object::List(List::from(basis)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        object::{Fraction, Symbol},
+        testing::int,
+    };
+
+    use super::*;
+
+    fn matrix(rows: Vec<Vec<i32>>) -> Matrix {
+        Matrix::new(
+            rows.into_iter()
+                .map(|row| row.into_iter().map(Object::int).collect())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn products() {
+        let a = matrix(vec![vec![1, 2], vec![3, 4]]);
+        let b = matrix(vec![vec![0, 1], vec![1, 0]]);
+
+        assert_eq!(a.mul(&b), Some(matrix(vec![vec![2, 1], vec![4, 3]])));
+        assert_eq!(
+            a.power(&BigInt::from(2)),
+            Some(matrix(vec![vec![7, 10], vec![15, 22]]))
+        );
+        assert_eq!(a.mul(&matrix(vec![vec![1, 2, 3]])), None);
+    }
+
+    #[test]
+    fn exact_inverse() {
+        let a = matrix(vec![vec![2, 1], vec![5, 3]]);
+
+        assert_eq!(a.determinant(), Some(int(1)));
+        assert_eq!(a.inverted(), Some(matrix(vec![vec![3, -1], vec![-5, 2]])));
+        assert_eq!(a.inverted().unwrap().mul(&a), Some(Matrix::identity(2)));
+
+        let half = Object::Fraction(Fraction::_new(1, 2));
+        let b = matrix(vec![vec![2, 0], vec![0, 1]]);
+        assert_eq!(b.inverted().unwrap().rows[0][0], half);
+        assert_eq!(matrix(vec![vec![1, 2], vec![2, 4]]).inverted(), None);
+    }
+
+    #[test]
+    fn elimination() {
+        let a = matrix(vec![vec![1, 2, 3], vec![2, 4, 6], vec![1, 0, 1]]);
+
+        assert_eq!(a.rank(), 2);
+        assert_eq!(a.determinant(), Some(int(0)));
+        assert_eq!(
+            a.rref(),
+            matrix(vec![vec![1, 0, 1], vec![0, 1, 1], vec![0, 0, 0]])
+        );
+        assert_eq!(
+            a.nullspace(),
+            vec![matrix(vec![vec![-1], vec![-1], vec![1]])]
+        );
+    }
+
+    #[test]
+    fn symbolic_determinant() {
+        let symbol = |name: &str| Object::Symbol(Symbol::new(name.into(), "Real".into()));
+        let a = Matrix::new(vec![
+            vec![symbol("a"), symbol("b")],
+            vec![symbol("c"), symbol("d")],
+        ]);
+
+        assert_eq!(a.determinant().unwrap().to_string(), "a*d - b*c");
+    }
+}
@@ -0,0 +1,367 @@
+use std::num::NonZeroU64;
+
+use bigdecimal::{BigDecimal, Context, One, RoundingMode, Signed, ToPrimitive, Zero};
+use num_bigint::BigInt;
+use num_integer::Integer as _;
+
+use crate::{
+    object::{Decimal, Object, Precision},
+    symbolic::Expression,
+};
+
+/// Past this many guard digits, two approximations that still round
+/// differently are taken as good enough.
+const MAX_GUARD_DIGITS: u64 = 640;
+
+/// A working precision, in significant digits.
+#[derive(Clone, Copy)]
+struct Work(u64);
+
+impl Work {
+    fn context(self) -> Context {
+        Context::new(NonZeroU64::new(self.0).unwrap(), RoundingMode::HalfEven)
+    }
+
+    fn more(self, digits: u64) -> Self {
+        Work(self.0 + digits)
+    }
+
+    fn trunc(self, val: BigDecimal) -> BigDecimal {
+        if val.digits() > self.0 {
+            val.with_precision_round(NonZeroU64::new(self.0).unwrap(), RoundingMode::HalfEven)
+        } else {
+            val
+        }
+    }
+
+    fn div(self, numer: &BigDecimal, denom: &BigDecimal) -> BigDecimal {
+        self.trunc(numer * denom.inverse_with_context(&self.context()))
+    }
+
+    /// Whether adding `term` to `sum` would not change its digits.
+    fn negligible(self, term: &BigDecimal, sum: &BigDecimal) -> bool {
+        term.is_zero() || term.abs() * BigDecimal::new(1.into(), -(self.0 as i64)) < sum.abs()
+    }
+}
+
+/// The number of digits in the integer part of `val`.
+fn integer_digits(val: &BigDecimal) -> u64 {
+    (val.digits() as i64 - val.fractional_digit_count()).max(0) as u64
+}
+
+fn small() -> BigDecimal {
+    BigDecimal::new(1.into(), 2)
+}
+
+/// Evaluates `approximation` with more and more guard digits, until two of
+/// them round to the same value with the current precision.
+fn correctly_rounded(approximation: impl Fn(Work) -> Option<BigDecimal>) -> Option<BigDecimal> {
+    let precision = Precision::current();
+    let digits = precision.digits.get();
+
+    let mut guard = 10;
+    let mut last = precision.round(approximation(Work(digits + guard))?);
+
+    loop {
+        guard *= 2;
+        let next = precision.round(approximation(Work(digits + guard))?);
+
+        if next == last || guard > MAX_GUARD_DIGITS {
+            return Some(next);
+        }
+
+        last = next;
+    }
+}
+
+fn sqrt_in(val: &BigDecimal, work: Work) -> Option<BigDecimal> {
+    val.sqrt_with_context(&work.context())
+}
+
+fn exp_in(val: &BigDecimal, work: Work) -> BigDecimal {
+    // exp(x) = exp(x/2**k)**(2**k), so the series is summed for a small
+    // argument and the digits lost when squaring are added beforehand
+    let mut arg = val.to_owned();
+    let mut halvings = 0;
+    while arg.abs() > small() {
+        arg = work.trunc(arg * BigDecimal::new(5.into(), 1));
+        halvings += 1;
+    }
+
+    let work = work.more(halvings / 3 + 2);
+    let mut sum = BigDecimal::one();
+    let mut term = BigDecimal::one();
+    let mut n = 1;
+    loop {
+        term = work.div(&(term * &arg), &BigDecimal::from(n));
+        if work.negligible(&term, &sum) {
+            break;
+        }
+
+        sum += &term;
+        n += 1;
+    }
+
+    for _ in 0..halvings {
+        sum = work.trunc(&sum * &sum);
+    }
+
+    sum
+}
+
+/// The series `x + x**3/3 + x**5/5 + ...` if `sign` is one, and the
+/// alternating one if it is minus one. These are `atanh` and `atan`.
+fn odd_series(val: &BigDecimal, sign: i32, work: Work) -> BigDecimal {
+    let square = work.trunc(val * val) * BigDecimal::from(sign);
+    let mut power = val.to_owned();
+    let mut sum = val.to_owned();
+    let mut n = 3;
+    loop {
+        power = work.trunc(power * &square);
+        let term = work.div(&power, &BigDecimal::from(n));
+        if work.negligible(&term, &sum) {
+            return sum;
+        }
+
+        sum += term;
+        n += 2;
+    }
+}
+
+fn ln_in(val: &BigDecimal, work: Work) -> Option<BigDecimal> {
+    if !val.is_positive() {
+        return None;
+    }
+
+    // ln(x) = 2**k*ln(x**(1/2**k)), and ln(y) = 2*atanh((y - 1)/(y + 1))
+    let inner = work.more(20);
+    let mut arg = val.to_owned();
+    let mut roots = 0;
+    while (&arg - BigDecimal::one()).abs() > small() {
+        arg = sqrt_in(&arg, inner)?;
+        roots += 1;
+    }
+
+    let ratio = inner.div(&(&arg - BigDecimal::one()), &(&arg + BigDecimal::one()));
+    let res = odd_series(&ratio, 1, inner) * BigDecimal::from(2_u64.pow(roots + 1));
+
+    Some(work.trunc(res))
+}
+
+fn pi_in(work: Work) -> BigDecimal {
+    // Machin's formula: pi = 16*atan(1/5) - 4*atan(1/239)
+    let work = work.more(5);
+    let fifth = work.div(&BigDecimal::one(), &BigDecimal::from(5));
+    let inverse = work.div(&BigDecimal::one(), &BigDecimal::from(239));
+
+    work.trunc(
+        odd_series(&fifth, -1, work) * BigDecimal::from(16)
+            - odd_series(&inverse, -1, work) * BigDecimal::from(4),
+    )
+}
+
+/// The sine and the cosine of a small argument.
+fn sin_cos_series(val: &BigDecimal, work: Work) -> (BigDecimal, BigDecimal) {
+    let square = -work.trunc(val * val);
+
+    let mut sin = val.to_owned();
+    let mut cos = BigDecimal::one();
+    let mut sin_term = val.to_owned();
+    let mut cos_term = BigDecimal::one();
+    let mut n: u64 = 1;
+    loop {
+        cos_term = work.div(
+            &(cos_term * &square),
+            &BigDecimal::from((2 * n - 1) * 2 * n),
+        );
+        sin_term = work.div(
+            &(sin_term * &square),
+            &BigDecimal::from(2 * n * (2 * n + 1)),
+        );
+        if work.negligible(&cos_term, &cos) && work.negligible(&sin_term, &sin) {
+            return (sin, cos);
+        }
+
+        sin += &sin_term;
+        cos += &cos_term;
+        n += 1;
+    }
+}
+
+/// The sine and the cosine, after taking the argument to `[-pi/4, pi/4]`.
+fn sin_cos_in(val: &BigDecimal, work: Work) -> (BigDecimal, BigDecimal) {
+    let work = work.more(integer_digits(val) + 5);
+    let half_pi = pi_in(work) * BigDecimal::new(5.into(), 1);
+
+    let quadrant = work
+        .div(val, &half_pi)
+        .with_scale_round(0, RoundingMode::HalfEven);
+    let arg = work.trunc(val - &quadrant * &half_pi);
+    let (sin, cos) = sin_cos_series(&arg, work);
+
+    let (quadrant, _) = quadrant.as_bigint_and_exponent();
+
+    match quadrant.mod_floor(&BigInt::from(4)).to_u8() {
+        Some(0) => (sin, cos),
+        Some(1) => (cos, -sin),
+        Some(2) => (-sin, -cos),
+        _ => (-cos, sin),
+    }
+}
+
+fn atan_in(val: &BigDecimal, work: Work) -> BigDecimal {
+    if val.abs() > BigDecimal::one() {
+        let half_pi = pi_in(work) * BigDecimal::new(5.into(), 1);
+        let complement = atan_in(&work.div(&BigDecimal::one(), val), work);
+
+        return match val.is_negative() {
+            true => -half_pi - complement,
+            false => half_pi - complement,
+        };
+    }
+
+    // atan(x) = 2*atan(x/(1 + sqrt(1 + x**2)))
+    let mut arg = val.to_owned();
+    let mut halvings = 0;
+    while arg.abs() > BigDecimal::new(1.into(), 1) {
+        let root = sqrt_in(&(BigDecimal::one() + &arg * &arg), work).unwrap();
+        arg = work.div(&arg, &(BigDecimal::one() + root));
+        halvings += 1;
+    }
+
+    odd_series(&arg, -1, work) * BigDecimal::from(2_u64.pow(halvings))
+}
+
+fn decimal(val: BigDecimal) -> Object {
+    Object::Decimal(Decimal::from(val))
+}
+
+pub fn pi() -> Object {
+    decimal(correctly_rounded(|work| Some(pi_in(work))).unwrap())
+}
+
+/// Applies an elementary function to a decimal, or gives `None` if it is
+/// outside of its domain.
+pub fn apply(name: &str, val: &BigDecimal) -> Option<Object> {
+    let res = match name {
+        "sqrt" => correctly_rounded(|work| sqrt_in(val, work)),
+        "exp" => correctly_rounded(|work| Some(exp_in(val, work))),
+        "ln" => correctly_rounded(|work| ln_in(val, work)),
+        "sin" => correctly_rounded(|work| Some(sin_cos_in(val, work).0)),
+        "cos" => correctly_rounded(|work| Some(sin_cos_in(val, work).1)),
+        "tan" => correctly_rounded(|work| {
+            let (sin, cos) = sin_cos_in(val, work);
+            Some(work.div(&sin, &cos))
+        }),
+        "atan" => correctly_rounded(|work| Some(atan_in(val, work))),
+        _ => None,
+    }?;
+
+    Some(decimal(res))
+}
+
+/// Raises a positive decimal to a decimal power, as `exp(exp*ln(base))`.
+pub fn power(base: &BigDecimal, exp: &BigDecimal) -> Option<Object> {
+    if base.is_zero() && exp.is_positive() {
+        return Some(decimal(BigDecimal::zero()));
+    }
+
+    let res = correctly_rounded(|work| {
+        let inner = work.more(integer_digits(exp) + 5);
+        let exponent = inner.trunc(ln_in(base, inner)? * exp);
+        Some(exp_in(&exponent, work.more(integer_digits(&exponent))))
+    })?;
+
+    Some(decimal(res))
+}
+
+/// The value of an expression without variables, like `2*sqrt(3)` or `pi`.
+pub fn value(obj: &Object) -> Option<BigDecimal> {
+    match obj {
+        obj if obj.is_numeric() => obj.as_decimal(),
+        Object::Symbol(symbol) if symbol.property == "Pi" => pi().as_decimal(),
+        Object::Expression(Expression::Sum(terms)) => terms
+            .iter()
+            .try_fold(BigDecimal::zero(), |acc, term| Some(acc + value(term)?)),
+        Object::Expression(Expression::Product(factors)) => factors
+            .iter()
+            .try_fold(BigDecimal::one(), |acc, factor| Some(acc * value(factor)?)),
+        Object::Expression(Expression::Power(base, exp)) => {
+            let base = decimal(value(base)?);
+
+            match exp.as_rational() {
+                Some(exp) if exp.is_integer() => {
+                    base.pow(&Object::from_rational(exp))?.as_decimal()
+                }
+                _ => power(&base.as_decimal()?, &value(exp)?)?.as_decimal(),
+            }
+        }
+        Object::Expression(Expression::Call(name, args)) if args.len() == 1 => {
+            apply(name, &value(&args[0])?)?.as_decimal()
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::object::Rounding;
+
+    use super::*;
+
+    fn with_digits(digits: u64) {
+        Precision::new(NonZeroU64::new(digits).unwrap(), Rounding::HalfEven).install();
+    }
+
+    fn dec(val: &str) -> BigDecimal {
+        val.parse().unwrap()
+    }
+
+    #[test]
+    fn constants() {
+        with_digits(30);
+
+        assert_eq!(pi().to_string(), "3.14159265358979323846264338328");
+        assert_eq!(
+            apply("exp", &BigDecimal::one()).unwrap().to_string(),
+            "2.71828182845904523536028747135"
+        );
+    }
+
+    #[test]
+    fn elementary_values() {
+        with_digits(20);
+
+        assert_eq!(
+            apply("sqrt", &dec("2")).unwrap().to_string(),
+            "1.4142135623730950488"
+        );
+        assert_eq!(
+            apply("ln", &dec("10")).unwrap().to_string(),
+            "2.3025850929940456840"
+        );
+        assert_eq!(
+            apply("sin", &dec("1")).unwrap().to_string(),
+            "0.84147098480789650665"
+        );
+        assert_eq!(
+            apply("cos", &dec("100")).unwrap().to_string(),
+            "0.86231887228768393410"
+        );
+        assert_eq!(
+            apply("atan", &dec("-3")).unwrap().to_string(),
+            "-1.2490457723982544258"
+        );
+        assert_eq!(apply("ln", &dec("-1")), None);
+    }
+
+    #[test]
+    fn decimal_power() {
+        with_digits(20);
+
+        assert_eq!(
+            power(&dec("2"), &dec("0.5")).unwrap().to_string(),
+            "1.4142135623730950488"
+        );
+    }
+}
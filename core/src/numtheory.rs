@@ -0,0 +1,358 @@
+use std::collections::BTreeMap;
+
+use bigdecimal::{num_traits::Pow, One, Signed, ToPrimitive, Zero};
+use num_bigint::BigInt;
+use num_integer::Integer as _;
+
+use crate::{
+    exec::EvalError,
+    object::{List, Object},
+};
+
+const SMALL_PRIMES: [u32; 25] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+];
+
+/// The Miller-Rabin test with the primes below 100 as bases. It has no
+/// false positives below `3.3*10**24`, and is very unlikely to have them
+/// above.
+pub fn is_prime(n: &BigInt) -> bool {
+    if *n < BigInt::from(2) {
+        return false;
+    }
+
+    for prime in SMALL_PRIMES {
+        if *n == BigInt::from(prime) {
+            return true;
+        }
+
+        if (n % prime).is_zero() {
+            return false;
+        }
+    }
+
+    let predecessor: BigInt = n - 1;
+    let twos = predecessor.trailing_zeros().unwrap_or_default();
+    let odd_part = &predecessor >> twos;
+
+    SMALL_PRIMES.iter().all(|&base| {
+        let mut val = BigInt::from(base).modpow(&odd_part, n);
+        if val.is_one() || val == predecessor {
+            return true;
+        }
+
+        for _ in 1..twos {
+            val = &val * &val % n;
+            if val == predecessor {
+                return true;
+            }
+        }
+
+        false
+    })
+}
+
+/// Looks for a factor of a composite number with Brent's variant of
+/// Pollard's rho method, iterating `x**2 + c`.
+fn pollard_rho(n: &BigInt, c: &BigInt) -> Option<BigInt> {
+    const BATCH: u64 = 128;
+
+    let next = |x: &BigInt| (x * x + c) % n;
+
+    let mut y = BigInt::from(2);
+    let mut x = y.to_owned();
+    let mut saved = y.to_owned();
+    let mut product = BigInt::one();
+    let mut divisor = BigInt::one();
+    let mut length: u64 = 1;
+
+    while divisor.is_one() {
+        x = y.to_owned();
+        for _ in 0..length {
+            y = next(&y);
+        }
+
+        let mut steps = 0;
+        while steps < length && divisor.is_one() {
+            saved = y.to_owned();
+            for _ in 0..BATCH.min(length - steps) {
+                y = next(&y);
+                product = product * (&x - &y).abs() % n;
+            }
+
+            divisor = product.gcd(n);
+            steps += BATCH;
+        }
+
+        length *= 2;
+    }
+
+    // The batch went past the factor, so it is found again step by step
+    if divisor == *n {
+        loop {
+            saved = next(&saved);
+            divisor = (&x - &saved).abs().gcd(n);
+            if !divisor.is_one() {
+                break;
+            }
+        }
+    }
+
+    (divisor != *n).then_some(divisor)
+}
+
+/// The prime factorization of a nonzero integer. Small factors are found
+/// by trial division, and the rest with Pollard's rho method.
+pub fn integer_factors(n: &BigInt) -> Vec<(BigInt, usize)> {
+    let mut n = n.abs();
+    if n <= BigInt::one() {
+        return if n.is_zero() { vec![(n, 1)] } else { vec![] };
+    }
+
+    let mut factors: BTreeMap<BigInt, usize> = BTreeMap::new();
+
+    for prime in SMALL_PRIMES {
+        while (&n % prime).is_zero() {
+            n /= prime;
+            *factors.entry(BigInt::from(prime)).or_default() += 1;
+        }
+    }
+
+    let mut pending = vec![n];
+    while let Some(n) = pending.pop() {
+        if n.is_one() {
+            continue;
+        }
+
+        if is_prime(&n) {
+            *factors.entry(n).or_default() += 1;
+            continue;
+        }
+
+        let mut c = BigInt::one();
+        let divisor = loop {
+            match pollard_rho(&n, &c) {
+                Some(divisor) => break divisor,
+                None => c += 1,
+            }
+        };
+
+        pending.push(&n / &divisor);
+        pending.push(divisor);
+    }
+
+    factors.into_iter().collect()
+}
+
+/// The positive divisors of a nonzero integer, in no particular order.
+pub fn divisors(n: &BigInt) -> Vec<BigInt> {
+    let mut res = vec![BigInt::one()];
+
+    for (prime, multiplicity) in integer_factors(n) {
+        let mut powers = vec![];
+        let mut power = BigInt::one();
+
+        for _ in 0..multiplicity {
+            power *= &prime;
+            powers.extend(res.iter().map(|divisor| divisor * &power));
+        }
+
+        res.extend(powers);
+    }
+
+    res
+}
+
+fn totient_of(n: &BigInt) -> BigInt {
+    integer_factors(n)
+        .into_iter()
+        .map(|(prime, multiplicity)| (&prime - 1) * Pow::pow(&prime, multiplicity - 1))
+        .product()
+}
+
+/// The Jacobi symbol `(a/n)`, for an odd positive `n`.
+fn jacobi_symbol(a: &BigInt, n: &BigInt) -> i32 {
+    let mut a = a.mod_floor(n);
+    let mut n = n.to_owned();
+    let mut res = 1;
+
+    while !a.is_zero() {
+        while a.is_even() {
+            a >>= 1;
+            if matches!((&n % 8_u32).to_u32(), Some(3 | 5)) {
+                res = -res;
+            }
+        }
+
+        std::mem::swap(&mut a, &mut n);
+        if (&a % 4_u32).to_u32() == Some(3) && (&n % 4_u32).to_u32() == Some(3) {
+            res = -res;
+        }
+
+        a = a.mod_floor(&n);
+    }
+
+    if n.is_one() {
+        res
+    } else {
+        0
+    }
+}
+
+pub fn inverse_mod(a: &BigInt, modulus: &BigInt) -> Option<BigInt> {
+    let res = a.extended_gcd(modulus);
+
+    match res.gcd.is_one() {
+        true => Some(res.x.mod_floor(modulus)),
+        false => None,
+    }
+}
+
+fn integer(func: &str, obj: &Object) -> Result<BigInt, EvalError> {
+    match obj {
+        Object::Integer(_) => Ok(obj.as_rational().unwrap().to_integer()),
+        obj => Err(EvalError::bad_argument(func, "Integer", obj)),
+    }
+}
+
+/// An integer argument that has to be positive.
+fn positive(func: &str, obj: &Object) -> Result<BigInt, EvalError> {
+    match integer(func, obj)? {
+        n if n.is_positive() => Ok(n),
+        _ => Err(EvalError::bad_argument(func, "positive Integer", obj)),
+    }
+}
+
+pub fn is_prime_extern(args: &[Object]) -> Result<Object, EvalError> {
+    Ok(is_prime(&integer("isPrime", &args[0])?).into())
+}
+
+pub fn gcd(args: &[Object]) -> Result<Object, EvalError> {
+    let lhs = integer("gcd", &args[0])?;
+    let rhs = integer("gcd", &args[1])?;
+
+    Ok(Object::int(lhs.gcd(&rhs)))
+}
+
+pub fn lcm(args: &[Object]) -> Result<Object, EvalError> {
+    let lhs = integer("lcm", &args[0])?;
+    let rhs = integer("lcm", &args[1])?;
+
+    Ok(Object::int(lhs.lcm(&rhs)))
+}
+
+pub fn modpow(args: &[Object]) -> Result<Object, EvalError> {
+    let base = integer("modpow", &args[0])?;
+    let exp = integer("modpow", &args[1])?;
+    let modulus = positive("modpow", &args[2])?;
+
+    let base = match exp.is_negative() {
+        true => inverse_mod(&base, &modulus)
+            .ok_or_else(|| EvalError::bad_argument("modpow", "invertible Integer", &args[0]))?,
+        false => base,
+    };
+
+    Ok(Object::int(base.modpow(&exp.abs(), &modulus)))
+}
+
+pub fn modinv(args: &[Object]) -> Result<Object, EvalError> {
+    let val = integer("modinv", &args[0])?;
+    let modulus = positive("modinv", &args[1])?;
+
+    match inverse_mod(&val, &modulus) {
+        Some(inverse) => Ok(Object::int(inverse)),
+        None => Err(EvalError::bad_argument(
+            "modinv",
+            "invertible Integer",
+            &args[0],
+        )),
+    }
+}
+
+pub fn totient(args: &[Object]) -> Result<Object, EvalError> {
+    Ok(Object::int(totient_of(&positive("totient", &args[0])?)))
+}
+
+pub fn divisors_extern(args: &[Object]) -> Result<Object, EvalError> {
+    let n = integer("divisors", &args[0])?;
+    if n.is_zero() {
+        return Err(EvalError::bad_argument(
+            "divisors",
+            "nonzero Integer",
+            &args[0],
+        ));
+    }
+
+    let mut res = divisors(&n);
+    res.sort();
+
+    Ok(Object::List(List::from(
+        res.into_iter().map(Object::int).collect::<Vec<_>>(),
+    )))
+}
+
+pub fn jacobi(args: &[Object]) -> Result<Object, EvalError> {
+    let a = integer("jacobi", &args[0])?;
+    let n = positive("jacobi", &args[1])?;
+    if n.is_even() {
+        return Err(EvalError::bad_argument("jacobi", "odd Integer", &args[1]));
+    }
+
+    Ok(Object::int(BigInt::from(jacobi_symbol(&a, &n))))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::int;
+
+    use super::*;
+
+    fn big(val: &str) -> BigInt {
+        val.parse().unwrap()
+    }
+
+    #[test]
+    fn primality() {
+        let primes: Vec<u32> = (0..60).filter(|n| is_prime(&BigInt::from(*n))).collect();
+
+        assert_eq!(
+            primes,
+            vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59]
+        );
+        assert!(is_prime(&big("170141183460469231731687303715884105727")));
+        // A Carmichael number
+        assert!(!is_prime(&big("561")));
+        assert!(!is_prime(&big("3825123056546413051")));
+    }
+
+    #[test]
+    fn large_factorization() {
+        let n = big("1000000016000000063");
+
+        assert_eq!(
+            integer_factors(&n),
+            vec![(big("1000000007"), 1), (big("1000000009"), 1)]
+        );
+        assert_eq!(
+            integer_factors(&big("-360")),
+            vec![(big("2"), 3), (big("3"), 2), (big("5"), 1)]
+        );
+    }
+
+    #[test]
+    fn arithmetic_functions() {
+        assert_eq!(totient_of(&big("36")), big("12"));
+        assert_eq!(jacobi_symbol(&big("1001"), &big("9907")), -1);
+        assert_eq!(jacobi_symbol(&big("19"), &big("45")), 1);
+        assert_eq!(jacobi_symbol(&big("6"), &big("9")), 0);
+        assert_eq!(inverse_mod(&big("3"), &big("7")), Some(big("5")));
+        assert_eq!(inverse_mod(&big("2"), &big("4")), None);
+    }
+
+    #[test]
+    fn sorted_divisors() {
+        let res = divisors_extern(&[int(12)]).unwrap();
+
+        assert_eq!(res.to_string(), "[1, 2, 3, 4, 6, 12]");
+    }
+}
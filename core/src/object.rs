@@ -1,25 +1,35 @@
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
     cmp::min,
     collections::{BTreeMap, BTreeSet},
     fmt,
     hash::Hash,
     iter::zip,
+    num::NonZeroU64,
     rc::Rc,
     vec,
 };
 
-use bigdecimal::{num_traits::Pow, BigDecimal, One, Signed, Zero};
+use bigdecimal::{num_traits::Pow, BigDecimal, One, RoundingMode, Signed, Zero};
 use num_bigint::{BigInt, BigUint};
+use num_integer::Integer as _;
 use num_rational::BigRational;
 
 use crate::{
     ast::ASTNode,
-    env::Environment,
+    complex::{self, Complex},
+    env::{EnvResponse, Environment},
     error::{Error, Position},
-    exec::{exec, EvalError},
+    exec::{apply, check_arguments, exec, exec_tail, holds, symbol, EvalError, Tail, TailCall},
+    interval::{self, Interval},
     lexer::Radix,
     matcher::{match_call, Match},
+    matrix::Matrix,
+    numeric, numtheory,
+    polynomial::{self, Polynomial},
+    properties,
+    stream::Stream,
+    symbolic::{self, Expression},
 };
 
 macro_rules! default_infix_method {
@@ -91,7 +101,13 @@ pub enum Object {
     Integer(Integer),
     Decimal(Decimal),
     Fraction(Fraction),
+    Complex(Complex),
+    Interval(Interval),
+    Modular(Modular),
     Symbol(Symbol),
+    Expression(Expression),
+    Polynomial(Polynomial),
+    Matrix(Matrix),
     String(MyString),
     Tuple(Tuple),
     List(List),
@@ -99,6 +115,7 @@ pub enum Object {
     Dictionary(Dictionary),
     Function(Function),
     Range(Range),
+    Stream(Stream),
     Error(FailedAssertion),
 }
 
@@ -107,18 +124,88 @@ impl Object {
         Self::Tuple(Tuple::from(vec![]))
     }
 
+    pub fn int(val: impl Into<Integer>) -> Self {
+        Self::Integer(val.into())
+    }
+
     pub fn is_zero(&self) -> bool {
         match self {
-            Object::Integer(Integer { val }) => val.is_zero(),
+            Object::Integer(Integer { val, .. }) => val.is_zero(),
             Object::Decimal(Decimal { val }) => val.is_zero(),
             Object::Fraction(Fraction { val }) => val.is_zero(),
+            Object::Polynomial(poly) => poly.is_zero(),
+            Object::Modular(Modular { val, .. }) => val.is_zero(),
+            Object::Complex(z) => z.is_zero(),
+            _ => false,
+        }
+    }
+
+    pub fn is_one(&self) -> bool {
+        match self {
+            Object::Integer(Integer { val, .. }) => val.is_one(),
+            Object::Decimal(Decimal { val }) => val.is_one(),
+            Object::Fraction(Fraction { val }) => val.is_one(),
             _ => false,
         }
     }
 
+    pub fn is_minus_one(&self) -> bool {
+        match self {
+            Object::Integer(Integer { val, .. }) => (-val).is_one(),
+            Object::Decimal(Decimal { val }) => (-val).is_one(),
+            Object::Fraction(Fraction { val }) => (-val).is_one(),
+            _ => false,
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        match self {
+            Object::Integer(Integer { val, .. }) => val.is_negative(),
+            Object::Decimal(Decimal { val }) => val.is_negative(),
+            Object::Fraction(Fraction { val }) => val.is_negative(),
+            _ => false,
+        }
+    }
+
+    pub fn is_symbolic(&self) -> bool {
+        matches!(self, Object::Symbol(_) | Object::Expression(_))
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            Object::Integer(_) | Object::Decimal(_) | Object::Fraction(_)
+        )
+    }
+
+    pub fn as_rational(&self) -> Option<BigRational> {
+        match self {
+            Object::Integer(Integer { val, .. }) => Some(BigRational::from_integer(val.to_owned())),
+            Object::Fraction(Fraction { val }) => Some(val.to_owned()),
+            _ => None,
+        }
+    }
+
+    pub fn as_decimal(&self) -> Option<BigDecimal> {
+        match self {
+            Object::Integer(Integer { val, .. }) => Some(BigDecimal::new(val.to_owned(), 0)),
+            Object::Decimal(Decimal { val }) => Some(val.to_owned()),
+            Object::Fraction(frac) => Some(Decimal::from(frac).val),
+            _ => None,
+        }
+    }
+
+    pub fn from_rational(val: BigRational) -> Self {
+        if val.is_integer() {
+            Object::Integer(Integer::from(val.to_integer()))
+        } else {
+            Object::Fraction(Fraction::from(val))
+        }
+    }
+
     pub fn has_property(&self, prop: &str) -> bool {
         match self {
-            Object::Symbol(symbol) => symbol.property == prop,
+            Object::Symbol(symbol) => properties::implies(&symbol.property, prop),
             _ => prop == self.kind(),
         }
     }
@@ -135,15 +222,22 @@ impl fmt::Display for Object {
         match self {
             Object::Boolean(boolean) => boolean.fmt(f),
             Object::Char(chr) => chr.fmt(f),
+            Object::Complex(z) => z.fmt(f),
+            Object::Interval(x) => x.fmt(f),
             Object::Decimal(dec) => dec.fmt(f),
             Object::Dictionary(dict) => dict.fmt(f),
             Object::Error(err) => err.fmt(f),
+            Object::Expression(expr) => expr.fmt(f),
             Object::List(list) => list.fmt(f),
             Object::Set(es) => es.fmt(f),
             Object::Fraction(frac) => frac.fmt(f),
             Object::Function(func) => func.fmt(f),
             Object::Integer(int) => int.fmt(f),
+            Object::Matrix(matrix) => matrix.fmt(f),
+            Object::Modular(modular) => modular.fmt(f),
+            Object::Polynomial(poly) => poly.fmt(f),
             Object::Range(range) => range.fmt(f),
+            Object::Stream(stream) => stream.fmt(f),
             Object::String(str) => str.fmt(f),
             Object::Symbol(s) => s.fmt(f),
             Object::Tuple(s) => s.fmt(f),
@@ -160,15 +254,22 @@ impl Kind for Object {
         match self {
             Object::Boolean(_) => "Boolean",
             Object::Char(_) => "Character",
+            Object::Complex(_) => "Complex",
+            Object::Interval(_) => "Interval",
             Object::Decimal(_) => "Decimal",
             Object::Dictionary(_) => "Dictionary",
             Object::Error(_) => "Error",
+            Object::Expression(_) => "Expression",
             Object::List(_) => "List",
             Object::Set(_) => "Set",
             Object::Fraction(_) => "Fraction",
             Object::Function(_) => "Function",
             Object::Integer(_) => "Integer",
+            Object::Matrix(_) => "Matrix",
+            Object::Modular(_) => "Modular",
+            Object::Polynomial(_) => "Polynomial",
             Object::Range(_) => "Range",
+            Object::Stream(_) => "Stream",
             Object::String(_) => "String",
             Object::Symbol(_) => "Symbol",
             Object::Tuple(_) => "Tuple",
@@ -183,15 +284,22 @@ macro_rules! derived_object_infix_trait {
             match self {
                 Self::Boolean(left) => left.$ident(other),
                 Self::Char(left) => left.$ident(other),
+                Self::Complex(left) => left.$ident(other),
                 Self::Decimal(left) => left.$ident(other),
                 Self::Dictionary(left) => left.$ident(other),
                 Self::Error(left) => left.$ident(other),
+                Self::Expression(left) => left.$ident(other),
                 Self::List(left) => left.$ident(other),
                 Self::Set(left) => left.$ident(other),
                 Self::Fraction(left) => left.$ident(other),
                 Self::Function(left) => left.$ident(other),
                 Self::Integer(left) => left.$ident(other),
+                Self::Interval(left) => left.$ident(other),
+                Self::Matrix(left) => left.$ident(other),
+                Self::Modular(left) => left.$ident(other),
+                Self::Polynomial(left) => left.$ident(other),
                 Self::Range(left) => left.$ident(other),
+                Self::Stream(left) => left.$ident(other),
                 Self::String(left) => left.$ident(other),
                 Self::Symbol(left) => left.$ident(other),
                 Self::Tuple(left) => left.$ident(other),
@@ -233,10 +341,34 @@ derived_object_infix_traits!(
 
 impl Object {
     pub fn equality(&self, other: &Object) -> Option<Object> {
+        if let (Object::Interval(x), other) | (other, Object::Interval(x)) = (self, other) {
+            if let Some(res) = x.equality(other) {
+                return Some(res);
+            }
+        }
+
+        if self.is_symbolic() || other.is_symbolic() {
+            if let Some(res) = symbolic::equation(self.to_owned(), other.to_owned()) {
+                return Some(res);
+            }
+        }
+
         Some(Object::Boolean((self == other).into()))
     }
 
     pub fn neq(&self, other: &Object) -> Option<Object> {
+        if let (Object::Interval(x), other) | (other, Object::Interval(x)) = (self, other) {
+            if let Some(res) = x.neq(other) {
+                return Some(res);
+            }
+        }
+
+        if self.is_symbolic() || other.is_symbolic() {
+            if let Some(res) = symbolic::inequation(self.to_owned(), other.to_owned()) {
+                return Some(res);
+            }
+        }
+
         Some(Object::Boolean((self != other).into()))
     }
 }
@@ -247,15 +379,22 @@ macro_rules! derived_object_prefix_trait {
             match self {
                 Self::Boolean(left) => left.$ident(),
                 Self::Char(left) => left.$ident(),
+                Self::Complex(left) => left.$ident(),
                 Self::Decimal(left) => left.$ident(),
                 Self::Dictionary(left) => left.$ident(),
                 Self::Error(left) => left.$ident(),
+                Self::Expression(left) => left.$ident(),
                 Self::List(left) => left.$ident(),
                 Self::Set(left) => left.$ident(),
                 Self::Fraction(left) => left.$ident(),
                 Self::Function(left) => left.$ident(),
                 Self::Integer(left) => left.$ident(),
+                Self::Interval(left) => left.$ident(),
+                Self::Matrix(left) => left.$ident(),
+                Self::Modular(left) => left.$ident(),
+                Self::Polynomial(left) => left.$ident(),
                 Self::Range(left) => left.$ident(),
+                Self::Stream(left) => left.$ident(),
                 Self::String(left) => left.$ident(),
                 Self::Symbol(left) => left.$ident(),
                 Self::Tuple(left) => left.$ident(),
@@ -277,20 +416,30 @@ macro_rules! derived_object_prefix_traits {
 
 derived_object_prefix_traits!(bitwise_not, logic_not, inverse);
 
+/// A truth value, which is `None` when it is unknown, like a comparison of
+/// intervals that overlap.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Bool {
-    val: bool,
+    val: Option<bool>,
 }
 
 impl Bool {
-    pub fn value(&self) -> bool {
+    pub fn unknown() -> Self {
+        Self { val: None }
+    }
+
+    pub fn value(&self) -> Option<bool> {
         self.val
     }
 }
 
 impl fmt::Display for Bool {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let text = if self.val { "true" } else { "false" };
+        let text = match self.val {
+            Some(true) => "true",
+            Some(false) => "false",
+            None => "unknown",
+        };
 
         write!(f, "{}", text)
     }
@@ -299,14 +448,26 @@ impl fmt::Display for Bool {
 impl InfixOperable for Bool {
     fn logic_and(&self, other: &Object) -> Option<Object> {
         match other {
-            Object::Boolean(boolean) => Some(Object::Boolean(Bool::from(self.val && boolean.val))),
+            Object::Boolean(boolean) => Some(Object::Boolean(Bool {
+                val: match (self.val, boolean.val) {
+                    (Some(false), _) | (_, Some(false)) => Some(false),
+                    (Some(true), Some(true)) => Some(true),
+                    _ => None,
+                },
+            })),
             _ => None,
         }
     }
 
     fn or(&self, other: &Object) -> Option<Object> {
         match other {
-            Object::Boolean(boolean) => Some(Object::Boolean(Bool::from(self.val || boolean.val))),
+            Object::Boolean(boolean) => Some(Object::Boolean(Bool {
+                val: match (self.val, boolean.val) {
+                    (Some(true), _) | (_, Some(true)) => Some(true),
+                    (Some(false), Some(false)) => Some(false),
+                    _ => None,
+                },
+            })),
             _ => None,
         }
     }
@@ -314,13 +475,15 @@ impl InfixOperable for Bool {
 
 impl PrefixOperable for Bool {
     fn logic_not(&self) -> Option<Object> {
-        Some(Object::Boolean(Bool::from(!self.val)))
+        Some(Object::Boolean(Bool {
+            val: self.val.map(|val| !val),
+        }))
     }
 }
 
 impl From<bool> for Bool {
     fn from(val: bool) -> Self {
-        Self { val }
+        Self { val: Some(val) }
     }
 }
 
@@ -379,6 +542,188 @@ impl From<char> for Char {
     }
 }
 
+/// How a `Decimal` is rounded when it has more digits than the precision.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Rounding {
+    Up,
+    Down,
+    Ceiling,
+    Floor,
+    HalfUp,
+    HalfDown,
+    HalfEven,
+}
+
+impl Rounding {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Up" => Some(Self::Up),
+            "Down" => Some(Self::Down),
+            "Ceiling" => Some(Self::Ceiling),
+            "Floor" => Some(Self::Floor),
+            "HalfUp" => Some(Self::HalfUp),
+            "HalfDown" => Some(Self::HalfDown),
+            "HalfEven" => Some(Self::HalfEven),
+            _ => None,
+        }
+    }
+
+    fn mode(self) -> RoundingMode {
+        match self {
+            Self::Up => RoundingMode::Up,
+            Self::Down => RoundingMode::Down,
+            Self::Ceiling => RoundingMode::Ceiling,
+            Self::Floor => RoundingMode::Floor,
+            Self::HalfUp => RoundingMode::HalfUp,
+            Self::HalfDown => RoundingMode::HalfDown,
+            Self::HalfEven => RoundingMode::HalfEven,
+        }
+    }
+}
+
+/// The number of significant digits kept by `Decimal` operations, and how
+/// the rest are rounded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Precision {
+    pub digits: NonZeroU64,
+    pub rounding: Rounding,
+}
+
+impl Default for Precision {
+    fn default() -> Self {
+        Self {
+            digits: NonZeroU64::new(100).unwrap(),
+            rounding: Rounding::HalfEven,
+        }
+    }
+}
+
+/// The precision of an execution context. Clones share it, so that a change
+/// made with `setPrecision` is seen by every environment of the same run.
+#[derive(Clone, Debug, Default)]
+pub struct SharedPrecision(Rc<Cell<Precision>>);
+
+impl SharedPrecision {
+    pub fn new(precision: Precision) -> Self {
+        Self(Rc::new(Cell::new(precision)))
+    }
+
+    pub fn get(&self) -> Precision {
+        self.0.get()
+    }
+
+    /// Makes this the precision `Decimal` operations use until the returned
+    /// guard is dropped.
+    pub fn activate(&self) -> ActivePrecision {
+        ActivePrecision(ACTIVE_PRECISION.with(|active| active.replace(self.clone())))
+    }
+}
+
+/// The precision is a setting of the run, not part of the functions whose
+/// environments carry it. It also changes with `setPrecision` while those
+/// functions are in sets, so every `SharedPrecision` compares equal.
+impl PartialEq for SharedPrecision {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for SharedPrecision {}
+
+impl Hash for SharedPrecision {
+    fn hash<H: std::hash::Hasher>(&self, _state: &mut H) {}
+}
+
+impl PartialOrd for SharedPrecision {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SharedPrecision {
+    fn cmp(&self, _other: &Self) -> std::cmp::Ordering {
+        std::cmp::Ordering::Equal
+    }
+}
+
+/// Puts the previously active precision back when dropped.
+pub struct ActivePrecision(SharedPrecision);
+
+impl Drop for ActivePrecision {
+    fn drop(&mut self) {
+        ACTIVE_PRECISION.with(|active| active.replace(self.0.clone()));
+    }
+}
+
+thread_local! {
+    static ACTIVE_PRECISION: RefCell<SharedPrecision> = RefCell::default();
+}
+
+fn digit_count(val: &BigInt) -> u64 {
+    val.magnitude().to_str_radix(10).len() as u64
+}
+
+/// Multiplies two decimals keeping the scale of both. `bigdecimal` drops
+/// the trailing zeros when a factor is one, so `1*2.0` would print as `2`.
+fn decimal_product(lhs: &BigDecimal, rhs: &BigDecimal) -> BigDecimal {
+    let (lhs, lhs_scale) = lhs.as_bigint_and_exponent();
+    let (rhs, rhs_scale) = rhs.as_bigint_and_exponent();
+
+    BigDecimal::new(lhs * rhs, lhs_scale + rhs_scale)
+}
+
+impl Precision {
+    pub fn new(digits: NonZeroU64, rounding: Rounding) -> Self {
+        Self { digits, rounding }
+    }
+
+    /// The precision `Decimal` operations are using.
+    pub fn current() -> Self {
+        ACTIVE_PRECISION.with(|active| active.borrow().get())
+    }
+
+    /// Makes this the precision of the active context from now on.
+    pub fn install(self) {
+        ACTIVE_PRECISION.with(|active| active.borrow().0.set(self));
+    }
+
+    fn with_extra_digits(self, extra: u64) -> Self {
+        Self {
+            digits: self.digits.saturating_add(extra),
+            ..self
+        }
+    }
+
+    pub fn round(self, val: BigDecimal) -> BigDecimal {
+        if val.digits() > self.digits.get() {
+            val.with_precision_round(self.digits, self.rounding.mode())
+        } else {
+            val
+        }
+    }
+
+    /// Divides two decimals, rounding the exact quotient. One more digit
+    /// than needed is computed, and a nonzero remainder is kept as a last
+    /// `1` so that the rounding can tell a tie from what is above it.
+    fn divide(self, numer: &BigDecimal, denom: &BigDecimal) -> BigDecimal {
+        let (numer, numer_scale) = numer.as_bigint_and_exponent();
+        let (denom, denom_scale) = denom.as_bigint_and_exponent();
+
+        let shift =
+            (self.digits.get() + 1 + digit_count(&denom)).saturating_sub(digit_count(&numer));
+        let (quotient, remainder) = (numer * BigInt::from(10).pow(shift)).div_rem(&denom);
+        let scale = numer_scale - denom_scale + shift as i64;
+
+        if remainder.is_zero() {
+            let val = self.round(BigDecimal::new(quotient, scale)).normalized();
+            val.with_scale(val.fractional_digit_count().max(0))
+        } else {
+            let sticky = &quotient * 10 + quotient.signum();
+            self.round(BigDecimal::new(sticky, scale + 1))
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Decimal {
     val: BigDecimal,
@@ -390,30 +735,44 @@ impl Decimal {
         Self { val }
     }
 
+    /// A decimal with all the digits of `val`, like a literal.
+    pub fn unrounded(val: BigDecimal) -> Self {
+        Self { val }
+    }
+
+    fn quotient(numer: &BigDecimal, denom: &BigDecimal) -> Self {
+        Self {
+            val: Precision::current().divide(numer, denom),
+        }
+    }
+
     fn binary_pow(&self, exponent: &Integer) -> Self {
         let zero = BigUint::zero();
         let one = BigUint::one();
         let two = &BigUint::from(2_u32);
 
+        // A few more digits for the intermediate products, so that the
+        // error does not reach the digits that are kept
+        let precision = Precision::current().with_extra_digits(10);
+
         let mut base = self.val.to_owned();
         let mut exp = exponent.val.magnitude().to_owned();
         let mut val = BigDecimal::one();
 
         while exp != zero {
             if &exp % two == one {
-                val *= &base;
+                val = precision.round(val * &base);
             }
-            base = &base * &base;
+            base = precision.round(&base * &base);
 
             exp = &exp / two;
         }
 
-        let must_invert = exponent.val.is_negative();
-        if must_invert {
-            val = val.inverse();
+        if exponent.val.is_negative() {
+            Self::quotient(&BigDecimal::one(), &val)
+        } else {
+            Self::from(val)
         }
-
-        Decimal { val }
     }
 }
 
@@ -425,10 +784,10 @@ impl PrefixOperable for Decimal {
 
 impl From<&Fraction> for Decimal {
     fn from(frac: &Fraction) -> Self {
-        let val = BigDecimal::new(frac.val.numer().to_owned(), 0)
-            / BigDecimal::new(frac.val.denom().to_owned(), 0);
-
-        Self { val }
+        Self::quotient(
+            &BigDecimal::new(frac.val.numer().to_owned(), 0),
+            &BigDecimal::new(frac.val.denom().to_owned(), 0),
+        )
     }
 }
 
@@ -438,12 +797,15 @@ impl InfixOperable for Decimal {
             Object::Decimal(Decimal { val }) => {
                 Some(Object::Decimal(Decimal::from(&self.val + val)))
             }
-            Object::Integer(Integer { val }) => {
+            Object::Integer(Integer { val, .. }) => {
                 Some(Object::Decimal(Decimal::from(&self.val + val)))
             }
             Object::Fraction(frac) => Some(Object::Decimal(Decimal::from(
                 &self.val + Decimal::from(frac).val,
             ))),
+            obj if obj.is_symbolic() => symbolic::sum(Object::Decimal(self.to_owned()), obj),
+            Object::Complex(_) => complex::coerced(Object::Decimal(self.to_owned()))?.sum(other),
+            Object::Interval(_) => interval::coerced(Object::Decimal(self.to_owned()))?.sum(other),
             _ => None,
         }
     }
@@ -453,27 +815,47 @@ impl InfixOperable for Decimal {
             Object::Decimal(Decimal { val }) => {
                 Some(Object::Decimal(Decimal::from(&self.val - val)))
             }
-            Object::Integer(Integer { val }) => {
+            Object::Integer(Integer { val, .. }) => {
                 Some(Object::Decimal(Decimal::from(&self.val - val)))
             }
             Object::Fraction(frac) => Some(Object::Decimal(Decimal::from(
                 &self.val - Decimal::from(frac).val,
             ))),
+            obj if obj.is_symbolic() => {
+                symbolic::substraction(Object::Decimal(self.to_owned()), obj)
+            }
+            Object::Complex(_) => {
+                complex::coerced(Object::Decimal(self.to_owned()))?.substraction(other)
+            }
+            Object::Interval(_) => {
+                interval::coerced(Object::Decimal(self.to_owned()))?.substraction(other)
+            }
             _ => None,
         }
     }
 
     fn product(&self, other: &Object) -> Option<Object> {
         match other {
-            Object::Decimal(Decimal { val }) => {
-                Some(Object::Decimal(Decimal::from(&self.val * val)))
+            Object::Decimal(Decimal { val }) => Some(Object::Decimal(Decimal::from(
+                decimal_product(&self.val, val),
+            ))),
+            Object::Integer(Integer { val, .. }) => Some(Object::Decimal(Decimal::from(
+                decimal_product(&self.val, &BigDecimal::from(val.to_owned())),
+            ))),
+            Object::Fraction(frac) => Some(Object::Decimal(Decimal::from(decimal_product(
+                &self.val,
+                &Decimal::from(frac).val,
+            )))),
+            obj if obj.is_symbolic() => symbolic::product(Object::Decimal(self.to_owned()), obj),
+            Object::Complex(_) => {
+                complex::coerced(Object::Decimal(self.to_owned()))?.product(other)
             }
-            Object::Integer(Integer { val }) => {
-                Some(Object::Decimal(Decimal::from(&self.val * val)))
+            Object::Interval(_) => {
+                interval::coerced(Object::Decimal(self.to_owned()))?.product(other)
             }
-            Object::Fraction(frac) => Some(Object::Decimal(Decimal::from(
-                &self.val * Decimal::from(frac).val,
-            ))),
+            Object::Matrix(matrix) => matrix
+                .scaled(&Object::Decimal(self.to_owned()))
+                .map(Object::Matrix),
             _ => None,
         }
     }
@@ -481,14 +863,19 @@ impl InfixOperable for Decimal {
     fn over(&self, other: &Object) -> Option<Object> {
         match other {
             Object::Decimal(Decimal { val }) => {
-                Some(Object::Decimal(Decimal::from(&self.val / val)))
+                Some(Object::Decimal(Decimal::quotient(&self.val, val)))
             }
-            Object::Integer(Integer { val }) => Some(Object::Decimal(Decimal::from(
-                &self.val / BigDecimal::new(val.clone(), 0),
+            Object::Integer(Integer { val, .. }) => Some(Object::Decimal(Decimal::quotient(
+                &self.val,
+                &BigDecimal::new(val.clone(), 0),
             ))),
-            Object::Fraction(frac) => Some(Object::Decimal(Decimal::from(
-                &self.val / Decimal::from(frac).val,
+            Object::Fraction(frac) => Some(Object::Decimal(Decimal::quotient(
+                &(&self.val * BigDecimal::new(frac.val.denom().to_owned(), 0)),
+                &BigDecimal::new(frac.val.numer().to_owned(), 0),
             ))),
+            obj if obj.is_symbolic() => symbolic::over(Object::Decimal(self.to_owned()), obj),
+            Object::Complex(_) => complex::coerced(Object::Decimal(self.to_owned()))?.over(other),
+            Object::Interval(_) => interval::coerced(Object::Decimal(self.to_owned()))?.over(other),
             _ => None,
         }
     }
@@ -496,6 +883,11 @@ impl InfixOperable for Decimal {
     fn pow(&self, other: &Object) -> Option<Object> {
         match other {
             Object::Integer(int) => Some(Object::Decimal(self.binary_pow(int))),
+            Object::Fraction(_) | Object::Decimal(_) => {
+                numeric::power(&self.val, &other.as_decimal()?)
+            }
+            obj if obj.is_symbolic() => symbolic::pow(Object::Decimal(self.to_owned()), obj),
+            Object::Complex(_) => complex::coerced(Object::Decimal(self.to_owned()))?.pow(other),
             _ => None,
         }
     }
@@ -507,9 +899,13 @@ impl fmt::Display for Decimal {
     }
 }
 
+/// Every operation that makes a `Decimal` goes through here, so its result
+/// is rounded to the current precision.
 impl From<BigDecimal> for Decimal {
     fn from(val: BigDecimal) -> Self {
-        Self { val }
+        Self {
+            val: Precision::current().round(val),
+        }
     }
 }
 
@@ -685,16 +1081,45 @@ impl fmt::Display for Dictionary {
 impl InfixOperable for Dictionary {}
 impl PrefixOperable for Dictionary {}
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+/// An integer, which remembers the radix of the literal it comes from so
+/// `0xff` is printed as `0xff`. The results of operations are decimal.
+#[derive(Clone, Debug)]
 pub struct Integer {
     val: BigInt,
+    radix: Radix,
+}
+
+impl PartialEq for Integer {
+    fn eq(&self, other: &Self) -> bool {
+        self.val == other.val
+    }
+}
+
+impl Eq for Integer {}
+
+impl Hash for Integer {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.val.hash(state);
+    }
+}
+
+impl PartialOrd for Integer {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Integer {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.val.cmp(&other.val)
+    }
 }
 
 impl Integer {
     pub fn new(literal: &str, radix: Radix) -> Self {
         let val = BigInt::parse_bytes(literal.as_bytes(), radix.into()).unwrap();
 
-        Self { val }
+        Self { val, radix }
     }
 
     fn to_machine_magnitude(&self) -> usize {
@@ -711,53 +1136,64 @@ impl Integer {
     pub fn is_zero(&self) -> bool {
         self.val.is_zero()
     }
+
+    pub fn is_positive(&self) -> bool {
+        self.val.is_positive()
+    }
 }
 
 impl fmt::Display for Integer {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.val)
+        let prefix = match self.radix {
+            Radix::Binary => "0b",
+            Radix::Decimal => return write!(f, "{}", self.val),
+            Radix::Hex => "0x",
+            Radix::Octal => "0o",
+        };
+        let sign = if self.val.is_negative() { "-" } else { "" };
+        let magnitude = self.val.magnitude().to_str_radix(self.radix.into());
+
+        write!(f, "{sign}{prefix}{magnitude}")
     }
 }
 
 impl From<i32> for Integer {
     fn from(value: i32) -> Self {
-        let val = BigInt::from(value);
-        Self { val }
+        Self::from(BigInt::from(value))
     }
 }
 
 impl From<i64> for Integer {
     fn from(value: i64) -> Self {
-        let val = BigInt::from(value);
-        Self { val }
+        Self::from(BigInt::from(value))
     }
 }
 
 impl From<usize> for Integer {
     fn from(value: usize) -> Self {
-        let val = BigInt::from(value);
-        Self { val }
+        Self::from(BigInt::from(value))
     }
 }
 
 impl From<BigInt> for Integer {
     fn from(val: BigInt) -> Self {
-        Self { val }
+        Self {
+            val,
+            radix: Radix::Decimal,
+        }
     }
 }
 
 impl From<&str> for Integer {
     fn from(val: &str) -> Self {
-        Self {
-            val: val.parse().unwrap(),
-        }
+        Self::from(val.parse::<BigInt>().unwrap())
     }
 }
 
 impl InfixOperable for Integer {
     fn bitwise_and(&self, other: &Object) -> Option<Object> {
         match other {
-            Object::Integer(Integer { val }) => {
+            Object::Integer(Integer { val, .. }) => {
                 Some(Object::Integer(Integer::from(&self.val & val)))
             }
             _ => None,
@@ -766,7 +1202,7 @@ impl InfixOperable for Integer {
 
     fn or(&self, other: &Object) -> Option<Object> {
         match other {
-            Object::Integer(Integer { val }) => {
+            Object::Integer(Integer { val, .. }) => {
                 Some(Object::Integer(Integer::from(&self.val | val)))
             }
             _ => None,
@@ -775,7 +1211,7 @@ impl InfixOperable for Integer {
 
     fn bitwise_xor(&self, other: &Object) -> Option<Object> {
         match other {
-            Object::Integer(Integer { val }) => {
+            Object::Integer(Integer { val, .. }) => {
                 Some(Object::Integer(Integer::from(&self.val ^ val)))
             }
             _ => None,
@@ -793,38 +1229,62 @@ impl InfixOperable for Integer {
 
     fn less(&self, other: &Object) -> Option<Object> {
         match other {
-            Object::Integer(Integer { val }) => Some(Object::Boolean(Bool::from(self.val < *val))),
+            Object::Integer(Integer { val, .. }) => {
+                Some(Object::Boolean(Bool::from(self.val < *val)))
+            }
+            obj if obj.is_symbolic() => symbolic::less(Object::Integer(self.to_owned()), obj),
+            Object::Interval(_) => interval::coerced(Object::Integer(self.to_owned()))?.less(other),
             _ => None,
         }
     }
 
     fn less_equal(&self, other: &Object) -> Option<Object> {
         match other {
-            Object::Integer(Integer { val }) => Some(Object::Boolean(Bool::from(self.val <= *val))),
+            Object::Integer(Integer { val, .. }) => {
+                Some(Object::Boolean(Bool::from(self.val <= *val)))
+            }
+            obj if obj.is_symbolic() => symbolic::less_equal(Object::Integer(self.to_owned()), obj),
+            Object::Interval(_) => {
+                interval::coerced(Object::Integer(self.to_owned()))?.less_equal(other)
+            }
             _ => None,
         }
     }
 
     fn rem(&self, other: &Object) -> Option<Object> {
         match other {
-            Object::Integer(Integer { val }) => {
+            Object::Integer(Integer { val, .. }) => {
                 Some(Object::Integer(Integer::from(&self.val % val)))
             }
+            obj if obj.is_symbolic() => symbolic::remainder(Object::Integer(self.to_owned()), obj),
+            Object::Polynomial(_) => {
+                polynomial::coerced(Object::Integer(self.to_owned()))?.rem(other)
+            }
             _ => None,
         }
     }
 
     fn over(&self, other: &Object) -> Option<Object> {
         match other {
-            Object::Integer(Integer { val }) => {
+            Object::Integer(Integer { val, .. }) => {
                 Some(Object::Integer(Integer::from(&self.val / val)))
             }
-            Object::Decimal(Decimal { val }) => Some(Object::Decimal(Decimal::from(
-                BigDecimal::new(self.val.clone(), 0) / val,
+            Object::Decimal(Decimal { val }) => Some(Object::Decimal(Decimal::quotient(
+                &BigDecimal::new(self.val.clone(), 0),
+                val,
             ))),
             Object::Fraction(Fraction { val }) => Some(Object::Fraction(
                 (&BigRational::from_integer(self.val.to_owned()) / val).into(),
             )),
+            obj if obj.is_symbolic() => symbolic::over(Object::Integer(self.to_owned()), obj),
+            Object::Polynomial(_) => {
+                polynomial::coerced(Object::Integer(self.to_owned()))?.over(other)
+            }
+            Object::Complex(_) => complex::coerced(Object::Integer(self.to_owned()))?.over(other),
+            Object::Interval(_) => interval::coerced(Object::Integer(self.to_owned()))?.over(other),
+            Object::Modular(modular) => modular
+                .coerced(&Object::Integer(self.to_owned()))?
+                .over(other),
             _ => None,
         }
     }
@@ -834,15 +1294,22 @@ impl InfixOperable for Integer {
             Object::Integer(int) => {
                 if int.val.is_negative() {
                     let inverse = self.val.to_owned().pow(int.val.magnitude());
-                    let val = BigDecimal::new(inverse, 0).inverse();
 
-                    Some(Object::Decimal(Decimal { val }))
+                    Some(Object::Decimal(Decimal::quotient(
+                        &BigDecimal::one(),
+                        &BigDecimal::new(inverse, 0),
+                    )))
                 } else {
                     let val = self.val.to_owned().pow(int.val.magnitude());
 
-                    Some(Object::Integer(Integer { val }))
+                    Some(Object::Integer(Integer::from(val)))
                 }
             }
+            Object::Decimal(Decimal { val }) => {
+                numeric::power(&BigDecimal::new(self.val.clone(), 0), val)
+            }
+            obj if obj.is_symbolic() => symbolic::pow(Object::Integer(self.to_owned()), obj),
+            Object::Complex(_) => complex::coerced(Object::Integer(self.to_owned()))?.pow(other),
             _ => None,
         }
     }
@@ -858,7 +1325,7 @@ impl InfixOperable for Integer {
 
     fn sum(&self, other: &Object) -> Option<Object> {
         match other {
-            Object::Integer(Integer { val }) => {
+            Object::Integer(Integer { val, .. }) => {
                 Some(Object::Integer(Integer::from(&self.val + val)))
             }
             Object::Decimal(Decimal { val }) => {
@@ -867,13 +1334,22 @@ impl InfixOperable for Integer {
             Object::Fraction(Fraction { val }) => Some(Object::Fraction(
                 (&BigRational::from_integer(self.val.to_owned()) + val).into(),
             )),
+            obj if obj.is_symbolic() => symbolic::sum(Object::Integer(self.to_owned()), obj),
+            Object::Polynomial(_) => {
+                polynomial::coerced(Object::Integer(self.to_owned()))?.sum(other)
+            }
+            Object::Complex(_) => complex::coerced(Object::Integer(self.to_owned()))?.sum(other),
+            Object::Interval(_) => interval::coerced(Object::Integer(self.to_owned()))?.sum(other),
+            Object::Modular(modular) => modular
+                .coerced(&Object::Integer(self.to_owned()))?
+                .sum(other),
             _ => None,
         }
     }
 
     fn substraction(&self, other: &Object) -> Option<Object> {
         match other {
-            Object::Integer(Integer { val }) => {
+            Object::Integer(Integer { val, .. }) => {
                 Some(Object::Integer(Integer::from(&self.val - val)))
             }
             Object::Decimal(Decimal { val }) => Some(Object::Decimal(Decimal::from(
@@ -882,38 +1358,79 @@ impl InfixOperable for Integer {
             Object::Fraction(Fraction { val }) => Some(Object::Fraction(
                 (&BigRational::from_integer(self.val.to_owned()) - val).into(),
             )),
+            obj if obj.is_symbolic() => {
+                symbolic::substraction(Object::Integer(self.to_owned()), obj)
+            }
+            Object::Polynomial(_) => {
+                polynomial::coerced(Object::Integer(self.to_owned()))?.substraction(other)
+            }
+            Object::Complex(_) => {
+                complex::coerced(Object::Integer(self.to_owned()))?.substraction(other)
+            }
+            Object::Interval(_) => {
+                interval::coerced(Object::Integer(self.to_owned()))?.substraction(other)
+            }
+            Object::Modular(modular) => modular
+                .coerced(&Object::Integer(self.to_owned()))?
+                .substraction(other),
             _ => None,
         }
     }
 
     fn product(&self, other: &Object) -> Option<Object> {
         match other {
-            Object::Integer(Integer { val }) => {
+            Object::Integer(Integer { val, .. }) => {
                 Some(Object::Integer(Integer::from(&self.val * val)))
             }
-            Object::Decimal(Decimal { val }) => {
-                Some(Object::Decimal(Decimal::from(&self.val * val)))
-            }
+            Object::Decimal(Decimal { val }) => Some(Object::Decimal(Decimal::from(
+                decimal_product(&BigDecimal::from(self.val.to_owned()), val),
+            ))),
             Object::Char(chr) => Some(chr.multiply(self)),
             Object::String(str) => Some(str.multiply(self)),
             Object::List(lst) => Some(lst.multiply(self)),
             Object::Fraction(Fraction { val }) => Some(Object::Fraction(
                 (&BigRational::from_integer(self.val.to_owned()) * val).into(),
             )),
+            obj if obj.is_symbolic() => symbolic::product(Object::Integer(self.to_owned()), obj),
+            Object::Polynomial(_) => {
+                polynomial::coerced(Object::Integer(self.to_owned()))?.product(other)
+            }
+            Object::Complex(_) => {
+                complex::coerced(Object::Integer(self.to_owned()))?.product(other)
+            }
+            Object::Interval(_) => {
+                interval::coerced(Object::Integer(self.to_owned()))?.product(other)
+            }
+            Object::Modular(modular) => modular
+                .coerced(&Object::Integer(self.to_owned()))?
+                .product(other),
+            Object::Matrix(matrix) => matrix
+                .scaled(&Object::Integer(self.to_owned()))
+                .map(Object::Matrix),
             _ => None,
         }
     }
 
     fn greater(&self, other: &Object) -> Option<Object> {
         match other {
-            Object::Integer(Integer { val }) => Some((self.val > *val).into()),
+            Object::Integer(Integer { val, .. }) => Some((self.val > *val).into()),
+            obj if obj.is_symbolic() => symbolic::greater(Object::Integer(self.to_owned()), obj),
+            Object::Interval(_) => {
+                interval::coerced(Object::Integer(self.to_owned()))?.greater(other)
+            }
             _ => None,
         }
     }
 
     fn greater_equal(&self, other: &Object) -> Option<Object> {
         match other {
-            Object::Integer(Integer { val }) => Some((self.val >= *val).into()),
+            Object::Integer(Integer { val, .. }) => Some((self.val >= *val).into()),
+            obj if obj.is_symbolic() => {
+                symbolic::greater_equal(Object::Integer(self.to_owned()), obj)
+            }
+            Object::Interval(_) => {
+                interval::coerced(Object::Integer(self.to_owned()))?.greater_equal(other)
+            }
             _ => None,
         }
     }
@@ -1016,9 +1533,53 @@ impl fmt::Display for Symbol {
     }
 }
 
-impl InfixOperable for Symbol {}
+impl InfixOperable for Symbol {
+    fn sum(&self, other: &Object) -> Option<Object> {
+        symbolic::sum(Object::Symbol(self.to_owned()), other)
+    }
+
+    fn substraction(&self, other: &Object) -> Option<Object> {
+        symbolic::substraction(Object::Symbol(self.to_owned()), other)
+    }
+
+    fn product(&self, other: &Object) -> Option<Object> {
+        symbolic::product(Object::Symbol(self.to_owned()), other)
+    }
+
+    fn over(&self, other: &Object) -> Option<Object> {
+        symbolic::over(Object::Symbol(self.to_owned()), other)
+    }
+
+    fn pow(&self, other: &Object) -> Option<Object> {
+        symbolic::pow(Object::Symbol(self.to_owned()), other)
+    }
+
+    fn rem(&self, other: &Object) -> Option<Object> {
+        symbolic::remainder(Object::Symbol(self.to_owned()), other)
+    }
 
-impl PrefixOperable for Symbol {}
+    fn less(&self, other: &Object) -> Option<Object> {
+        symbolic::less(Object::Symbol(self.to_owned()), other)
+    }
+
+    fn less_equal(&self, other: &Object) -> Option<Object> {
+        symbolic::less_equal(Object::Symbol(self.to_owned()), other)
+    }
+
+    fn greater(&self, other: &Object) -> Option<Object> {
+        symbolic::greater(Object::Symbol(self.to_owned()), other)
+    }
+
+    fn greater_equal(&self, other: &Object) -> Option<Object> {
+        symbolic::greater_equal(Object::Symbol(self.to_owned()), other)
+    }
+}
+
+impl PrefixOperable for Symbol {
+    fn inverse(&self) -> Option<Object> {
+        Some(symbolic::negation(Object::Symbol(self.to_owned())))
+    }
+}
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Tuple {
@@ -1071,7 +1632,7 @@ impl Function {
         call_pos: Position,
     ) -> Result<Object, Error> {
         match self {
-            Self::Pattern(f) => f.call(args, call_pos),
+            Self::Pattern(f) => f.call(args, env, call_pos),
             Self::Anonymous(f) => f.call(args, call_pos),
             Self::Extern(ef) => ef.call(args, env, call_pos),
         }
@@ -1092,19 +1653,49 @@ pub enum FunctionPatternKind {
     NotMemoized,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+/// A clause of a pattern function: its parameters, guard and result.
+type Clause = (FunctionPatternKind, Vec<ASTNode>, Option<ASTNode>, ASTNode);
+
+#[derive(Clone, Debug)]
 pub struct PatternFunction {
     pub env: Rc<RefCell<Environment>>,
-    patterns: Vec<(FunctionPatternKind, Vec<ASTNode>, ASTNode)>,
+    patterns: Vec<Clause>,
     cache: BTreeMap<Vec<Object>, Object>,
     params: usize,
 }
 
+/// Functions are compared by their clauses. The environment is shared and
+/// the cache grows with every call, so they are left out: otherwise a
+/// function could move inside the sets and dictionaries that hold it.
+impl PatternFunction {
+    fn key(&self) -> (&[Clause], usize) {
+        (&self.patterns, self.params)
+    }
+}
+
+impl PartialEq for PatternFunction {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for PatternFunction {}
+
 impl Hash for PatternFunction {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.patterns.hash(state);
-        self.cache.hash(state);
-        self.params.hash(state);
+        self.key().hash(state);
+    }
+}
+
+impl PartialOrd for PatternFunction {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PatternFunction {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key().cmp(&other.key())
     }
 }
 
@@ -1117,11 +1708,17 @@ impl PatternFunction {
             params: usize::default(),
         }
     }
-    pub fn add_pattern(&mut self, args: &[ASTNode], value: &ASTNode, kind: FunctionPatternKind) {
+    pub fn add_pattern(
+        &mut self,
+        args: &[ASTNode],
+        guard: Option<&ASTNode>,
+        value: &ASTNode,
+        kind: FunctionPatternKind,
+    ) {
         for i in 0..self.patterns.len() {
-            let (_, other_args, _) = &self.patterns[i];
-            if args == other_args {
-                self.patterns[i] = (kind, args.to_owned(), value.to_owned());
+            let (_, other_args, other_guard, _) = &self.patterns[i];
+            if args == other_args && guard == other_guard.as_ref() {
+                self.patterns[i] = (kind, args.to_owned(), guard.cloned(), value.to_owned());
             }
         }
 
@@ -1131,59 +1728,239 @@ impl PatternFunction {
             self.params = min(self.params, args.len())
         }
 
-        self.patterns.push((kind, args.to_vec(), value.clone()));
+        self.patterns
+            .push((kind, args.to_vec(), guard.cloned(), value.clone()));
     }
 
-    fn exec_call(
+    /// Evaluates the result of a clause with the values its patterns bound,
+    /// unless its guard doesn't hold, which gives `None`.
+    fn exec_call<T>(
         matched_values: BTreeMap<String, Object>,
+        guard: Option<&ASTNode>,
         result_node: &ASTNode,
         env: &mut Environment,
-    ) -> Result<Object, Error> {
+        eval: fn(&ASTNode, &mut Environment) -> Result<T, Error>,
+    ) -> Result<Option<T>, Error> {
         env.push_scope();
 
         for (name, pattern_val) in matched_values {
             env.set_inmutable(&name, pattern_val);
         }
 
-        let res = exec(result_node, env);
+        let res = match guard
+            .map(|guard| exec(guard, env).and_then(|cond| holds(&cond, guard)))
+            .transpose()
+        {
+            Ok(Some(false)) => Ok(None),
+            Ok(_) => eval(result_node, env).map(Some),
+            Err(err) => Err(err),
+        };
 
         env.pop_scope();
 
         res
     }
 
-    fn call(&mut self, args: &[Object], call_pos: Position) -> Result<Object, Error> {
+    /// Runs the first clause that matches, leaving a call in tail position
+    /// of its result to the caller. Memoized clauses are evaluated fully so
+    /// their result can be cached.
+    fn step(&mut self, args: &[Object], call_pos: Position) -> Result<Tail, Error> {
         if let Some(cached) = self.cache.get(args) {
-            Ok(cached.to_owned())
-        } else {
-            for (kind, patterns, val) in &self.patterns {
-                if let Some(Match(v)) = match_call(patterns, args) {
-                    match kind {
-                        FunctionPatternKind::NotMemoized => {
-                            return Self::exec_call(v, val, &mut self.env.borrow().to_owned());
-                        }
-                        FunctionPatternKind::Memoized => {
-                            let res = Self::exec_call(v, val, &mut self.env.borrow().to_owned())?;
-                            self.cache.insert(args.to_owned(), res.clone());
-                            return Ok(res);
+            return Ok(Tail::Value(cached.to_owned()));
+        }
+
+        for (kind, patterns, guard, val) in &self.patterns {
+            if let Some(Match(v)) = match_call(patterns, args) {
+                let env = &mut self.env.borrow().to_owned();
+
+                let res = match kind {
+                    FunctionPatternKind::Memoized => {
+                        let Some(res) = Self::exec_call(v, guard.as_ref(), val, env, exec)? else {
+                            continue;
+                        };
+
+                        self.cache.insert(args.to_owned(), res.clone());
+                        Tail::Value(res)
+                    }
+                    FunctionPatternKind::NotMemoized => {
+                        match Self::exec_call(v, guard.as_ref(), val, env, exec_tail)? {
+                            Some(res) => res,
+                            None => continue,
                         }
                     }
+                };
+
+                return Ok(res);
+            }
+        }
+
+        Err(Error::new(EvalError::UnmatchedCall.into(), call_pos))
+    }
+
+    /// Calls the function, making the calls in tail position of its clauses
+    /// one after another instead of nesting them, so tail recursion runs in
+    /// constant stack space. The stack grows as needed for the rest of the
+    /// calls, up to the active `RecursionLimit`.
+    fn call(
+        &mut self,
+        args: &[Object],
+        env: &mut Environment,
+        call_pos: Position,
+    ) -> Result<Object, Error> {
+        let _depth = CallDepth::enter(call_pos)?;
+
+        stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT, || {
+            let mut others = vec![];
+            let mut tail = self.step(args, call_pos);
+
+            let res = loop {
+                match tail {
+                    Ok(Tail::Value(res)) => break Ok(res),
+                    Ok(Tail::Call(call)) => tail = self.tail_step(call, &mut others, env),
+                    Err(err) => break Err(err),
+                }
+            };
+
+            for (name, func) in others {
+                if let EnvResponse::Mutable(Object::Function(Function::Pattern(current))) =
+                    env.get(&name)
+                {
+                    if current.same_function(&func) {
+                        *current = func;
+                    }
                 }
             }
 
-            Err(Error::new(EvalError::UnmatchedCall.into(), call_pos))
+            res
+        })
+    }
+
+    /// Makes a call left in tail position. It runs on this function when it
+    /// calls itself, and on the ones in `others` when it calls them again,
+    /// so what they memoize isn't lost. Functions the clause couldn't see
+    /// are looked up in `env`.
+    fn tail_step(
+        &mut self,
+        call: TailCall,
+        others: &mut Vec<(String, PatternFunction)>,
+        env: &mut Environment,
+    ) -> Result<Tail, Error> {
+        let func = match call.func {
+            Some(func) => func,
+            None => match symbol(&call.name, env, call.name_pos)? {
+                Object::Function(Function::Pattern(func)) if call.args.len() >= func.params => func,
+                func => {
+                    check_arguments(&func, call.args.len(), call.call_pos)?;
+                    return apply(
+                        func,
+                        Some(&call.name),
+                        &call.args,
+                        env,
+                        call.call_pos,
+                        call.name_pos,
+                    )
+                    .map(Tail::Value);
+                }
+            },
+        };
+
+        if self.same_function(&func) {
+            return self.step(&call.args, call.call_pos);
         }
+
+        let index = match others
+            .iter()
+            .position(|(_, other)| other.same_function(&func))
+        {
+            Some(index) => index,
+            None => {
+                others.push((call.name, func));
+                others.len() - 1
+            }
+        };
+
+        others[index].1.step(&call.args, call.call_pos)
     }
 
-    fn param_number(&self) -> usize {
+    /// Whether both are the same version of a function. Declaring another
+    /// clause keeps the environment but makes a new version.
+    fn same_function(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.env, &other.env) && self.patterns.len() == other.patterns.len()
+    }
+
+    pub fn param_number(&self) -> usize {
         self.params
     }
 }
 
+/// How many pattern function calls can be nested before giving up. The
+/// stack grows as needed, so the limit only stops a runaway recursion
+/// before it takes all the memory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RecursionLimit(pub usize);
+
+impl Default for RecursionLimit {
+    fn default() -> Self {
+        Self(100_000)
+    }
+}
+
+impl RecursionLimit {
+    /// Makes this the limit of nested calls until the returned guard is
+    /// dropped.
+    pub fn activate(self) -> ActiveRecursionLimit {
+        ActiveRecursionLimit(RECURSION_LIMIT.with(|limit| limit.replace(self)))
+    }
+}
+
+/// Puts the previously active recursion limit back when dropped.
+pub struct ActiveRecursionLimit(RecursionLimit);
+
+impl Drop for ActiveRecursionLimit {
+    fn drop(&mut self) {
+        RECURSION_LIMIT.with(|limit| limit.set(self.0));
+    }
+}
+
+/// The stack left when a pattern function call gets a new stack segment,
+/// and the size of that segment.
+const STACK_RED_ZONE: usize = 256 * 1024;
+const STACK_SEGMENT: usize = 4 * 1024 * 1024;
+
+thread_local! {
+    static CALL_DEPTH: Cell<usize> = const { Cell::new(0) };
+    static RECURSION_LIMIT: Cell<RecursionLimit> = Cell::default();
+}
+
+/// A nested pattern function call, which stops counting when dropped.
+struct CallDepth;
+
+impl CallDepth {
+    fn enter(call_pos: Position) -> Result<Self, Error> {
+        let depth = CALL_DEPTH.with(Cell::get);
+
+        if depth >= RECURSION_LIMIT.with(Cell::get).0 {
+            return Err(Error::new(
+                EvalError::RecursionDepthExceeded.into(),
+                call_pos,
+            ));
+        }
+
+        CALL_DEPTH.with(|cell| cell.set(depth + 1));
+        Ok(Self)
+    }
+}
+
+impl Drop for CallDepth {
+    fn drop(&mut self) {
+        CALL_DEPTH.with(|cell| cell.set(cell.get() - 1));
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct AnonFunction {
     params: Vec<String>,
-    result: ASTNode,
+    result: Box<ASTNode>,
     env: Environment,
 }
 
@@ -1191,7 +1968,7 @@ impl AnonFunction {
     pub fn new(params: Vec<String>, result: ASTNode, env: Environment) -> Self {
         Self {
             params,
-            result,
+            result: Box::new(result),
             env,
         }
     }
@@ -1215,24 +1992,44 @@ impl AnonFunction {
     }
 }
 
+#[allow(unpredictable_function_pointer_comparisons)]
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct ExternFunction {
-    func: fn(&[Object]) -> Object,
+    func: fn(&[Object]) -> Result<Object, EvalError>,
+    bound: Vec<Object>,
     param_number: usize,
 }
 
 impl ExternFunction {
-    pub fn new(func: fn(&[Object]) -> Object, param_number: usize) -> Self {
-        Self { func, param_number }
+    pub fn new(func: fn(&[Object]) -> Result<Object, EvalError>, param_number: usize) -> Self {
+        Self::bound(func, vec![], param_number)
+    }
+
+    /// A function that calls `func` with `bound` before its own arguments.
+    pub fn bound(
+        func: fn(&[Object]) -> Result<Object, EvalError>,
+        bound: Vec<Object>,
+        param_number: usize,
+    ) -> Self {
+        Self {
+            func,
+            bound,
+            param_number,
+        }
     }
 
     fn call(
         &mut self,
         args: &[Object],
         _env: &mut Environment,
-        _call_pos: Position,
+        call_pos: Position,
     ) -> Result<Object, Error> {
-        Ok((self.func)(args))
+        let args = [self.bound.as_slice(), args].concat();
+
+        (self.func)(&args).map_err(|err| match err {
+            EvalError::Inner(err) => *err,
+            err => Error::new(err.into(), call_pos),
+        })
     }
 
     fn param_number(&self) -> usize {
@@ -1371,12 +2168,18 @@ impl InfixOperable for Fraction {
             Object::Fraction(Fraction { val }) => Some(Object::Fraction(Fraction {
                 val: &self.val + val,
             })),
-            Object::Integer(Integer { val }) => Some(Object::Fraction(Fraction {
+            Object::Integer(Integer { val, .. }) => Some(Object::Fraction(Fraction {
                 val: &self.val + BigRational::from_integer(val.to_owned()),
             })),
             Object::Decimal(dec) => Some(Object::Decimal(Decimal::from(
                 &Decimal::from(self).val + &dec.val,
             ))),
+            obj if obj.is_symbolic() => symbolic::sum(Object::Fraction(self.to_owned()), obj),
+            Object::Polynomial(_) => {
+                polynomial::coerced(Object::Fraction(self.to_owned()))?.sum(other)
+            }
+            Object::Complex(_) => complex::coerced(Object::Fraction(self.to_owned()))?.sum(other),
+            Object::Interval(_) => interval::coerced(Object::Fraction(self.to_owned()))?.sum(other),
             _ => None,
         }
     }
@@ -1386,12 +2189,24 @@ impl InfixOperable for Fraction {
             Object::Fraction(Fraction { val }) => Some(Object::Fraction(Fraction {
                 val: &self.val - val,
             })),
-            Object::Integer(Integer { val }) => Some(Object::Fraction(Fraction {
+            Object::Integer(Integer { val, .. }) => Some(Object::Fraction(Fraction {
                 val: &self.val - BigRational::from_integer(val.to_owned()),
             })),
             Object::Decimal(dec) => Some(Object::Decimal(Decimal::from(
                 &Decimal::from(self).val - &dec.val,
             ))),
+            obj if obj.is_symbolic() => {
+                symbolic::substraction(Object::Fraction(self.to_owned()), obj)
+            }
+            Object::Polynomial(_) => {
+                polynomial::coerced(Object::Fraction(self.to_owned()))?.substraction(other)
+            }
+            Object::Complex(_) => {
+                complex::coerced(Object::Fraction(self.to_owned()))?.substraction(other)
+            }
+            Object::Interval(_) => {
+                interval::coerced(Object::Fraction(self.to_owned()))?.substraction(other)
+            }
             _ => None,
         }
     }
@@ -1401,12 +2216,25 @@ impl InfixOperable for Fraction {
             Object::Fraction(Fraction { val }) => Some(Object::Fraction(Fraction {
                 val: &self.val * val,
             })),
-            Object::Integer(Integer { val }) => Some(Object::Fraction(Fraction {
+            Object::Integer(Integer { val, .. }) => Some(Object::Fraction(Fraction {
                 val: &self.val * BigRational::from_integer(val.to_owned()),
             })),
             Object::Decimal(dec) => Some(Object::Decimal(Decimal::from(
                 &Decimal::from(self).val * &dec.val,
             ))),
+            obj if obj.is_symbolic() => symbolic::product(Object::Fraction(self.to_owned()), obj),
+            Object::Polynomial(_) => {
+                polynomial::coerced(Object::Fraction(self.to_owned()))?.product(other)
+            }
+            Object::Complex(_) => {
+                complex::coerced(Object::Fraction(self.to_owned()))?.product(other)
+            }
+            Object::Interval(_) => {
+                interval::coerced(Object::Fraction(self.to_owned()))?.product(other)
+            }
+            Object::Matrix(matrix) => matrix
+                .scaled(&Object::Fraction(self.to_owned()))
+                .map(Object::Matrix),
             _ => None,
         }
     }
@@ -1416,21 +2244,33 @@ impl InfixOperable for Fraction {
             Object::Fraction(Fraction { val }) => Some(Object::Fraction(Fraction {
                 val: &self.val / val,
             })),
-            Object::Integer(Integer { val }) => Some(Object::Fraction(Fraction {
+            Object::Integer(Integer { val, .. }) => Some(Object::Fraction(Fraction {
                 val: &self.val / BigRational::from_integer(val.to_owned()),
             })),
-            Object::Decimal(dec) => Some(Object::Decimal(Decimal::from(
-                &Decimal::from(self).val / &dec.val,
+            Object::Decimal(dec) => Some(Object::Decimal(Decimal::quotient(
+                &BigDecimal::new(self.val.numer().to_owned(), 0),
+                &(&dec.val * BigDecimal::new(self.val.denom().to_owned(), 0)),
             ))),
+            obj if obj.is_symbolic() => symbolic::over(Object::Fraction(self.to_owned()), obj),
+            Object::Polynomial(_) => {
+                polynomial::coerced(Object::Fraction(self.to_owned()))?.over(other)
+            }
+            Object::Complex(_) => complex::coerced(Object::Fraction(self.to_owned()))?.over(other),
+            Object::Interval(_) => {
+                interval::coerced(Object::Fraction(self.to_owned()))?.over(other)
+            }
             _ => None,
         }
     }
 
     fn pow(&self, other: &Object) -> Option<Object> {
         match other {
-            Object::Integer(Integer { val }) => Some(Object::Fraction(Fraction {
+            Object::Integer(Integer { val, .. }) => Some(Object::Fraction(Fraction {
                 val: Pow::pow(self.val.to_owned(), val),
             })),
+            Object::Decimal(Decimal { val }) => numeric::power(&Decimal::from(self).val, val),
+            obj if obj.is_symbolic() => symbolic::pow(Object::Fraction(self.to_owned()), obj),
+            Object::Complex(_) => complex::coerced(Object::Fraction(self.to_owned()))?.pow(other),
             _ => None,
         }
     }
@@ -1451,6 +2291,122 @@ impl fmt::Display for Fraction {
     }
 }
 
+/// An integer modulo a positive `modulus`, kept in `0..modulus`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Modular {
+    pub val: BigInt,
+    pub modulus: BigInt,
+}
+
+impl Modular {
+    pub fn new(val: &Integer, modulus: &Integer) -> Self {
+        Self::reduced(&val.val, &modulus.val)
+    }
+
+    fn reduced(val: &BigInt, modulus: &BigInt) -> Self {
+        Self {
+            val: val.mod_floor(modulus),
+            modulus: modulus.to_owned(),
+        }
+    }
+
+    /// `obj` as an element of the same ring, when it is an integer or an
+    /// element of it.
+    fn coerced(&self, obj: &Object) -> Option<Self> {
+        match obj {
+            Object::Integer(Integer { val, .. }) => Some(Self::reduced(val, &self.modulus)),
+            Object::Modular(other) if other.modulus == self.modulus => Some(other.to_owned()),
+            _ => None,
+        }
+    }
+
+    pub fn reciprocal(&self) -> Option<Self> {
+        let val = numtheory::inverse_mod(&self.val, &self.modulus)?;
+        Some(Self::reduced(&val, &self.modulus))
+    }
+
+    /// The divisor of `lhs / rhs` as an element of a ring, when one of them
+    /// is in it.
+    pub fn divisor(lhs: &Object, rhs: &Object) -> Option<Self> {
+        match (lhs, rhs) {
+            (_, Object::Modular(modular)) => Some(modular.to_owned()),
+            (Object::Modular(modular), rhs) => modular.coerced(rhs),
+            _ => None,
+        }
+    }
+
+    fn operate(&self, other: &Object, op: fn(&BigInt, &BigInt) -> BigInt) -> Option<Object> {
+        let other = self.coerced(other)?;
+        let val = op(&self.val, &other.val);
+
+        Some(Object::Modular(Self::reduced(&val, &self.modulus)))
+    }
+}
+
+impl InfixOperable for Modular {
+    fn sum(&self, other: &Object) -> Option<Object> {
+        match other {
+            obj if obj.is_symbolic() => symbolic::sum(Object::Modular(self.to_owned()), obj),
+            _ => self.operate(other, |lhs, rhs| lhs + rhs),
+        }
+    }
+
+    fn substraction(&self, other: &Object) -> Option<Object> {
+        match other {
+            obj if obj.is_symbolic() => {
+                symbolic::substraction(Object::Modular(self.to_owned()), obj)
+            }
+            _ => self.operate(other, |lhs, rhs| lhs - rhs),
+        }
+    }
+
+    fn product(&self, other: &Object) -> Option<Object> {
+        match other {
+            obj if obj.is_symbolic() => symbolic::product(Object::Modular(self.to_owned()), obj),
+            _ => self.operate(other, |lhs, rhs| lhs * rhs),
+        }
+    }
+
+    fn over(&self, other: &Object) -> Option<Object> {
+        if other.is_symbolic() {
+            return symbolic::over(Object::Modular(self.to_owned()), other);
+        }
+
+        let reciprocal = self.coerced(other)?.reciprocal()?;
+        self.product(&Object::Modular(reciprocal))
+    }
+
+    fn pow(&self, other: &Object) -> Option<Object> {
+        match other {
+            obj if obj.is_symbolic() => symbolic::pow(Object::Modular(self.to_owned()), obj),
+            Object::Integer(Integer { val, .. }) => {
+                let base = match val.is_negative() {
+                    true => self.reciprocal()?,
+                    false => self.to_owned(),
+                };
+
+                Some(Object::Modular(Self::reduced(
+                    &base.val.modpow(&val.abs(), &self.modulus),
+                    &self.modulus,
+                )))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl PrefixOperable for Modular {
+    fn inverse(&self) -> Option<Object> {
+        Some(Object::Modular(Self::reduced(&-&self.val, &self.modulus)))
+    }
+}
+
+impl fmt::Display for Modular {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} mod {}", self.val, self.modulus)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Range {
     pub start: Integer,
@@ -1464,7 +2420,7 @@ impl Iterator for Range {
         if self.cur.val < self.end.val {
             let val = self.cur.val.to_owned();
             self.cur.val += 1;
-            Some(Object::Integer(Integer { val }))
+            Some(Object::Integer(Integer::from(val)))
         } else {
             None
         }
@@ -1473,7 +2429,7 @@ impl Iterator for Range {
 
 impl PartialOrd for Range {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(vec![&self.start, &self.end].cmp(&vec![&other.start, &other.end]))
+        Some(self.cmp(other))
     }
 }
 
@@ -1530,6 +2486,43 @@ impl InfixOperable for FailedAssertion {}
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[test]
+    fn shared_precisions_compare_equal() {
+        let digits = |n| NonZeroU64::new(n).unwrap();
+        let lhs = SharedPrecision::new(Precision::new(digits(5), Rounding::HalfEven));
+        let rhs = SharedPrecision::new(Precision::new(digits(50), Rounding::Up));
+
+        assert_eq!(lhs, rhs);
+        assert_eq!(lhs.cmp(&rhs), std::cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn unknown_truth_value() {
+        let unknown = Object::Boolean(Bool::unknown());
+        let yes = Object::from(true);
+        let no = Object::from(false);
+
+        assert_eq!(unknown.logic_not(), Some(unknown.clone()));
+        assert_eq!(unknown.logic_and(&no), Some(no.clone()));
+        assert_eq!(unknown.logic_and(&yes), Some(unknown.clone()));
+        assert_eq!(yes.or(&unknown), Some(yes.clone()));
+        assert_eq!(no.or(&unknown), Some(unknown.clone()));
+        assert_eq!(unknown.to_string(), "unknown");
+    }
+
+    #[test]
+    fn integer_radix() {
+        let hex = Integer::new("ff", Radix::Hex);
+
+        assert_eq!(hex.to_string(), "0xff");
+        assert_eq!(hex, Integer::from(255));
+        assert_eq!(Integer::new("101", Radix::Binary).to_string(), "0b101");
+        assert_eq!(
+            Object::Integer(hex).sum(&Object::Integer(Integer::from(1))),
+            Some(Object::Integer(Integer::from(256)))
+        );
+    }
+
     #[test]
     fn concat_strings() {
         let str1 = Object::String(MyString::from("foo"));
@@ -1654,4 +2647,46 @@ mod tests {
 
         assert_eq!(a.pow(&b), Some(Object::Decimal(Decimal::new("0", "25"))),);
     }
+
+    #[test]
+    fn decimal_precision() {
+        Precision::new(NonZeroU64::new(5).unwrap(), Rounding::HalfEven).install();
+
+        let one = Object::Integer(Integer::from(1));
+        let three = Object::Decimal(Decimal::new("3", "0"));
+        let tie = Object::Decimal(Decimal::new("1", "00005"));
+
+        assert_eq!(one.over(&three).unwrap().to_string(), "0.33333");
+        assert_eq!(tie.product(&one).unwrap().to_string(), "1.0000");
+
+        Precision::new(NonZeroU64::new(5).unwrap(), Rounding::Up).install();
+
+        assert_eq!(tie.product(&one).unwrap().to_string(), "1.0001");
+        assert_eq!(one.over(&three).unwrap().to_string(), "0.33334");
+
+        Precision::default().install();
+    }
+
+    #[test]
+    fn exact_decimal_quotient() {
+        let a = Object::Decimal(Decimal::new("1000", "0"));
+        let b = Object::Integer(Integer::from(8));
+
+        assert_eq!(a.over(&b).unwrap().to_string(), "125");
+    }
+
+    #[test]
+    fn modular_arithmetic() {
+        let a = Object::Modular(Modular::new(&Integer::from(3), &Integer::from(7)));
+        let b = Object::Modular(Modular::new(&Integer::from(-2), &Integer::from(7)));
+
+        assert_eq!(a.sum(&b).unwrap().to_string(), "1 mod 7");
+        assert_eq!(a.product(&b).unwrap().to_string(), "1 mod 7");
+        assert_eq!(Object::Integer(Integer::from(1)).over(&a).unwrap(), b);
+        let minus_two = Object::Integer(Integer::from(-2));
+        assert_eq!(a.pow(&minus_two).unwrap().to_string(), "4 mod 7");
+
+        let other = Object::Modular(Modular::new(&Integer::from(3), &Integer::from(5)));
+        assert_eq!(a.sum(&other), None);
+    }
 }
@@ -18,15 +18,21 @@ pub struct Parser<T: Iterator<Item = Result<Token, Error>>> {
     tokens: Peekable<T>,
     cur_pos: Position,
     ignore_whitespace: bool,
+    allow_guards: bool,
+    at_statement: bool,
 }
 
 impl<T: Iterator<Item = Result<Token, Error>>> Iterator for Parser<T> {
     type Item = Result<CSTNode, Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if let Err(err) = self.skip_newlines() {
+            return Some(Err(err));
+        }
+
         match self.peek_token() {
             Ok(None) => None,
-            Ok(Some(_)) => Some(self.expression(Precedence::Lowest)),
+            Ok(Some(_)) => Some(self.statement()),
             Err(err) => Some(Err(err)),
         }
     }
@@ -44,6 +50,8 @@ impl<T: Iterator<Item = Result<Token, Error>>> Parser<T> {
     }
 
     fn non_infix(&mut self) -> NodeResult {
+        let at_statement = std::mem::take(&mut self.at_statement);
+
         match self.next_token()? {
             None => self.err_with_cur(ParserError::EOFReached),
             Some(tok) => match tok {
@@ -54,18 +62,20 @@ impl<T: Iterator<Item = Result<Token, Error>>> Parser<T> {
                 TokenType::From => self.import_from(),
                 TokenType::If => self.if_(),
                 TokenType::Import => self.import(),
-                TokenType::Let => self.let_expression(),
-                TokenType::Var => self.var_expression(),
+                TokenType::Let => self.let_expression(at_statement),
+                TokenType::Var => self.var_expression(at_statement),
                 TokenType::True => self.boolean(true),
                 TokenType::False => self.boolean(false),
                 TokenType::Lparen => self.parenthesis(),
                 TokenType::Lbrace => self.set_or_dict(),
                 TokenType::Lbrack => self.list(),
                 TokenType::Integer(int, radix) => self.integer(int, radix),
+                TokenType::Imaginary(int) => self.imaginary(int),
                 TokenType::Ident(literal) => self.symbol(literal),
                 TokenType::Indent => self.block(),
                 TokenType::String(str) => self.string(str),
                 TokenType::Wildcard => self.wildcard(),
+                TokenType::With => self.with_precision(),
                 tok => {
                     if let Some(op) = PrefixOperator::from(&tok) {
                         self.prefix(op)
@@ -91,7 +101,8 @@ impl<T: Iterator<Item = Result<Token, Error>>> Parser<T> {
                     break;
                 }
                 Some(_) => {
-                    let left = self.expression(Precedence::Lowest)?;
+                    let left =
+                        self.allowing_guards(|parser| parser.expression(Precedence::Lowest))?;
                     self.consume(TokenType::FatArrow)?;
                     let right = self.expression(Precedence::Lowest)?;
 
@@ -108,17 +119,17 @@ impl<T: Iterator<Item = Result<Token, Error>>> Parser<T> {
         ))
     }
 
-    fn let_expression(&mut self) -> NodeResult {
+    fn let_expression(&mut self, at_statement: bool) -> NodeResult {
         if let Ok(Some(TokenType::Memoize)) = self.peek_token() {
             let start = self.cur_pos.start;
             self.next_token()?;
-            self.declaration(DeclarationKind::InmutableMemoized, start)
+            self.declaration(DeclarationKind::InmutableMemoized, start, at_statement)
         } else {
-            self.declaration(DeclarationKind::Inmutable, self.cur_pos.start)
+            self.declaration(DeclarationKind::Inmutable, self.cur_pos.start, at_statement)
         }
     }
 
-    fn var_expression(&mut self) -> NodeResult {
+    fn var_expression(&mut self, at_statement: bool) -> NodeResult {
         if let Ok(Some(TokenType::Memoize)) = self.peek_token() {
             self.next_token()?;
             Err(Error::new(
@@ -126,12 +137,12 @@ impl<T: Iterator<Item = Result<Token, Error>>> Parser<T> {
                 self.cur_pos,
             ))
         } else {
-            self.declaration(DeclarationKind::Mutable, self.cur_pos.start)
+            self.declaration(DeclarationKind::Mutable, self.cur_pos.start, at_statement)
         }
     }
 
     fn block(&mut self) -> NodeResult {
-        let first = self.expression(Precedence::Lowest)?;
+        let first = self.statement()?;
         let start = first.position.start;
         let mut exprs = vec![first];
 
@@ -148,18 +159,37 @@ impl<T: Iterator<Item = Result<Token, Error>>> Parser<T> {
                     break res;
                 }
                 _ => {
-                    exprs.push(self.expression(Precedence::Lowest)?);
+                    exprs.push(self.statement()?);
                     continue;
                 }
             }
         }
     }
 
+    /// An expression that starts a line of a program or a block.
+    fn statement(&mut self) -> NodeResult {
+        self.at_statement = true;
+        self.expression(Precedence::Lowest)
+    }
+
     fn expression(&mut self, precedence: Precedence) -> NodeResult {
+        let expr = self.line_expression(precedence)?;
+        self.skip_newlines()?;
+
+        Ok(expr)
+    }
+
+    /// An expression that stops at the end of its line.
+    fn line_expression(&mut self, precedence: Precedence) -> NodeResult {
         let start = self.peek_pos().start;
+        let expr = self.non_infix()?;
 
-        let mut expr = self.non_infix()?;
+        self.infixes(expr, precedence, start)
+    }
 
+    /// Continues `expr` with the infix operators that bind tighter than
+    /// `precedence`.
+    fn infixes(&mut self, mut expr: CSTNode, precedence: Precedence, start: usize) -> NodeResult {
         while let Some(op) = self.current_infix() {
             if precedence < op.precedence() {
                 self.next_token()?;
@@ -169,11 +199,15 @@ impl<T: Iterator<Item = Result<Token, Error>>> Parser<T> {
             }
         }
 
+        Ok(expr)
+    }
+
+    fn skip_newlines(&mut self) -> Result<(), Error> {
         while let Ok(Some(TokenType::Newline)) = self.peek_token() {
             self.next_token()?;
         }
 
-        Ok(expr)
+        Ok(())
     }
 
     fn ad_infinitum(&self) -> NodeResult {
@@ -208,8 +242,56 @@ impl<T: Iterator<Item = Result<Token, Error>>> Parser<T> {
         self.node_with_cur(CSTNodeKind::Symbol(literal))
     }
 
-    fn declaration(&mut self, kind: DeclarationKind, start: usize) -> NodeResult {
-        let expr = self.expression(Precedence::Lowest)?;
+    /// A declaration like `let a := value`. Inside another expression, an
+    /// `in` right after the value makes it local to the expression that
+    /// follows, as in `(let a := 1 in a + 1)`, so a value with `in` needs
+    /// parentheses there. A declaration that starts a line keeps reading
+    /// `in` as membership, so `let b := 2 in {1, 2}` binds a boolean.
+    fn declaration(
+        &mut self,
+        kind: DeclarationKind,
+        start: usize,
+        at_statement: bool,
+    ) -> NodeResult {
+        let left_start = self.peek_pos().start;
+        let mut expr = self.line_expression(Precedence::Assignment)?;
+
+        if let CSTNodeKind::Infix(InfixOperator::Call, ..) = expr.kind {
+            expr = self.allowing_guards(|parser| {
+                parser.infixes(expr, Precedence::Assignment, left_start)
+            })?;
+        }
+
+        if let Some(TokenType::Assign) = self.peek_token()? {
+            self.next_token()?;
+            let precedence = match at_statement {
+                true => Precedence::Assignment,
+                false => Precedence::In,
+            };
+            let value = self.line_expression(precedence)?;
+            let position = Self::start_to_pos(left_start, value.position);
+            expr = infix(InfixOperator::Assignment, expr, value, position);
+
+            if let (false, Some(TokenType::In)) = (at_statement, self.peek_token()?) {
+                self.next_token()?;
+                let position = Self::start_to_pos(start, expr.position);
+                let declaration = Box::new(CSTNode::new(
+                    CSTNodeKind::Declaration(Box::new(expr), kind),
+                    position,
+                ));
+                let body = Box::new(self.expression(Precedence::Lowest)?);
+                let position = Self::start_to_pos(start, body.position);
+
+                return Ok(CSTNode::new(
+                    CSTNodeKind::LetIn { declaration, body },
+                    position,
+                ));
+            }
+
+            expr = self.infixes(expr, Precedence::Lowest, left_start)?;
+        }
+
+        self.skip_newlines()?;
         let last_pos = expr.position;
 
         Ok(CSTNode::new(
@@ -300,6 +382,11 @@ impl<T: Iterator<Item = Result<Token, Error>>> Parser<T> {
 
                 Ok(infix(op, lhs, rhs, self.start_to_cur(start)))
             }
+            InfixOperator::Range if self.ends_open_range()? => {
+                let rhs = self.ad_infinitum()?;
+
+                Ok(infix(op, lhs, rhs, self.start_to_cur(start)))
+            }
             _ => self.expression(op.precedence()).map(|rhs| {
                 let last_pos = rhs.position;
                 CSTNode::new(
@@ -310,10 +397,39 @@ impl<T: Iterator<Item = Result<Token, Error>>> Parser<T> {
         }
     }
 
+    /// Whether a range has no end, like `1..` right before a closing
+    /// bracket or the end of a line.
+    fn ends_open_range(&mut self) -> Result<bool, Error> {
+        if let Some(Ok(Token {
+            token: TokenType::Indent | TokenType::Dedent | TokenType::Newline,
+            ..
+        })) = self.tokens.peek()
+        {
+            return Ok(true);
+        }
+
+        Ok(matches!(
+            self.peek_token()?,
+            None | Some(
+                TokenType::Rparen
+                    | TokenType::Rbrack
+                    | TokenType::Rbrace
+                    | TokenType::Comma
+                    | TokenType::Do
+                    | TokenType::If
+                    | TokenType::For
+            )
+        ))
+    }
+
     fn integer(&mut self, int: String, radix: Radix) -> NodeResult {
         self.node_with_cur(CSTNodeKind::Integer(int, radix))
     }
 
+    fn imaginary(&mut self, int: String) -> NodeResult {
+        self.node_with_cur(CSTNodeKind::Imaginary(int))
+    }
+
     fn ignoring_whitespace<F: FnOnce(&mut Parser<T>) -> Result<P, Error>, P>(
         &mut self,
         f: F,
@@ -329,6 +445,22 @@ impl<T: Iterator<Item = Result<Token, Error>>> Parser<T> {
         res
     }
 
+    /// Guards only follow the function in a declaration, a case arm or a
+    /// generator, so elsewhere `if` starts a new expression.
+    fn allowing_guards<F: FnOnce(&mut Parser<T>) -> Result<P, Error>, P>(
+        &mut self,
+        f: F,
+    ) -> Result<P, Error> {
+        let last = self.allow_guards;
+        self.allow_guards = true;
+
+        let res = f(self);
+
+        self.allow_guards = last;
+
+        res
+    }
+
     fn parenthesis(&mut self) -> NodeResult {
         let start = self.cur_pos.start;
 
@@ -402,7 +534,9 @@ impl<T: Iterator<Item = Result<Token, Error>>> Parser<T> {
             let first = parser.expression(Precedence::Lowest)?;
 
             match parser.next_token()? {
-                Some(TokenType::For) => parser.comprehension(first, ComprehensionKind::List, start),
+                Some(TokenType::For) => {
+                    parser.comprehension(first, None, ComprehensionKind::List, start)
+                }
                 Some(TokenType::Comma) => parser
                     .sequence(TokenType::Rbrack, Some(first))
                     .map(|lst| extension_list(lst, parser.start_to_cur(start))),
@@ -426,37 +560,46 @@ impl<T: Iterator<Item = Result<Token, Error>>> Parser<T> {
     fn comprehension(
         &mut self,
         first: CSTNode,
+        value: Option<CSTNode>,
         kind: ComprehensionKind,
         start: usize,
     ) -> NodeResult {
-        let variable = self.consume_symbol()?;
-        self.consume(TokenType::In)?;
-        let iterator = self.expression(Precedence::Lowest)?;
-        self.consume(match kind {
+        let terminator = match kind {
             ComprehensionKind::List => TokenType::Rbrack,
-            ComprehensionKind::Set => TokenType::Rbrace,
-        })?;
+            ComprehensionKind::Set | ComprehensionKind::Dictionary => TokenType::Rbrace,
+        };
+        let mut generators = vec![];
+
+        loop {
+            generators.push(self.allowing_guards(|parser| parser.expression(Precedence::Lowest))?);
+
+            match self.next_token()? {
+                Some(TokenType::Comma) => continue,
+                Some(tok) if tok == terminator => break,
+                Some(tok) => {
+                    return self.err_with_cur(ParserError::UnexpectedToken(
+                        vec![TokenType::Comma, terminator],
+                        tok,
+                    ))
+                }
+                None => {
+                    return self.err_with_cur(ParserError::EOFExpecting(vec![
+                        TokenType::Comma,
+                        terminator,
+                    ]))
+                }
+            }
+        }
 
         Ok(comprehension(
             first,
-            variable,
-            iterator,
+            value,
+            generators,
             kind,
             self.start_to_cur(start),
         ))
     }
 
-    fn consume_symbol(&mut self) -> Result<String, Error> {
-        match self.next_token()? {
-            Some(TokenType::Ident(name)) => Ok(name),
-            Some(tok) => self.err_with_cur(ParserError::UnexpectedToken(
-                vec![TokenType::Ident("".into())],
-                tok,
-            )),
-            None => self.err_with_cur(ParserError::EOFExpecting(vec![TokenType::Ident("".into())])),
-        }
-    }
-
     fn prepend_(&mut self, first: CSTNode, start: usize) -> NodeResult {
         let last = self.expression(Precedence::Lowest)?;
 
@@ -496,6 +639,23 @@ impl<T: Iterator<Item = Result<Token, Error>>> Parser<T> {
         Ok(_for(&ident, iter, proc, self.start_to_cur(start)))
     }
 
+    fn with_precision(&mut self) -> NodeResult {
+        let start = self.cur_pos.start;
+
+        self.consume(TokenType::Ident("precision".into()))?;
+
+        let digits = Box::new(self.expression(Precedence::Lowest)?);
+
+        self.consume(TokenType::Do)?;
+
+        let body = Box::new(self.expression(Precedence::Lowest)?);
+
+        Ok(CSTNode::new(
+            CSTNodeKind::WithPrecision { digits, body },
+            self.start_to_cur(start),
+        ))
+    }
+
     fn import_from(&mut self) -> NodeResult {
         let start = self.cur_pos.start;
 
@@ -564,7 +724,9 @@ impl<T: Iterator<Item = Result<Token, Error>>> Parser<T> {
 
     fn current_infix(&mut self) -> Option<InfixOperator> {
         match self.peek_token() {
-            Ok(opt) => opt.and_then(InfixOperator::from),
+            Ok(opt) => opt
+                .and_then(InfixOperator::from)
+                .filter(|op| self.allow_guards || *op != InfixOperator::Guard),
             _ => None,
         }
     }
@@ -591,11 +753,24 @@ impl<T: Iterator<Item = Result<Token, Error>>> Parser<T> {
                 Some(TokenType::Comma) => parser
                     .sequence(TokenType::Rbrace, Some(first))
                     .map(|lst| extension_set(lst, parser.start_to_cur(start))),
-                Some(TokenType::For) => parser.comprehension(first, ComprehensionKind::Set, start),
+                Some(TokenType::For) => {
+                    parser.comprehension(first, None, ComprehensionKind::Set, start)
+                }
                 Some(TokenType::VerticalBar) => parser.set_cons(first, start),
                 Some(TokenType::FatArrow) => {
-                    let first = (first, parser.expression(Precedence::Lowest)?);
-                    parser.dict(first, start)
+                    let value = parser.expression(Precedence::Lowest)?;
+
+                    if matches!(parser.peek_token()?, Some(TokenType::For)) {
+                        parser.next_token()?;
+                        parser.comprehension(
+                            first,
+                            Some(value),
+                            ComprehensionKind::Dictionary,
+                            start,
+                        )
+                    } else {
+                        parser.dict((first, value), start)
+                    }
                 }
                 Some(TokenType::Rbrace) => {
                     Ok(extension_set(vec![first], parser.start_to_cur(start)))
@@ -680,6 +855,8 @@ impl<T: Iterator<Item = Result<Token, Error>>> From<T> for Parser<T> {
             tokens: tokens.peekable(),
             cur_pos: Position::new(0, 0),
             ignore_whitespace: false,
+            allow_guards: false,
+            at_statement: false,
         }
     }
 }
@@ -965,6 +1142,135 @@ mod tests {
         );
     }
 
+    #[test]
+    fn guard() {
+        let input = "let f(n) if n > 0 := n";
+        let lexer = Lexer::from(input);
+
+        assert_eq!(
+            Parser::from(lexer).next(),
+            Some(Ok(CSTNode::new(
+                CSTNodeKind::Declaration(
+                    Box::new(infix(
+                        InfixOperator::Assignment,
+                        infix(
+                            InfixOperator::Guard,
+                            infix(
+                                InfixOperator::Call,
+                                symbol("f", _pos(4, 1)),
+                                tuple(vec![symbol("n", _pos(6, 1))], _pos(5, 3)),
+                                _pos(4, 4)
+                            ),
+                            infix(
+                                InfixOperator::Greater,
+                                symbol("n", _pos(12, 1)),
+                                dec_integer("0", _pos(16, 1)),
+                                _pos(12, 5)
+                            ),
+                            _pos(4, 13)
+                        ),
+                        symbol("n", _pos(21, 1)),
+                        _pos(4, 18)
+                    )),
+                    DeclarationKind::Inmutable
+                ),
+                _pos(0, 22)
+            ))),
+        );
+    }
+
+    #[test]
+    fn if_after_statement() {
+        let input = "1\nif a then b else c";
+        let lexer = Lexer::from(input);
+        let mut parser = Parser::from(lexer);
+
+        assert_eq!(parser.next(), Some(Ok(dec_integer("1", _pos(0, 1)))));
+        assert!(matches!(
+            parser.next(),
+            Some(Ok(CSTNode {
+                kind: CSTNodeKind::If { .. },
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn let_in() {
+        let input = "(let a := 1 in a)";
+        let lexer = Lexer::from(input);
+
+        assert_eq!(
+            Parser::from(lexer).next(),
+            Some(Ok(CSTNode::new(
+                CSTNodeKind::LetIn {
+                    declaration: Box::new(let_(
+                        symbol("a", _pos(5, 1)),
+                        Some(dec_integer("1", _pos(10, 1))),
+                        _pos(1, 10)
+                    )),
+                    body: Box::new(symbol("a", _pos(15, 1))),
+                },
+                _pos(1, 15)
+            ))),
+        );
+    }
+
+    #[test]
+    fn let_statement_membership() {
+        let input = "let b := 2 in {1, 2}";
+        let lexer = Lexer::from(input);
+
+        assert_eq!(
+            Parser::from(lexer).next(),
+            Some(Ok(let_(
+                symbol("b", _pos(4, 1)),
+                Some(infix(
+                    InfixOperator::In,
+                    dec_integer("2", _pos(9, 1)),
+                    extension_set(
+                        vec![dec_integer("1", _pos(15, 1)), dec_integer("2", _pos(18, 1))],
+                        _pos(14, 6)
+                    ),
+                    _pos(9, 11)
+                )),
+                _pos(0, 20)
+            ))),
+        );
+    }
+
+    #[test]
+    fn where_() {
+        let input = "let b := a where a := 1";
+        let lexer = Lexer::from(input);
+
+        assert_eq!(
+            Parser::from(lexer).next(),
+            Some(Ok(CSTNode::new(
+                CSTNodeKind::Declaration(
+                    Box::new(infix(
+                        InfixOperator::Where,
+                        infix(
+                            InfixOperator::Assignment,
+                            symbol("b", _pos(4, 1)),
+                            symbol("a", _pos(9, 1)),
+                            _pos(4, 6)
+                        ),
+                        infix(
+                            InfixOperator::Assignment,
+                            symbol("a", _pos(17, 1)),
+                            dec_integer("1", _pos(22, 1)),
+                            _pos(17, 6)
+                        ),
+                        _pos(4, 19)
+                    )),
+                    DeclarationKind::Inmutable
+                ),
+                _pos(0, 23)
+            ))),
+        );
+    }
+
     #[test]
     fn empty_set() {
         let tokens = vec![
@@ -1040,8 +1346,13 @@ mod tests {
             Parser::from(lexer).next(),
             Some(Ok(comprehension(
                 symbol("a", _pos(1, 1)),
-                "a".into(),
-                symbol("S", _pos(12, 1)),
+                None,
+                vec![infix(
+                    InfixOperator::In,
+                    symbol("a", _pos(7, 1)),
+                    symbol("S", _pos(12, 1)),
+                    _pos(7, 6)
+                )],
                 ComprehensionKind::Set,
                 _pos(0, 14),
             )))
@@ -1438,6 +1749,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn with_precision() {
+        let input = "with precision 5 do x";
+
+        let lexer = Lexer::from(input);
+
+        assert_eq!(
+            Parser::from(lexer).next(),
+            Some(Ok(CSTNode::new(
+                CSTNodeKind::WithPrecision {
+                    digits: Box::new(dec_integer("5", _pos(15, 1))),
+                    body: Box::new(symbol("x", _pos(20, 1))),
+                },
+                _pos(0, 21)
+            )))
+        );
+    }
+
     #[test]
     fn list() {
         let input = "[[], 2]";
@@ -1519,8 +1848,13 @@ mod tests {
                 InfixOperator::Sum,
                 comprehension(
                     symbol("a", _pos(1, 1)),
-                    "a".into(),
-                    symbol("b", _pos(12, 1)),
+                    None,
+                    vec![infix(
+                        InfixOperator::In,
+                        symbol("a", _pos(7, 1)),
+                        symbol("b", _pos(12, 1)),
+                        _pos(7, 6)
+                    )],
                     ComprehensionKind::List,
                     _pos(0, 14),
                 ),
@@ -1530,6 +1864,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn multiple_generators() {
+        let input = "[a for a in b if a, c in d]";
+        let lexer = Lexer::from(input);
+
+        assert_eq!(
+            Parser::from(lexer).next(),
+            Some(Ok(comprehension(
+                symbol("a", _pos(1, 1)),
+                None,
+                vec![
+                    infix(
+                        InfixOperator::Guard,
+                        infix(
+                            InfixOperator::In,
+                            symbol("a", _pos(7, 1)),
+                            symbol("b", _pos(12, 1)),
+                            _pos(7, 6)
+                        ),
+                        symbol("a", _pos(17, 1)),
+                        _pos(7, 11)
+                    ),
+                    infix(
+                        InfixOperator::In,
+                        symbol("c", _pos(20, 1)),
+                        symbol("d", _pos(25, 1)),
+                        _pos(20, 6)
+                    ),
+                ],
+                ComprehensionKind::List,
+                _pos(0, 27),
+            )))
+        );
+    }
+
     #[test]
     fn expected_rparen() {
         let input = "(15]";
@@ -1626,6 +1995,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn open_range() {
+        let input = "1..";
+        let lexer = Lexer::from(input);
+
+        assert_eq!(
+            Parser::from(lexer).next(),
+            Some(Ok(infix(
+                InfixOperator::Range,
+                dec_integer("1", _pos(0, 1)),
+                ad_infinitum(_pos(1, 2)),
+                _pos(0, 3)
+            )))
+        );
+    }
+
+    #[test]
+    fn open_range_before_newline() {
+        let input = "let s := 1..\nlet t := 5";
+        let lexer = Lexer::from(input);
+        let mut parser = Parser::from(lexer);
+
+        assert_eq!(
+            parser.next(),
+            Some(Ok(let_(
+                symbol("s", _pos(4, 1)),
+                Some(infix(
+                    InfixOperator::Range,
+                    dec_integer("1", _pos(9, 1)),
+                    ad_infinitum(_pos(10, 2)),
+                    _pos(9, 3)
+                )),
+                _pos(0, 12)
+            )))
+        );
+        assert!(matches!(parser.next(), Some(Ok(_))));
+        assert_eq!(parser.next(), None);
+    }
+
     #[test]
     fn fraction() {
         let input = "1 // 2";
@@ -1642,6 +2050,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn modular() {
+        let input = "a + 1 mod 7";
+        let lexer = Lexer::from(input);
+
+        assert_eq!(
+            Parser::from(lexer).next(),
+            Some(Ok(infix(
+                InfixOperator::Modular,
+                infix(
+                    InfixOperator::Sum,
+                    symbol("a", _pos(0, 1)),
+                    dec_integer("1", _pos(4, 1)),
+                    _pos(0, 5)
+                ),
+                dec_integer("7", _pos(10, 1)),
+                _pos(0, 11)
+            )))
+        );
+    }
+
     #[test]
     fn oop_cal_with_int() {
         let input = "2.f()";
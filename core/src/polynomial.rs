@@ -0,0 +1,722 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    ops::{Add, Mul, Neg, Sub},
+};
+
+use bigdecimal::{
+    num_traits::{Pow, ToPrimitive},
+    One, Signed, Zero,
+};
+use num_bigint::BigInt;
+use num_integer::Integer as _;
+use num_rational::BigRational;
+
+use crate::{
+    exec::EvalError,
+    object::{InfixOperable, Integer, List, Object, PrefixOperable, Symbol},
+    simplify::simplify,
+    symbolic::Expression,
+};
+
+pub type Monomial = BTreeMap<Symbol, u32>;
+
+/// A multivariate polynomial with exact rational coefficients. Zero
+/// coefficients are never stored.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Polynomial {
+    terms: BTreeMap<Monomial, BigRational>,
+}
+
+/// Lexicographic monomial order, where variables that sort first are the
+/// most significant.
+fn lex_cmp(lhs: &Monomial, rhs: &Monomial) -> Ordering {
+    let vars: BTreeSet<&Symbol> = lhs.keys().chain(rhs.keys()).collect();
+
+    for var in vars {
+        let lhs_exp = lhs.get(var).unwrap_or(&0);
+        let rhs_exp = rhs.get(var).unwrap_or(&0);
+
+        match lhs_exp.cmp(rhs_exp) {
+            Ordering::Equal => continue,
+            ord => return ord,
+        }
+    }
+
+    Ordering::Equal
+}
+
+fn divides(lhs: &Monomial, rhs: &Monomial) -> bool {
+    lhs.iter()
+        .all(|(var, exp)| rhs.get(var).is_some_and(|rhs_exp| rhs_exp >= exp))
+}
+
+fn monomial_quotient(lhs: &Monomial, rhs: &Monomial) -> Monomial {
+    let mut res = lhs.to_owned();
+
+    for (var, exp) in rhs {
+        let entry = res.get_mut(var).unwrap();
+        *entry -= exp;
+        if *entry == 0 {
+            res.remove(var);
+        }
+    }
+
+    res
+}
+
+fn monomial_product(lhs: &Monomial, rhs: &Monomial) -> Monomial {
+    let mut res = lhs.to_owned();
+
+    for (var, exp) in rhs {
+        *res.entry(var.to_owned()).or_insert(0) += exp;
+    }
+
+    res
+}
+
+impl Polynomial {
+    fn from_terms(terms: impl IntoIterator<Item = (Monomial, BigRational)>) -> Self {
+        let mut res = Self::default();
+
+        for (monomial, coefficient) in terms {
+            res.add_term(monomial, coefficient);
+        }
+
+        res
+    }
+
+    fn add_term(&mut self, monomial: Monomial, coefficient: BigRational) {
+        let total = match self.terms.remove(&monomial) {
+            Some(previous) => previous + coefficient,
+            None => coefficient,
+        };
+
+        if !total.is_zero() {
+            self.terms.insert(monomial, total);
+        }
+    }
+
+    pub fn constant(val: BigRational) -> Self {
+        Self::from_terms([(Monomial::new(), val)])
+    }
+
+    pub fn variable(var: Symbol) -> Self {
+        Self::from_terms([(Monomial::from([(var, 1)]), BigRational::one())])
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn constant_value(&self) -> Option<BigRational> {
+        match self.terms.len() {
+            0 => Some(BigRational::zero()),
+            1 => self.terms.get(&Monomial::new()).cloned(),
+            _ => None,
+        }
+    }
+
+    pub fn variables(&self) -> BTreeSet<Symbol> {
+        self.terms
+            .keys()
+            .flat_map(|monomial| monomial.keys().cloned())
+            .collect()
+    }
+
+    pub fn degree(&self) -> Option<u32> {
+        self.terms
+            .keys()
+            .map(|monomial| monomial.values().sum())
+            .max()
+    }
+
+    pub fn degree_in(&self, var: &Symbol) -> Option<u32> {
+        self.terms
+            .keys()
+            .map(|monomial| *monomial.get(var).unwrap_or(&0))
+            .max()
+    }
+
+    fn leading_term(&self) -> Option<(&Monomial, &BigRational)> {
+        self.terms
+            .iter()
+            .max_by(|(lhs, _), (rhs, _)| lex_cmp(lhs, rhs))
+    }
+
+    pub fn scale(&self, factor: &BigRational) -> Self {
+        Self::from_terms(
+            self.terms
+                .iter()
+                .map(|(monomial, coefficient)| (monomial.to_owned(), coefficient * factor)),
+        )
+    }
+
+    fn monic(&self) -> Self {
+        match self.leading_term() {
+            Some((_, coefficient)) => self.scale(&coefficient.recip()),
+            None => self.to_owned(),
+        }
+    }
+
+    pub fn power(&self, exp: u32) -> Self {
+        let mut base = self.to_owned();
+        let mut exp = exp;
+        let mut res = Self::constant(BigRational::one());
+
+        while exp > 0 {
+            if exp % 2 == 1 {
+                res = &res * &base;
+            }
+
+            base = &base * &base;
+            exp /= 2;
+        }
+
+        res
+    }
+
+    /// Divides by `divisor` using the lexicographic order. For univariate
+    /// polynomials this is the usual long division.
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        let mut quotient = Self::default();
+        let mut remainder = Self::default();
+        let mut rest = self.to_owned();
+
+        let (divisor_monomial, divisor_coefficient) = divisor.leading_term().unwrap();
+
+        while let Some((monomial, coefficient)) = rest.leading_term() {
+            let (monomial, coefficient) = (monomial.to_owned(), coefficient.to_owned());
+
+            if divides(divisor_monomial, &monomial) {
+                let term = Self::from_terms([(
+                    monomial_quotient(&monomial, divisor_monomial),
+                    coefficient / divisor_coefficient,
+                )]);
+
+                rest = &rest - &(&term * divisor);
+                quotient = &quotient + &term;
+            } else {
+                rest.terms.remove(&monomial);
+                remainder.add_term(monomial, coefficient);
+            }
+        }
+
+        (quotient, remainder)
+    }
+
+    /// The coefficients of the polynomial seen as univariate in `var`,
+    /// indexed by the power of `var`.
+    pub fn coefficients_in(&self, var: &Symbol) -> Vec<Self> {
+        let degree = self.degree_in(var).unwrap_or(0) as usize;
+        let mut res = vec![Self::default(); degree + 1];
+
+        for (monomial, coefficient) in &self.terms {
+            let mut monomial = monomial.to_owned();
+            let exp = monomial.remove(var).unwrap_or(0) as usize;
+
+            res[exp].add_term(monomial, coefficient.to_owned());
+        }
+
+        res
+    }
+
+    /// Builds a univariate polynomial from its coefficients, indexed by
+    /// the power of `var`.
+    pub fn from_coefficients(var: &Symbol, coefficients: &[BigRational]) -> Self {
+        Self::from_terms(coefficients.iter().enumerate().map(|(exp, coefficient)| {
+            let monomial = match exp {
+                0 => Monomial::new(),
+                exp => Monomial::from([(var.to_owned(), exp as u32)]),
+            };
+
+            (monomial, coefficient.to_owned())
+        }))
+    }
+
+    pub fn derivative(&self, var: &Symbol) -> Self {
+        Self::from_terms(self.terms.iter().filter_map(|(monomial, coefficient)| {
+            let exp = *monomial.get(var)?;
+            let mut monomial = monomial.to_owned();
+
+            match exp {
+                1 => monomial.remove(var),
+                exp => monomial.insert(var.to_owned(), exp - 1),
+            };
+
+            Some((
+                monomial,
+                coefficient * BigRational::from_integer(exp.into()),
+            ))
+        }))
+    }
+
+    /// Splits the polynomial into a rational constant and a polynomial with
+    /// coprime integer coefficients and a positive leading coefficient.
+    pub fn integer_primitive(&self) -> (BigRational, Self) {
+        let denominator = self.terms.values().fold(BigInt::one(), |acc, coefficient| {
+            acc.lcm(coefficient.denom())
+        });
+
+        let numerator = self
+            .terms
+            .values()
+            .fold(BigInt::zero(), |acc, coefficient| {
+                acc.gcd(&(coefficient * &denominator).to_integer())
+            });
+
+        let mut constant = BigRational::new(numerator, denominator);
+        if self
+            .leading_term()
+            .is_some_and(|(_, coefficient)| coefficient.is_negative())
+        {
+            constant = -constant;
+        }
+
+        if constant.is_zero() {
+            return (constant, self.to_owned());
+        }
+
+        (constant.to_owned(), self.scale(&constant.recip()))
+    }
+
+    pub fn content_in(&self, var: &Symbol) -> Self {
+        self.coefficients_in(var)
+            .iter()
+            .fold(Self::default(), |acc, coefficient| acc.gcd(coefficient))
+    }
+
+    pub fn exact_quotient(&self, divisor: &Self) -> Self {
+        self.div_rem(divisor).0
+    }
+
+    /// The pseudo-remainder of `self` divided by `divisor`, both seen as
+    /// univariate in `var`. It avoids divisions by the coefficients.
+    fn pseudo_remainder(&self, divisor: &Self, var: &Symbol) -> Self {
+        let divisor_degree = divisor.degree_in(var).unwrap();
+        let divisor_coefficients = divisor.coefficients_in(var);
+        let divisor_lead = &divisor_coefficients[divisor_degree as usize];
+
+        let mut rest = self.to_owned();
+        while let Some(degree) = rest
+            .degree_in(var)
+            .filter(|degree| *degree >= divisor_degree)
+        {
+            let lead = &rest.coefficients_in(var)[degree as usize];
+            let shift = Self::variable(var.to_owned()).power(degree - divisor_degree);
+
+            rest = &(divisor_lead * &rest) - &(&(lead * &shift) * divisor);
+        }
+
+        rest
+    }
+
+    /// The monic greatest common divisor. It uses primitive remainder
+    /// sequences, recursing on the coefficients for multivariate polynomials.
+    pub fn gcd(&self, other: &Self) -> Self {
+        if self.is_zero() {
+            return other.monic();
+        }
+
+        if other.is_zero() {
+            return self.monic();
+        }
+
+        let var = match self.variables().union(&other.variables()).max() {
+            Some(var) => var.to_owned(),
+            None => return Self::constant(BigRational::one()),
+        };
+
+        let lhs_content = self.content_in(&var);
+        let rhs_content = other.content_in(&var);
+        let content = lhs_content.gcd(&rhs_content);
+
+        let mut lhs = self.exact_quotient(&lhs_content);
+        let mut rhs = other.exact_quotient(&rhs_content);
+
+        if lhs.degree_in(&var) < rhs.degree_in(&var) {
+            (lhs, rhs) = (rhs, lhs);
+        }
+
+        let primitive = loop {
+            if rhs.is_zero() {
+                break lhs.exact_quotient(&lhs.content_in(&var));
+            }
+
+            if rhs.degree_in(&var) == Some(0) {
+                break Self::constant(BigRational::one());
+            }
+
+            let rest = lhs.pseudo_remainder(&rhs, &var);
+            let rest_content = rest.content_in(&var);
+
+            lhs = rhs;
+            rhs = if rest.is_zero() {
+                rest
+            } else {
+                rest.exact_quotient(&rest_content)
+            };
+        };
+
+        (&content * &primitive).monic()
+    }
+
+    pub fn from_object(obj: &Object) -> Option<Self> {
+        match obj {
+            Object::Polynomial(poly) => Some(poly.to_owned()),
+            Object::Symbol(symbol) => Some(Self::variable(symbol.to_owned())),
+            Object::Integer(_) | Object::Fraction(_) => Some(Self::constant(obj.as_rational()?)),
+            Object::Expression(Expression::Sum(terms)) => {
+                terms.iter().try_fold(Self::default(), |acc, term| {
+                    Some(&acc + &Self::from_object(term)?)
+                })
+            }
+            Object::Expression(Expression::Product(factors)) => factors
+                .iter()
+                .try_fold(Self::constant(BigRational::one()), |acc, factor| {
+                    Some(&acc * &Self::from_object(factor)?)
+                }),
+            Object::Expression(Expression::Power(base, exp)) => {
+                let base = Self::from_object(base)?;
+                let exp = exp.as_rational().filter(BigRational::is_integer)?;
+
+                match (exp.to_integer().to_u32(), base.constant_value()) {
+                    (Some(exp), _) => Some(base.power(exp)),
+                    (None, Some(val)) if !val.is_zero() => {
+                        Some(Self::constant(Pow::pow(val, &exp.to_integer())))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    pub fn to_expression(&self) -> Object {
+        let terms = self
+            .terms
+            .iter()
+            .map(|(monomial, coefficient)| {
+                let mut factors = vec![Object::from_rational(coefficient.to_owned())];
+
+                for (var, exp) in monomial {
+                    factors.push(Object::Expression(Expression::Power(
+                        Box::new(Object::Symbol(var.to_owned())),
+                        Box::new(Object::Integer(Integer::from(*exp as usize))),
+                    )));
+                }
+
+                Object::Expression(Expression::Product(factors))
+            })
+            .collect();
+
+        simplify(&Object::Expression(Expression::Sum(terms)))
+    }
+
+    /// Converts constants back to plain numbers.
+    pub fn to_object(&self) -> Object {
+        match self.constant_value() {
+            Some(val) => Object::from_rational(val),
+            None => Object::Polynomial(self.to_owned()),
+        }
+    }
+
+    /// Evaluates the polynomial, binding its variables in order to `args`.
+    pub fn evaluate(&self, args: &[Object]) -> Result<Object, EvalError> {
+        let vars = self.variables();
+
+        if vars.len() != args.len() {
+            return Err(EvalError::MissingFunctionArguments {
+                expected: vars.len(),
+                actual: args.len(),
+            });
+        }
+
+        let values: BTreeMap<&Symbol, &Object> = vars.iter().zip(args).collect();
+        let bad_argument = |obj: &Object| EvalError::bad_argument("Polynomial", "Number", obj);
+
+        let mut res = Object::Integer(Integer::from(0));
+
+        for (monomial, coefficient) in &self.terms {
+            let mut term = Object::from_rational(coefficient.to_owned());
+
+            for (var, exp) in monomial {
+                let val = values[var];
+                let exp = Object::Integer(Integer::from(*exp as usize));
+                let power = val.pow(&exp).ok_or_else(|| bad_argument(val))?;
+
+                term = term.product(&power).ok_or_else(|| bad_argument(val))?;
+            }
+
+            res = res.sum(&term).ok_or_else(|| bad_argument(&term))?;
+        }
+
+        if res.is_symbolic() {
+            Ok(simplify(&res))
+        } else {
+            Ok(res)
+        }
+    }
+}
+
+impl Add for &Polynomial {
+    type Output = Polynomial;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut res = self.to_owned();
+
+        for (monomial, coefficient) in &rhs.terms {
+            res.add_term(monomial.to_owned(), coefficient.to_owned());
+        }
+
+        res
+    }
+}
+
+impl Neg for &Polynomial {
+    type Output = Polynomial;
+
+    fn neg(self) -> Self::Output {
+        self.scale(&-BigRational::one())
+    }
+}
+
+impl Sub for &Polynomial {
+    type Output = Polynomial;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + &-rhs
+    }
+}
+
+impl Mul for &Polynomial {
+    type Output = Polynomial;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let mut res = Polynomial::default();
+
+        for (lhs_monomial, lhs_coefficient) in &self.terms {
+            for (rhs_monomial, rhs_coefficient) in &rhs.terms {
+                res.add_term(
+                    monomial_product(lhs_monomial, rhs_monomial),
+                    lhs_coefficient * rhs_coefficient,
+                );
+            }
+        }
+
+        res
+    }
+}
+
+impl fmt::Display for Polynomial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "poly({})", self.to_expression())
+    }
+}
+
+/// Turns `obj` into a polynomial, so it can operate with another one.
+pub fn coerced(obj: Object) -> Option<Object> {
+    Polynomial::from_object(&obj).map(Object::Polynomial)
+}
+
+impl InfixOperable for Polynomial {
+    fn sum(&self, other: &Object) -> Option<Object> {
+        Some(Object::Polynomial(self + &Polynomial::from_object(other)?))
+    }
+
+    fn substraction(&self, other: &Object) -> Option<Object> {
+        Some(Object::Polynomial(self - &Polynomial::from_object(other)?))
+    }
+
+    fn product(&self, other: &Object) -> Option<Object> {
+        Some(Object::Polynomial(self * &Polynomial::from_object(other)?))
+    }
+
+    fn over(&self, other: &Object) -> Option<Object> {
+        match Polynomial::from_object(other)? {
+            divisor if divisor.is_zero() => None,
+            divisor => Some(Object::Polynomial(self.div_rem(&divisor).0)),
+        }
+    }
+
+    fn rem(&self, other: &Object) -> Option<Object> {
+        match Polynomial::from_object(other)? {
+            divisor if divisor.is_zero() => None,
+            divisor => Some(Object::Polynomial(self.div_rem(&divisor).1)),
+        }
+    }
+
+    fn pow(&self, other: &Object) -> Option<Object> {
+        let exp = other
+            .as_rational()
+            .filter(|exp| matches!(other, Object::Integer(_)) && !exp.is_negative())?
+            .to_integer()
+            .to_u32()?;
+
+        Some(Object::Polynomial(self.power(exp)))
+    }
+}
+
+impl PrefixOperable for Polynomial {
+    fn inverse(&self) -> Option<Object> {
+        Some(Object::Polynomial(-self))
+    }
+}
+
+fn polynomial_arg(func: &str, obj: &Object) -> Result<Polynomial, EvalError> {
+    Polynomial::from_object(obj).ok_or_else(|| EvalError::bad_argument(func, "Polynomial", obj))
+}
+
+fn variable_arg(func: &str, poly: &Polynomial, args: &[Object]) -> Result<Symbol, EvalError> {
+    match args.get(1) {
+        Some(Object::Symbol(var)) => Ok(var.to_owned()),
+        Some(obj) => Err(EvalError::bad_argument(func, "Symbol", obj)),
+        None => {
+            let vars = poly.variables();
+
+            match vars.len() {
+                0 => Ok(Symbol::new("x".into(), "Real".into())),
+                1 => Ok(vars.into_iter().next().unwrap()),
+                _ => Err(EvalError::MissingFunctionArguments {
+                    expected: 2,
+                    actual: 1,
+                }),
+            }
+        }
+    }
+}
+
+pub fn poly(args: &[Object]) -> Result<Object, EvalError> {
+    Ok(Object::Polynomial(polynomial_arg("poly", &args[0])?))
+}
+
+pub fn degree(args: &[Object]) -> Result<Object, EvalError> {
+    let poly = polynomial_arg("degree", &args[0])?;
+
+    let degree = match args.get(1) {
+        None => poly.degree(),
+        Some(Object::Symbol(var)) => poly.degree_in(var),
+        Some(obj) => return Err(EvalError::bad_argument("degree", "Symbol", obj)),
+    };
+
+    Ok(Object::Integer(match degree {
+        Some(degree) => Integer::from(degree as usize),
+        None => Integer::from(-1),
+    }))
+}
+
+pub fn coeffs(args: &[Object]) -> Result<Object, EvalError> {
+    let poly = polynomial_arg("coeffs", &args[0])?;
+    let var = variable_arg("coeffs", &poly, args)?;
+
+    let list = poly
+        .coefficients_in(&var)
+        .iter()
+        .rev()
+        .map(Polynomial::to_object)
+        .collect::<Vec<_>>();
+
+    Ok(Object::List(List::from(list)))
+}
+
+pub fn gcd(args: &[Object]) -> Result<Object, EvalError> {
+    if let (Object::Integer(_), Object::Integer(_)) = (&args[0], &args[1]) {
+        let lhs: BigInt = args[0].as_rational().unwrap().to_integer();
+        let rhs: BigInt = args[1].as_rational().unwrap().to_integer();
+
+        return Ok(Object::Integer(Integer::from(lhs.gcd(&rhs))));
+    }
+
+    let lhs = polynomial_arg("gcd", &args[0])?;
+    let rhs = polynomial_arg("gcd", &args[1])?;
+
+    Ok(Object::Polynomial(lhs.gcd(&rhs)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn x() -> Polynomial {
+        Polynomial::variable(Symbol::new("x".into(), "Real".into()))
+    }
+
+    fn y() -> Polynomial {
+        Polynomial::variable(Symbol::new("y".into(), "Real".into()))
+    }
+
+    fn c(val: i32) -> Polynomial {
+        Polynomial::constant(BigRational::from_integer(val.into()))
+    }
+
+    #[test]
+    fn arithmetic() {
+        let p = &(&x() + &c(1)) * &(&x() - &c(1));
+
+        assert_eq!(p, &x().power(2) - &c(1));
+        assert_eq!(p.to_string(), "poly(x**2 - 1)");
+    }
+
+    #[test]
+    fn long_division() {
+        let p = &(&x().power(3) + &c(2)) + &x();
+        let (quotient, remainder) = p.div_rem(&(&x() - &c(1)));
+
+        assert_eq!(quotient, &(&x().power(2) + &x()) + &c(2));
+        assert_eq!(remainder, c(4));
+    }
+
+    #[test]
+    fn multivariate_division() {
+        let p = &(&x() * &y()) + &y().power(2);
+        let (quotient, remainder) = p.div_rem(&(&x() + &y()));
+
+        assert_eq!(quotient, y());
+        assert!(remainder.is_zero());
+    }
+
+    #[test]
+    fn univariate_gcd() {
+        let p = &(&x() - &c(1)) * &(&x() + &c(2));
+        let q = &(&x() - &c(1)) * &(&x() - &c(3)).scale(&BigRational::new(1.into(), 2.into()));
+
+        assert_eq!(p.gcd(&q), &x() - &c(1));
+    }
+
+    #[test]
+    fn multivariate_gcd() {
+        let common = &(&x() * &y()) + &c(1);
+        let p = &common * &(&x() + &y());
+        let q = &common * &(&x() - &y());
+
+        assert_eq!(p.gcd(&q), common);
+    }
+
+    #[test]
+    fn coefficients() {
+        let p = &(&x().power(2).scale(&BigRational::new(1.into(), 2.into())) + &c(3)) - &x();
+
+        assert_eq!(
+            coeffs(&[Object::Polynomial(p)]),
+            Ok(Object::List(List::from(vec![
+                Object::from_rational(BigRational::new(1.into(), 2.into())),
+                Object::Integer(Integer::from(-1)),
+                Object::Integer(Integer::from(3)),
+            ])))
+        );
+    }
+
+    #[test]
+    fn evaluation() {
+        let p = &(&x() * &y()) + &c(1);
+
+        assert_eq!(
+            p.evaluate(&[
+                Object::Integer(Integer::from(2)),
+                Object::Integer(Integer::from(3))
+            ]),
+            Ok(Object::Integer(Integer::from(7)))
+        );
+    }
+}
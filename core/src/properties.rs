@@ -0,0 +1,251 @@
+use num_integer::Integer as _;
+
+use crate::{object::Object, symbolic::Expression};
+
+/// What is known about the sign of an object.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sign {
+    Negative,
+    NonPositive,
+    Zero,
+    NonNegative,
+    Positive,
+}
+
+impl Sign {
+    fn of(val: &Object) -> Self {
+        if val.is_zero() {
+            Sign::Zero
+        } else if val.is_negative() {
+            Sign::Negative
+        } else {
+            Sign::Positive
+        }
+    }
+
+    fn negated(self) -> Self {
+        match self {
+            Sign::Negative => Sign::Positive,
+            Sign::NonPositive => Sign::NonNegative,
+            Sign::Zero => Sign::Zero,
+            Sign::NonNegative => Sign::NonPositive,
+            Sign::Positive => Sign::Negative,
+        }
+    }
+
+    pub fn is_nonnegative(self) -> bool {
+        matches!(self, Sign::Zero | Sign::NonNegative | Sign::Positive)
+    }
+
+    pub fn is_nonpositive(self) -> bool {
+        matches!(self, Sign::Zero | Sign::NonPositive | Sign::Negative)
+    }
+
+    fn plus(self, other: Self) -> Option<Self> {
+        match (self, other) {
+            (Sign::Zero, sign) | (sign, Sign::Zero) => Some(sign),
+            (Sign::Positive, sign) | (sign, Sign::Positive) if sign.is_nonnegative() => {
+                Some(Sign::Positive)
+            }
+            (Sign::Negative, sign) | (sign, Sign::Negative) if sign.is_nonpositive() => {
+                Some(Sign::Negative)
+            }
+            (Sign::NonNegative, Sign::NonNegative) => Some(Sign::NonNegative),
+            (Sign::NonPositive, Sign::NonPositive) => Some(Sign::NonPositive),
+            _ => None,
+        }
+    }
+
+    fn times(self, other: Self) -> Self {
+        match (self, other) {
+            (Sign::Zero, _) | (_, Sign::Zero) => Sign::Zero,
+            (Sign::Positive, sign) => sign,
+            (Sign::Negative, sign) => sign.negated(),
+            (sign, Sign::Positive) => sign,
+            (sign, Sign::Negative) => sign.negated(),
+            (Sign::NonNegative, Sign::NonNegative) | (Sign::NonPositive, Sign::NonPositive) => {
+                Sign::NonNegative
+            }
+            _ => Sign::NonPositive,
+        }
+    }
+}
+
+/// The properties implied by a symbol property: a `Natural` symbol is
+/// also an `Integer`, and both are `Real`, like `Positive` and `Negative`
+/// symbols.
+pub fn implies(property: &str, implied: &str) -> bool {
+    property == implied
+        || matches!(
+            (property, implied),
+            ("Natural", "Integer")
+                | (
+                    "Natural" | "Integer" | "Positive" | "Negative" | "Pi",
+                    "Real"
+                )
+                | ("Pi", "Positive")
+        )
+}
+
+pub fn is_integer(obj: &Object) -> bool {
+    match obj {
+        Object::Integer(_) => true,
+        Object::Symbol(_) => obj.has_property("Integer"),
+        Object::Expression(Expression::Sum(objs) | Expression::Product(objs)) => {
+            objs.iter().all(is_integer)
+        }
+        Object::Expression(Expression::Power(base, exp)) => {
+            is_integer(base) && sign(exp).is_some_and(Sign::is_nonnegative) && is_integer(exp)
+        }
+        _ => false,
+    }
+}
+
+/// Whether an integer is even, when it can be known.
+pub fn is_even(obj: &Object) -> Option<bool> {
+    if !is_integer(obj) {
+        return None;
+    }
+
+    match obj {
+        Object::Integer(_) => Some(obj.as_rational()?.to_integer().is_even()),
+        Object::Expression(Expression::Sum(terms)) => terms
+            .iter()
+            .try_fold(true, |acc, term| Some(acc == is_even(term)?)),
+        Object::Expression(Expression::Product(factors)) => {
+            let parities: Vec<Option<bool>> = factors.iter().map(is_even).collect();
+
+            if parities.contains(&Some(true)) {
+                Some(true)
+            } else if parities.iter().all(|parity| *parity == Some(false)) {
+                Some(false)
+            } else {
+                None
+            }
+        }
+        Object::Expression(Expression::Power(base, exp)) if !exp.is_zero() => is_even(base),
+        _ => None,
+    }
+}
+
+pub fn sign(obj: &Object) -> Option<Sign> {
+    match obj {
+        obj if obj.is_numeric() => Some(Sign::of(obj)),
+        Object::Symbol(_) if obj.has_property("Natural") => Some(Sign::NonNegative),
+        Object::Symbol(_) if obj.has_property("Positive") => Some(Sign::Positive),
+        Object::Symbol(_) if obj.has_property("Negative") => Some(Sign::Negative),
+        Object::Expression(Expression::Sum(terms)) => terms
+            .iter()
+            .try_fold(Sign::Zero, |acc, term| acc.plus(sign(term)?)),
+        Object::Expression(Expression::Product(factors)) => factors
+            .iter()
+            .try_fold(Sign::Positive, |acc, factor| Some(acc.times(sign(factor)?))),
+        Object::Expression(Expression::Power(base, exp)) => match (sign(base), is_even(exp)) {
+            (Some(Sign::Positive), _) => Some(Sign::Positive),
+            (Some(sign), _) if sign.is_nonnegative() => Some(Sign::NonNegative),
+            (_, Some(true)) => Some(Sign::NonNegative),
+            _ => None,
+        },
+        Object::Expression(Expression::Call(name, _)) if name == "exp" => Some(Sign::Positive),
+        _ => None,
+    }
+}
+
+/// Whether `obj` has the property `property`, when it can be known.
+pub fn satisfies(obj: &Object, property: &str) -> Option<bool> {
+    let is_constant = !obj.is_symbolic() || constant(obj);
+
+    match property {
+        "Integer" if is_integer(obj) => Some(true),
+        "Integer" if is_constant => Some(false),
+        "Natural" => match (satisfies(obj, "Integer")?, sign(obj)) {
+            (false, _) => Some(false),
+            (true, Some(sign)) if sign.is_nonnegative() => Some(true),
+            (true, Some(Sign::Negative)) => Some(false),
+            _ => None,
+        },
+        "Positive" => match sign(obj)? {
+            Sign::Positive => Some(true),
+            sign if sign.is_nonpositive() => Some(false),
+            _ => None,
+        },
+        "Negative" => match sign(obj)? {
+            Sign::Negative => Some(true),
+            sign if sign.is_nonnegative() => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Expressions without symbols are already in their simplest form, so the
+/// ones that are left are irrational. So is `pi`.
+fn constant(obj: &Object) -> bool {
+    match obj {
+        Object::Symbol(_) => obj.has_property("Pi"),
+        Object::Expression(
+            Expression::Sum(objs) | Expression::Product(objs) | Expression::Call(_, objs),
+        ) => objs.iter().all(constant),
+        Object::Expression(Expression::Power(base, exp))
+        | Object::Expression(Expression::Remainder(base, exp))
+        | Object::Expression(Expression::Equation(base, exp))
+        | Object::Expression(Expression::Inequation(base, exp)) => constant(base) && constant(exp),
+        obj => obj.is_numeric(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{int, symbol};
+
+    use super::*;
+
+    #[test]
+    fn integer_parity() {
+        let n = symbol("n", "Integer");
+        let even = int(2).product(&n).unwrap();
+
+        assert_eq!(is_even(&even), Some(true));
+        assert_eq!(is_even(&even.sum(&int(1)).unwrap()), Some(false));
+        assert_eq!(is_even(&n), None);
+        assert_eq!(
+            is_even(&symbol("x", "Real").product(&int(2)).unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn natural_sign() {
+        let n = symbol("n", "Natural");
+
+        assert_eq!(sign(&n.sum(&int(1)).unwrap()), Some(Sign::Positive));
+        assert_eq!(sign(&n.product(&int(-3)).unwrap()), Some(Sign::NonPositive));
+        assert_eq!(
+            sign(&symbol("x", "Real").pow(&int(2)).unwrap()),
+            Some(Sign::NonNegative)
+        );
+        assert_eq!(sign(&n.substraction(&int(1)).unwrap()), None);
+    }
+
+    #[test]
+    fn positive_and_negative_signs() {
+        let p = symbol("p", "Positive");
+        let q = symbol("q", "Negative");
+
+        assert_eq!(sign(&p.sum(&int(1)).unwrap()), Some(Sign::Positive));
+        assert_eq!(sign(&p.product(&q).unwrap()), Some(Sign::Negative));
+        assert_eq!(sign(&p.sum(&q).unwrap()), None);
+        assert_eq!(
+            satisfies(&q.substraction(&p).unwrap(), "Negative"),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn implied_properties() {
+        assert!(implies("Natural", "Integer"));
+        assert!(implies("Integer", "Real"));
+        assert!(implies("Positive", "Real"));
+        assert!(!implies("Real", "Integer"));
+    }
+}
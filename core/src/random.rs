@@ -0,0 +1,349 @@
+use std::{
+    cell::RefCell,
+    cmp::Ordering,
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hash, Hasher},
+    rc::Rc,
+};
+
+use bigdecimal::{Signed, ToPrimitive, Zero};
+use num_bigint::{BigInt, BigUint};
+use num_rational::BigRational;
+
+use crate::{
+    exec::EvalError,
+    object::{Integer, List, Object, Set},
+};
+
+/// The xoshiro256** generator, which is fast and good enough for
+/// simulations, but not for cryptography.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Generator {
+    state: [u64; 4],
+}
+
+impl Generator {
+    /// Fills the state with SplitMix64, so that close seeds give unrelated
+    /// sequences.
+    fn seeded(mut seed: u64) -> Self {
+        let mut state = [0; 4];
+
+        for word in &mut state {
+            seed = seed.wrapping_add(0x9e3779b97f4a7c15);
+            let mut z = seed;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+            *word = z ^ (z >> 31);
+        }
+
+        Self { state }
+    }
+
+    /// A generator that starts somewhere different in every run.
+    fn unpredictable() -> Self {
+        Self::seeded(RandomState::new().build_hasher().finish())
+    }
+
+    fn next(&mut self) -> u64 {
+        let [s0, s1, s2, s3] = &mut self.state;
+        let res = s1.wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = *s1 << 17;
+
+        *s2 ^= *s0;
+        *s3 ^= *s1;
+        *s1 ^= *s2;
+        *s0 ^= *s3;
+        *s2 ^= t;
+        *s3 = s3.rotate_left(45);
+
+        res
+    }
+
+    /// A uniform integer in `0..bound`, for a positive `bound`. Random bits
+    /// are drawn until they give a number below it.
+    fn below(&mut self, bound: &BigUint) -> BigUint {
+        let bits = bound.bits();
+        let words = bits.div_ceil(64);
+        let excess = words * 64 - bits;
+
+        loop {
+            let candidate = BigUint::from_slice(
+                &(0..words)
+                    .flat_map(|_| {
+                        let word = self.next();
+                        [word as u32, (word >> 32) as u32]
+                    })
+                    .collect::<Vec<_>>(),
+            ) >> excess;
+
+            if candidate < *bound {
+                return candidate;
+            }
+        }
+    }
+
+    fn index(&mut self, len: usize) -> usize {
+        self.below(&BigUint::from(len)).to_usize().unwrap()
+    }
+
+    /// Shuffles the first `count` elements with the rest, as the first
+    /// steps of the Fisher-Yates shuffle.
+    fn shuffle_prefix<T>(&mut self, vals: &mut [T], count: usize) {
+        for i in 0..count.min(vals.len()) {
+            let j = i + self.index(vals.len() - i);
+            vals.swap(i, j);
+        }
+    }
+}
+
+/// The random numbers of an execution context. Clones share them, so that
+/// every environment of the same run draws from the same sequence. Without
+/// a seed, the sequence starts somewhere different in every run.
+#[derive(Clone, Debug, Default)]
+pub struct SharedGenerator(Rc<RefCell<Option<Generator>>>);
+
+impl SharedGenerator {
+    pub fn seeded(seed: u64) -> Self {
+        Self(Rc::new(RefCell::new(Some(Generator::seeded(seed)))))
+    }
+
+    /// Makes this the generator of the random functions until the returned
+    /// guard is dropped.
+    pub fn activate(&self) -> ActiveGenerator {
+        ActiveGenerator(ACTIVE_GENERATOR.with(|active| active.replace(self.clone())))
+    }
+}
+
+/// The generator is a setting of the run, not part of the functions whose
+/// environments carry it. Its state also advances with every draw while
+/// those functions are in sets, so every `SharedGenerator` compares equal.
+impl PartialEq for SharedGenerator {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for SharedGenerator {}
+
+impl Hash for SharedGenerator {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
+
+impl PartialOrd for SharedGenerator {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SharedGenerator {
+    fn cmp(&self, _other: &Self) -> Ordering {
+        Ordering::Equal
+    }
+}
+
+/// Puts the previously active generator back when dropped.
+pub struct ActiveGenerator(SharedGenerator);
+
+impl Drop for ActiveGenerator {
+    fn drop(&mut self) {
+        ACTIVE_GENERATOR.with(|active| active.replace(self.0.clone()));
+    }
+}
+
+thread_local! {
+    static ACTIVE_GENERATOR: RefCell<SharedGenerator> = RefCell::default();
+}
+
+/// Starts the random numbers of the active context from `seed`.
+fn install_seed(seed: u64) {
+    ACTIVE_GENERATOR.with(|active| *active.borrow().0.borrow_mut() = Some(Generator::seeded(seed)));
+}
+
+fn with_generator<T>(f: impl FnOnce(&mut Generator) -> T) -> T {
+    ACTIVE_GENERATOR.with(|active| {
+        f(active
+            .borrow()
+            .0
+            .borrow_mut()
+            .get_or_insert_with(Generator::unpredictable))
+    })
+}
+
+fn big(int: &Integer) -> BigInt {
+    Object::Integer(int.to_owned())
+        .as_rational()
+        .unwrap()
+        .to_integer()
+}
+
+/// The elements of a container that can be picked from.
+fn elements(func: &str, obj: &Object) -> Result<Vec<Object>, EvalError> {
+    match obj {
+        Object::List(list) => Ok(list.list.to_owned()),
+        Object::Tuple(tuple) => Ok(tuple.list.to_owned()),
+        Object::Set(set) => Ok(set.set.iter().cloned().collect()),
+        obj => Err(EvalError::bad_argument(func, "List, Tuple or Set", obj)),
+    }
+}
+
+/// Uses `n` modulo `2**64` as the seed.
+pub fn seed(args: &[Object]) -> Result<Object, EvalError> {
+    let n = match &args[0] {
+        Object::Integer(_) => args[0].as_rational().unwrap().to_integer(),
+        obj => return Err(EvalError::bad_argument("seed", "Integer", obj)),
+    };
+
+    let (_, digits) = n.to_u64_digits();
+    let word = digits.first().copied().unwrap_or_default();
+    install_seed(if n.is_negative() {
+        word.wrapping_neg()
+    } else {
+        word
+    });
+
+    Ok(Object::empty_tuple())
+}
+
+/// A uniform integer in a range, which excludes its end.
+pub fn rand_int(args: &[Object]) -> Result<Object, EvalError> {
+    let (start, end) = match &args[0] {
+        Object::Range(range) => (big(&range.start), big(&range.end)),
+        obj => return Err(EvalError::bad_argument("randInt", "Range", obj)),
+    };
+
+    match (&end - &start).to_biguint().filter(|len| !len.is_zero()) {
+        Some(len) => Ok(Object::int(
+            start + BigInt::from(with_generator(|g| g.below(&len))),
+        )),
+        None => Err(EvalError::bad_argument(
+            "randInt",
+            "nonempty Range",
+            &args[0],
+        )),
+    }
+}
+
+/// A uniform element of a container, or of a range.
+pub fn choice(args: &[Object]) -> Result<Object, EvalError> {
+    if let Object::Range(_) = &args[0] {
+        return rand_int(args)
+            .map_err(|_| EvalError::bad_argument("choice", "nonempty Range", &args[0]));
+    }
+
+    let mut vals = elements("choice", &args[0])?;
+    if vals.is_empty() {
+        return Err(EvalError::bad_argument(
+            "choice",
+            "nonempty container",
+            &args[0],
+        ));
+    }
+
+    let i = with_generator(|g| g.index(vals.len()));
+    Ok(vals.swap_remove(i))
+}
+
+/// `k` different elements of a set, or of a list in a random order.
+pub fn sample(args: &[Object]) -> Result<Object, EvalError> {
+    let mut vals = elements("sample", &args[0])?;
+
+    let k = match &args[1] {
+        Object::Integer(_) => args[1]
+            .as_rational()
+            .unwrap()
+            .to_integer()
+            .to_usize()
+            .filter(|&k| k <= vals.len()),
+        _ => None,
+    }
+    .ok_or_else(|| EvalError::bad_argument("sample", "Integer up to the size", &args[1]))?;
+
+    with_generator(|g| g.shuffle_prefix(&mut vals, k));
+    vals.truncate(k);
+
+    match &args[0] {
+        Object::Set(_) => Ok(Object::Set(Set::from(vals))),
+        _ => Ok(Object::List(List::from(vals))),
+    }
+}
+
+pub fn shuffle(args: &[Object]) -> Result<Object, EvalError> {
+    let mut vals = match &args[0] {
+        Object::List(list) => list.list.to_owned(),
+        obj => return Err(EvalError::bad_argument("shuffle", "List", obj)),
+    };
+
+    let len = vals.len();
+    with_generator(|g| g.shuffle_prefix(&mut vals, len));
+
+    Ok(Object::List(List::from(vals)))
+}
+
+/// A uniform fraction in `[0, 1)` with denominator `n`, before it is
+/// reduced.
+pub fn rand_fraction(args: &[Object]) -> Result<Object, EvalError> {
+    let denom = match args[0].as_rational() {
+        Some(val) if matches!(args[0], Object::Integer(_)) && val.is_positive() => val.to_integer(),
+        _ => {
+            return Err(EvalError::bad_argument(
+                "randFraction",
+                "positive Integer",
+                &args[0],
+            ))
+        }
+    };
+
+    let numer = with_generator(|g| g.below(denom.magnitude()));
+
+    Ok(Object::from_rational(BigRational::new(numer.into(), denom)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reproducible() {
+        let mut first = Generator::seeded(42);
+        let mut second = Generator::seeded(42);
+        let mut other = Generator::seeded(43);
+
+        let vals: Vec<u64> = (0..5).map(|_| first.next()).collect();
+        assert_eq!(vals, (0..5).map(|_| second.next()).collect::<Vec<_>>());
+        assert_ne!(vals, (0..5).map(|_| other.next()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn shared_generators_compare_equal() {
+        assert_eq!(SharedGenerator::seeded(1), SharedGenerator::seeded(2));
+        assert_eq!(SharedGenerator::seeded(1), SharedGenerator::default());
+    }
+
+    #[test]
+    fn uniform_below() {
+        let mut generator = Generator::seeded(7);
+        let bound = BigUint::from(6_u32);
+        let mut counts = [0; 6];
+
+        for _ in 0..6000 {
+            counts[generator.below(&bound).to_usize().unwrap()] += 1;
+        }
+
+        assert!(counts.iter().all(|&count| (900..1100).contains(&count)));
+
+        let big = BigUint::from(10_u32).pow(30);
+        assert!((0..100).all(|_| generator.below(&big) < big));
+    }
+
+    #[test]
+    fn shuffle_keeps_elements() {
+        let mut generator = Generator::seeded(1);
+        let mut vals: Vec<u32> = (0..20).collect();
+
+        generator.shuffle_prefix(&mut vals, 20);
+        assert_ne!(vals, (0..20).collect::<Vec<_>>());
+
+        vals.sort();
+        assert_eq!(vals, (0..20).collect::<Vec<_>>());
+    }
+}
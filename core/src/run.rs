@@ -2,6 +2,7 @@ use std::{fs, path::Path};
 
 use crate::{
     ast::{ASTNode, ASTNodeKind},
+    builtin::native_module,
     cst::CSTNode,
     env::{EnvResponse, Environment},
     error::{Error, Position},
@@ -15,6 +16,7 @@ use crate::{
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ImportError {
     SymbolNotFound { module: String, symbol: String },
+    ModuleNotFound(String),
 }
 
 fn collect_nodes<T: Iterator<Item = Result<Token, Error>>>(
@@ -29,6 +31,7 @@ pub fn run(source: &str, env: &mut Environment) -> Result<(), Error> {
     let lexer = Lexer::from(source);
     let parser = Parser::from(lexer);
     let nodes = collect_nodes(parser)?;
+    let _ctx = env.ctx.activate();
 
     for node in nodes {
         run_node(node, env)?;
@@ -38,6 +41,7 @@ pub fn run(source: &str, env: &mut Environment) -> Result<(), Error> {
 }
 
 pub fn run_node(node: ASTNode, env: &mut Environment) -> Result<Object, Error> {
+    let _ctx = env.ctx.activate();
     exec(&node, env)
 }
 
@@ -47,7 +51,11 @@ fn is_std_module(module_name: &str) -> bool {
 
 static STDLIB_PATH: &str = "../std/";
 
-fn get_module_code(module_name: &str, env: &Environment) -> Result<String, Error> {
+fn get_module_code(
+    module_name: &str,
+    env: &Environment,
+    position: Position,
+) -> Result<String, Error> {
     let reference_path = &env.ctx.reference_path;
 
     let path = if is_std_module(module_name) {
@@ -56,31 +64,52 @@ fn get_module_code(module_name: &str, env: &Environment) -> Result<String, Error
         reference_path.join(Path::new(&format!("{module_name}.komodo")))
     };
 
-    let source = fs::read_to_string(path).unwrap();
-    Ok(source)
+    fs::read_to_string(path).map_err(|_| {
+        Error::new(
+            ImportError::ModuleNotFound(module_name.into()).into(),
+            position,
+        )
+    })
 }
 
-pub fn import_from(
+/// Runs the declarations and imports of a module written in Komodo. They
+/// run with the context of the importer, so a module's values are computed
+/// with the precision and random numbers of the program that imports it.
+fn module_env(
     module_name: &str,
-    values: &[(String, Position)],
-    env: &mut Environment,
-) -> Result<(), Error> {
-    let source = get_module_code(module_name, env)?;
+    env: &Environment,
+    position: Position,
+) -> Result<Environment, Error> {
+    let source = get_module_code(module_name, env, position)?;
     let lexer = Lexer::from(source.as_str());
     let parser = Parser::from(lexer);
     let nodes = collect_nodes(parser)?;
 
-    let mut temp_env = Environment::default();
+    let mut module = Environment::new(env.ctx.clone());
 
     for node in nodes {
         match &node.kind {
             ASTNodeKind::Declaration { .. } | ASTNodeKind::ImportFrom { .. } => {
-                run_node(node, &mut temp_env)?;
+                run_node(node, &mut module)?;
             }
             _ => continue,
         }
     }
 
+    Ok(module)
+}
+
+pub fn import_from(
+    module_name: &str,
+    values: &[(String, Position)],
+    env: &mut Environment,
+    position: Position,
+) -> Result<(), Error> {
+    let mut temp_env = match native_module(module_name, &env.ctx) {
+        Some(module) => module,
+        None => module_env(module_name, env, position)?,
+    };
+
     for (value, position) in values {
         match temp_env.get(value) {
             EnvResponse::Mutable(obj) => env.set_mutable(value, obj.to_owned()),
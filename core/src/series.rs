@@ -0,0 +1,610 @@
+use bigdecimal::{One, Signed, ToPrimitive};
+use num_rational::BigRational;
+
+use crate::{
+    calculus::depends_on,
+    elementary,
+    exec::EvalError,
+    object::{Object, Symbol},
+    properties::{self, Sign},
+    simplify::simplify,
+    symbolic::{self, Expression},
+};
+
+/// The largest number of extra terms computed to make up for the ones that
+/// cancel out, like in `(sin(x) - x)/x**3`.
+const MAX_EXTRA_TERMS: i64 = 32;
+
+fn add(lhs: &Object, rhs: &Object) -> Object {
+    simplify(&Object::Expression(Expression::Sum(vec![
+        lhs.to_owned(),
+        rhs.to_owned(),
+    ])))
+}
+
+fn multiply(factors: Vec<Object>) -> Object {
+    simplify(&Object::Expression(Expression::Product(factors)))
+}
+
+fn power(base: &Object, exp: Object) -> Object {
+    simplify(&Object::Expression(Expression::Power(
+        Box::new(base.to_owned()),
+        Box::new(exp),
+    )))
+}
+
+fn total(terms: Vec<Object>) -> Object {
+    simplify(&Object::Expression(Expression::Sum(terms)))
+}
+
+pub fn infinity() -> Object {
+    Object::Symbol(Symbol::new("infinity".into(), "Infinity".into()))
+}
+
+fn minus_infinity() -> Object {
+    multiply(vec![Object::int(-1), infinity()])
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Point {
+    Finite(Object),
+    Infinity,
+    MinusInfinity,
+}
+
+impl Point {
+    fn from(obj: &Object) -> Self {
+        let obj = simplify(obj);
+
+        if obj == infinity() {
+            Point::Infinity
+        } else if obj == minus_infinity() {
+            Point::MinusInfinity
+        } else {
+            Point::Finite(obj)
+        }
+    }
+}
+
+/// A truncated Laurent series `c_0*t**start + c_1*t**(start + 1) + ...`,
+/// where every term with an exponent below `precision` is known.
+#[derive(Clone, Debug, PartialEq)]
+struct Series {
+    start: i64,
+    coefficients: Vec<Object>,
+}
+
+impl Series {
+    fn new(start: i64, coefficients: Vec<Object>) -> Self {
+        let mut res = Self {
+            start,
+            coefficients,
+        };
+
+        let zeros = res
+            .coefficients
+            .iter()
+            .take_while(|coefficient| coefficient.is_zero())
+            .count();
+        res.coefficients.drain(..zeros);
+        res.start += zeros as i64;
+
+        res
+    }
+
+    fn constant(val: Object, precision: i64) -> Self {
+        let mut coefficients = vec![val];
+        coefficients.resize(precision.max(0) as usize, Object::int(0));
+
+        Self::new(precision.min(0), coefficients)
+    }
+
+    /// The variable, written in terms of `t`, which goes to zero as the
+    /// variable goes to `point`.
+    fn variable(point: &Point, precision: i64) -> Self {
+        let (start, mut coefficients) = match point {
+            Point::Finite(point) => (0, vec![point.to_owned(), Object::int(1)]),
+            Point::Infinity => (-1, vec![Object::int(1)]),
+            Point::MinusInfinity => (-1, vec![Object::int(-1)]),
+        };
+
+        let len = coefficients.len().max((precision - start).max(0) as usize);
+        coefficients.resize(len, Object::int(0));
+        Self::new(start, coefficients)
+    }
+
+    fn precision(&self) -> i64 {
+        self.start + self.coefficients.len() as i64
+    }
+
+    fn coefficient(&self, exp: i64) -> Object {
+        match exp - self.start {
+            index if index < 0 => Object::int(0),
+            index => self.coefficients[index as usize].to_owned(),
+        }
+    }
+
+    /// The coefficients from the constant term up to the precision, if
+    /// there are no negative powers.
+    fn taylor(&self) -> Option<Vec<Object>> {
+        if self.start < 0 {
+            return None;
+        }
+
+        Some(
+            (0..self.precision())
+                .map(|exp| self.coefficient(exp))
+                .collect(),
+        )
+    }
+
+    fn sum(&self, other: &Self) -> Self {
+        let start = self.start.min(other.start);
+        let precision = self.precision().min(other.precision());
+
+        Self::new(
+            start,
+            (start..precision)
+                .map(|exp| add(&self.coefficient(exp), &other.coefficient(exp)))
+                .collect(),
+        )
+    }
+
+    fn product(&self, other: &Self) -> Self {
+        let start = self.start + other.start;
+        let precision = (self.start + other.precision()).min(other.start + self.precision());
+
+        Self::new(
+            start,
+            (start..precision)
+                .map(|exp| {
+                    total(
+                        (self.start..=exp - other.start)
+                            .map(|i| {
+                                multiply(vec![self.coefficient(i), other.coefficient(exp - i)])
+                            })
+                            .collect(),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// Raises the series to a rational power with the J. C. P. Miller recurrence.
+    fn power(&self, exp: &BigRational) -> Option<Self> {
+        if self.coefficients.is_empty() {
+            return match exp.is_integer() && exp.is_positive() {
+                true => Some(Self::new(
+                    (BigRational::from_integer(self.start.into()) * exp)
+                        .to_integer()
+                        .to_i64()?,
+                    vec![],
+                )),
+                false => None,
+            };
+        }
+
+        let start = BigRational::from_integer(self.start.into()) * exp;
+        if !start.is_integer() {
+            return None;
+        }
+
+        let lead = &self.coefficients[0];
+        let exp_obj = Object::from_rational(exp.to_owned());
+        let mut res = vec![power(lead, exp_obj.to_owned())];
+
+        for n in 1..self.coefficients.len() {
+            let terms = (1..=n)
+                .map(|k| {
+                    let weight = (exp + BigRational::one()) * BigRational::from_integer(k.into())
+                        - BigRational::from_integer(n.into());
+
+                    multiply(vec![
+                        Object::from_rational(weight),
+                        self.coefficients[k].to_owned(),
+                        res[n - k].to_owned(),
+                    ])
+                })
+                .collect();
+
+            res.push(multiply(vec![
+                total(terms),
+                power(
+                    &multiply(vec![Object::int(n as i64), lead.to_owned()]),
+                    Object::int(-1),
+                ),
+            ]));
+        }
+
+        Some(Self::new(start.to_integer().to_i64()?, res))
+    }
+
+    fn exp(&self) -> Option<Self> {
+        let f = self.taylor()?;
+        let mut res = vec![elementary::apply("exp", f.first()?)];
+
+        for n in 1..f.len() {
+            let terms = (1..=n)
+                .map(|k| {
+                    multiply(vec![
+                        Object::int(k as i64),
+                        f[k].to_owned(),
+                        res[n - k].to_owned(),
+                    ])
+                })
+                .collect();
+
+            res.push(multiply(vec![
+                total(terms),
+                power(&Object::int(n as i64), Object::int(-1)),
+            ]));
+        }
+
+        Some(Self::new(0, res))
+    }
+
+    fn ln(&self) -> Option<Self> {
+        if self.start != 0 {
+            return None;
+        }
+
+        let f = &self.coefficients;
+        let lead_inverse = power(&f[0], Object::int(-1));
+        let mut res = vec![elementary::apply("ln", &f[0])];
+
+        for n in 1..f.len() {
+            let terms = (1..n)
+                .map(|k| {
+                    multiply(vec![
+                        Object::int(k as i64),
+                        res[k].to_owned(),
+                        f[n - k].to_owned(),
+                    ])
+                })
+                .collect();
+
+            res.push(multiply(vec![
+                lead_inverse.to_owned(),
+                add(
+                    &f[n],
+                    &multiply(vec![
+                        Object::int(-1),
+                        total(terms),
+                        power(&Object::int(n as i64), Object::int(-1)),
+                    ]),
+                ),
+            ]));
+        }
+
+        Some(Self::new(0, res))
+    }
+
+    /// The sine and cosine, from `sin' = cos*f'` and `cos' = -sin*f'`.
+    fn sin_cos(&self) -> Option<(Self, Self)> {
+        let f = self.taylor()?;
+        let mut sin = vec![elementary::apply("sin", f.first()?)];
+        let mut cos = vec![elementary::apply("cos", &f[0])];
+
+        for n in 1..f.len() {
+            let inverse = power(&Object::int(n as i64), Object::int(-1));
+            let sin_terms = (1..=n)
+                .map(|k| {
+                    multiply(vec![
+                        Object::int(k as i64),
+                        f[k].to_owned(),
+                        cos[n - k].to_owned(),
+                    ])
+                })
+                .collect();
+            let cos_terms = (1..=n)
+                .map(|k| {
+                    multiply(vec![
+                        Object::int(-(k as i64)),
+                        f[k].to_owned(),
+                        sin[n - k].to_owned(),
+                    ])
+                })
+                .collect();
+
+            sin.push(multiply(vec![total(sin_terms), inverse.to_owned()]));
+            cos.push(multiply(vec![total(cos_terms), inverse]));
+        }
+
+        Some((Self::new(0, sin), Self::new(0, cos)))
+    }
+
+    /// The arctangent, from `(1 + f**2)*atan(f)' = f'`.
+    fn atan(&self) -> Option<Self> {
+        let f = self.taylor()?;
+        let square = Self::new(0, f.to_owned()).power(&BigRational::from_integer(2.into()))?;
+        let q: Vec<Object> = (0..f.len() as i64)
+            .map(|exp| match exp {
+                0 => add(&Object::int(1), &square.coefficient(0)),
+                exp => square.coefficient(exp),
+            })
+            .collect();
+
+        let mut res = vec![elementary::apply("atan", f.first()?)];
+        for n in 0..f.len() - 1 {
+            let terms = (1..=n)
+                .map(|k| {
+                    multiply(vec![
+                        q[k].to_owned(),
+                        Object::int((n - k + 1) as i64),
+                        res[n - k + 1].to_owned(),
+                    ])
+                })
+                .collect();
+
+            res.push(multiply(vec![
+                add(
+                    &multiply(vec![Object::int((n + 1) as i64), f[n + 1].to_owned()]),
+                    &multiply(vec![Object::int(-1), total(terms)]),
+                ),
+                power(
+                    &multiply(vec![Object::int((n + 1) as i64), q[0].to_owned()]),
+                    Object::int(-1),
+                ),
+            ]));
+        }
+
+        Some(Self::new(0, res))
+    }
+}
+
+fn expand(expr: &Object, var: &Symbol, point: &Point, precision: i64) -> Option<Series> {
+    if !depends_on(expr, var) {
+        return Some(Series::constant(expr.to_owned(), precision));
+    }
+
+    let expand = |expr: &Object| expand(expr, var, point, precision);
+
+    match expr {
+        Object::Symbol(_) => Some(Series::variable(point, precision)),
+        Object::Expression(Expression::Sum(terms)) => {
+            let mut terms = terms.iter().map(expand);
+            let first = terms.next()??;
+            terms.try_fold(first, |acc, term| Some(acc.sum(&term?)))
+        }
+        Object::Expression(Expression::Product(factors)) => {
+            let mut factors = factors.iter().map(expand);
+            let first = factors.next()??;
+            factors.try_fold(first, |acc, factor| Some(acc.product(&factor?)))
+        }
+        Object::Expression(Expression::Power(base, exp)) => match exp.as_rational() {
+            Some(exp) => expand(base)?.power(&exp),
+            None => {
+                let exponent = multiply(vec![*exp.to_owned(), elementary::apply("ln", base)]);
+                expand(&exponent)?.exp()
+            }
+        },
+        Object::Expression(Expression::Call(name, args)) if args.len() == 1 => {
+            let arg = expand(&args[0])?;
+
+            match name.as_str() {
+                "exp" => arg.exp(),
+                "ln" => arg.ln(),
+                "sin" => Some(arg.sin_cos()?.0),
+                "cos" => Some(arg.sin_cos()?.1),
+                "tan" => {
+                    let (sin, cos) = arg.sin_cos()?;
+                    Some(sin.product(&cos.power(&-BigRational::one())?))
+                }
+                "atan" => arg.atan(),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Expands `expr` until every term with an exponent up to `order` is known.
+/// An expansion can also fail for lack of terms, like when dividing by
+/// `sin(x) - x`, so it is retried with more of them.
+fn expansion(expr: &Object, var: &Symbol, point: &Point, order: i64) -> Option<Series> {
+    let mut extra = 0;
+
+    while extra <= MAX_EXTRA_TERMS {
+        match expand(expr, var, point, order + 1 + extra) {
+            Some(res) if res.precision() > order => return Some(res),
+            _ => (),
+        }
+
+        extra = (extra * 2).max(2);
+    }
+
+    None
+}
+
+fn limit_of(expr: &Object, var: &Symbol, point: &Point) -> Option<Object> {
+    let res = expansion(expr, var, point, 0)?;
+
+    if res.start >= 0 {
+        return Some(res.coefficient(0));
+    }
+
+    let lead = &res.coefficients[0];
+    let is_one_sided = matches!(point, Point::Infinity | Point::MinusInfinity);
+
+    if !is_one_sided && res.start % 2 != 0 {
+        return None;
+    }
+
+    match properties::sign(lead)? {
+        Sign::Positive => Some(infinity()),
+        Sign::Negative => Some(minus_infinity()),
+        _ => None,
+    }
+}
+
+fn arguments<'a>(func: &str, args: &'a [Object]) -> Result<(Object, &'a Symbol), EvalError> {
+    let bad_argument = |expected: &str, obj: &Object| EvalError::bad_argument(func, expected, obj);
+
+    let expr = match &args[0] {
+        Object::Polynomial(poly) => poly.to_expression(),
+        obj if obj.is_numeric() || obj.is_symbolic() => simplify(obj),
+        obj => return Err(bad_argument("Expression", obj)),
+    };
+
+    match &args[1] {
+        Object::Symbol(var) => Ok((expr, var)),
+        obj => Err(bad_argument("Symbol", obj)),
+    }
+}
+
+pub fn limit(args: &[Object]) -> Result<Object, EvalError> {
+    let (expr, var) = arguments("limit", args)?;
+
+    match limit_of(&expr, var, &Point::from(&args[2])) {
+        Some(res) => Ok(res),
+        None => Ok(symbolic::call(
+            "limit",
+            vec![expr, Object::Symbol(var.to_owned()), args[2].to_owned()],
+        )),
+    }
+}
+
+pub fn series(args: &[Object]) -> Result<Object, EvalError> {
+    let (expr, var) = arguments("series", args)?;
+    let point = Point::from(&args[2]);
+
+    let order = match args[3].as_rational() {
+        Some(val) if val.is_integer() && !val.is_negative() => {
+            val.to_integer().to_i64().unwrap_or(i64::MAX)
+        }
+        _ => {
+            return Err(EvalError::bad_argument(
+                "series",
+                "nonnegative Integer",
+                &args[3],
+            ))
+        }
+    };
+
+    let res = match expansion(&expr, var, &point, order) {
+        Some(res) => res,
+        None => {
+            return Ok(symbolic::call(
+                "series",
+                vec![
+                    expr,
+                    Object::Symbol(var.to_owned()),
+                    args[2].to_owned(),
+                    args[3].to_owned(),
+                ],
+            ))
+        }
+    };
+
+    let var = Object::Symbol(var.to_owned());
+    let t = match point {
+        Point::Finite(point) => add(&var, &multiply(vec![Object::int(-1), point])),
+        Point::Infinity => power(&var, Object::int(-1)),
+        Point::MinusInfinity => multiply(vec![Object::int(-1), power(&var, Object::int(-1))]),
+    };
+
+    Ok(total(
+        (res.start..=order)
+            .map(|exp| multiply(vec![res.coefficient(exp), power(&t, Object::int(exp))]))
+            .collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{int, x};
+
+    use super::*;
+
+    fn call(name: &str, arg: Object) -> Object {
+        symbolic::call(name, vec![arg])
+    }
+
+    #[test]
+    fn sine_series() {
+        let res = series(&[call("sin", x()), x(), int(0), int(5)]);
+
+        assert_eq!(res.unwrap().to_string(), "x**5/120 - x**3/6 + x");
+    }
+
+    #[test]
+    fn arctangent_series() {
+        let res = series(&[call("atan", x()), x(), int(0), int(5)]);
+
+        assert_eq!(res.unwrap().to_string(), "x**5/5 - x**3/3 + x");
+    }
+
+    #[test]
+    fn logarithm_series() {
+        let res = series(&[call("ln", int(1).sum(&x()).unwrap()), x(), int(0), int(3)]);
+
+        assert_eq!(res.unwrap().to_string(), "x**3/3 - x**2/2 + x");
+    }
+
+    #[test]
+    fn negative_order() {
+        let res = series(&[call("exp", x()), x(), int(0), int(-1)]);
+
+        assert_eq!(
+            res,
+            Err(EvalError::BadArgument {
+                func: "series".into(),
+                expected: "nonnegative Integer".into(),
+                actual: "Integer".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn exponential_at_one() {
+        let res = series(&[call("exp", x()), x(), int(1), int(2)]);
+
+        assert_eq!(res.unwrap().to_string(), "x**2*exp(1)/2 + exp(1)/2");
+    }
+
+    #[test]
+    fn removable_singularity() {
+        let expr = call("sin", x()).over(&x()).unwrap();
+
+        assert_eq!(limit(&[expr, x(), int(0)]), Ok(int(1)));
+    }
+
+    #[test]
+    fn cancellation() {
+        // (1 - cos(x))/x**2 goes to 1/2
+        let expr = int(1)
+            .substraction(&call("cos", x()))
+            .unwrap()
+            .over(&x().pow(&int(2)).unwrap())
+            .unwrap();
+
+        assert_eq!(limit(&[expr, x(), int(0)]).unwrap().to_string(), "1 // 2");
+    }
+
+    #[test]
+    fn limit_at_infinity() {
+        // (2x**2 + x)/(x**2 + 1) goes to 2
+        let numer = int(2)
+            .product(&x().pow(&int(2)).unwrap())
+            .unwrap()
+            .sum(&x())
+            .unwrap();
+        let denom = x().pow(&int(2)).unwrap().sum(&int(1)).unwrap();
+
+        assert_eq!(
+            limit(&[numer.over(&denom).unwrap(), x(), infinity()]),
+            Ok(int(2))
+        );
+        assert_eq!(limit(&[x(), x(), infinity()]), Ok(infinity()));
+    }
+
+    #[test]
+    fn no_two_sided_limit() {
+        let expr = int(1).over(&x()).unwrap();
+
+        assert_eq!(
+            limit(&[expr, x(), int(0)]).unwrap().to_string(),
+            "limit(1/x, x, 0)"
+        );
+    }
+}
@@ -0,0 +1,573 @@
+use std::{cmp::Reverse, collections::BTreeMap};
+
+use bigdecimal::{
+    num_traits::{Pow, ToPrimitive},
+    Signed, Zero,
+};
+use num_bigint::BigInt;
+use num_rational::BigRational;
+
+use crate::{
+    object::Object,
+    properties::{self, Sign},
+    symbolic::{self, Expression},
+};
+
+/// Whether `obj` is a number, which operates with the other numbers of an
+/// expression as it is simplified.
+fn is_constant(obj: &Object) -> bool {
+    obj.is_numeric() || matches!(obj, Object::Complex(_) | Object::Modular(_))
+}
+
+fn normalized(obj: Object) -> Object {
+    match obj.as_rational() {
+        Some(val) => Object::from_rational(val),
+        None => obj,
+    }
+}
+
+fn add(lhs: &Object, rhs: &Object) -> Object {
+    normalized(lhs.sum(rhs).unwrap())
+}
+
+fn multiply(lhs: &Object, rhs: &Object) -> Object {
+    normalized(lhs.product(rhs).unwrap())
+}
+
+/// Brings an object to its canonical form. Two expressions that are
+/// equal after simplification compare equal with `==`.
+pub fn simplify(obj: &Object) -> Object {
+    match obj {
+        Object::Fraction(_) => normalized(obj.to_owned()),
+        Object::Expression(Expression::Sum(terms)) => {
+            simplify_sum(terms.iter().map(simplify).collect())
+        }
+        Object::Expression(Expression::Product(factors)) => {
+            simplify_product(factors.iter().map(simplify).collect())
+        }
+        Object::Expression(Expression::Power(base, exp)) => {
+            simplify_power(simplify(base), simplify(exp))
+        }
+        Object::Expression(Expression::Remainder(lhs, rhs)) => {
+            simplify_remainder(simplify(lhs), simplify(rhs))
+        }
+        Object::Expression(Expression::Call(name, args)) => Object::Expression(Expression::Call(
+            name.to_owned(),
+            args.iter().map(simplify).collect(),
+        )),
+        Object::Expression(Expression::Equation(lhs, rhs)) => {
+            symbolic::equation(simplify(lhs), simplify(rhs)).unwrap()
+        }
+        Object::Expression(Expression::Inequation(lhs, rhs)) => {
+            symbolic::inequation(simplify(lhs), simplify(rhs)).unwrap()
+        }
+        Object::Expression(Expression::Range(start, end)) => {
+            symbolic::range(simplify(start), simplify(end))
+        }
+        obj => obj.to_owned(),
+    }
+}
+
+fn split_coefficient(term: Object) -> (Object, Object) {
+    match term {
+        Object::Expression(Expression::Product(mut factors)) if is_constant(&factors[0]) => {
+            let coefficient = factors.remove(0);
+            let monomial = match factors.len() {
+                1 => factors.remove(0),
+                _ => Object::Expression(Expression::Product(factors)),
+            };
+
+            (coefficient, monomial)
+        }
+        term => (Object::int(1), term),
+    }
+}
+
+fn with_coefficient(coefficient: Object, monomial: Object) -> Object {
+    if coefficient.is_one() {
+        return monomial;
+    }
+
+    match monomial {
+        Object::Expression(Expression::Product(mut factors)) => {
+            factors.insert(0, coefficient);
+            Object::Expression(Expression::Product(factors))
+        }
+        monomial => Object::Expression(Expression::Product(vec![coefficient, monomial])),
+    }
+}
+
+fn degree(obj: &Object) -> BigRational {
+    match obj {
+        Object::Symbol(_) => BigRational::from_integer(1.into()),
+        Object::Expression(Expression::Product(factors)) => factors.iter().map(degree).sum(),
+        Object::Expression(Expression::Sum(terms)) => {
+            terms.iter().map(degree).max().unwrap_or_default()
+        }
+        Object::Expression(Expression::Power(base, exp)) => match exp.as_rational() {
+            Some(exp) => degree(base) * exp,
+            None => BigRational::zero(),
+        },
+        _ => BigRational::zero(),
+    }
+}
+
+fn simplify_sum(terms: Vec<Object>) -> Object {
+    let mut constant = Object::int(0);
+    let mut coefficients: BTreeMap<Object, Object> = BTreeMap::new();
+
+    for term in terms {
+        let terms = match term {
+            Object::Expression(Expression::Sum(terms)) => terms,
+            term => vec![term],
+        };
+
+        for term in terms {
+            if is_constant(&term) {
+                constant = add(&constant, &term);
+            } else {
+                let (coefficient, monomial) = split_coefficient(term);
+                let total = match coefficients.get(&monomial) {
+                    Some(previous) => add(previous, &coefficient),
+                    None => coefficient,
+                };
+
+                coefficients.insert(monomial, total);
+            }
+        }
+    }
+
+    let mut monomials: Vec<(Object, Object)> = coefficients
+        .into_iter()
+        .filter(|(_, coefficient)| !coefficient.is_zero())
+        .collect();
+
+    monomials.sort_by_key(|(monomial, _)| (Reverse(degree(monomial)), monomial.to_owned()));
+
+    let mut terms: Vec<Object> = monomials
+        .into_iter()
+        .map(|(monomial, coefficient)| with_coefficient(coefficient, monomial))
+        .collect();
+
+    if !constant.is_zero() || terms.is_empty() {
+        terms.push(constant);
+    }
+
+    match terms.len() {
+        1 => terms.remove(0),
+        _ => Object::Expression(Expression::Sum(terms)),
+    }
+}
+
+fn simplify_product(factors: Vec<Object>) -> Object {
+    let mut coefficient = Object::int(1);
+    let mut exponents: BTreeMap<Object, Object> = BTreeMap::new();
+
+    for factor in factors {
+        let factors = match factor {
+            Object::Expression(Expression::Product(factors)) => factors,
+            factor => vec![factor],
+        };
+
+        for factor in factors {
+            if is_constant(&factor) {
+                coefficient = multiply(&coefficient, &factor);
+                continue;
+            }
+
+            let (base, exp) = match factor {
+                Object::Expression(Expression::Power(base, exp)) => (*base, *exp),
+                factor => (factor, Object::int(1)),
+            };
+
+            let total = match exponents.get(&base) {
+                Some(previous) => simplify_sum(vec![previous.to_owned(), exp]),
+                None => exp,
+            };
+
+            exponents.insert(base, total);
+        }
+    }
+
+    let mut rest = vec![];
+    let mut must_flatten = false;
+
+    for (base, exp) in exponents {
+        match simplify_power(base, exp) {
+            obj if is_constant(&obj) => coefficient = multiply(&coefficient, &obj),
+            obj => {
+                must_flatten |= matches!(obj, Object::Expression(Expression::Product(_)));
+                rest.push(obj);
+            }
+        }
+    }
+
+    if coefficient.is_zero() {
+        return coefficient;
+    }
+
+    if must_flatten {
+        rest.insert(0, coefficient);
+        return simplify_product(rest);
+    }
+
+    if let Some(index) = rest.iter().position(is_sum) {
+        let terms = match rest.remove(index) {
+            Object::Expression(Expression::Sum(terms)) => terms,
+            _ => unreachable!(),
+        };
+
+        rest.insert(0, coefficient);
+
+        return simplify_sum(
+            terms
+                .into_iter()
+                .map(|term| {
+                    let mut factors = rest.to_owned();
+                    factors.push(term);
+                    simplify_product(factors)
+                })
+                .collect(),
+        );
+    }
+
+    match rest.as_slice() {
+        [] => coefficient,
+        [single] if coefficient.is_one() => single.to_owned(),
+        _ => {
+            if !coefficient.is_one() {
+                rest.insert(0, coefficient);
+            }
+
+            Object::Expression(Expression::Product(rest))
+        }
+    }
+}
+
+fn simplify_power(base: Object, exp: Object) -> Object {
+    if exp.is_zero() {
+        return Object::int(1);
+    }
+
+    if exp.is_one() || base.is_one() {
+        return base;
+    }
+
+    match (&base, &exp) {
+        (base, exp) if is_constant(base) && is_constant(exp) => numeric_power(base, exp),
+        (Object::Expression(Expression::Power(inner, inner_exp)), Object::Integer(_)) => {
+            let exp = simplify_product(vec![*inner_exp.to_owned(), exp.to_owned()]);
+            simplify_power(*inner.to_owned(), exp)
+        }
+        (Object::Expression(Expression::Power(inner, inner_exp)), _)
+            if properties::sign(inner).is_some_and(Sign::is_nonnegative) =>
+        {
+            let exp = simplify_product(vec![*inner_exp.to_owned(), exp.to_owned()]);
+            simplify_power(*inner.to_owned(), exp)
+        }
+        (base, exp) if base.is_numeric() && base.is_negative() => match properties::is_even(exp) {
+            Some(is_even) => {
+                let power = simplify_power(normalized(base.inverse().unwrap()), exp.to_owned());
+
+                if is_even {
+                    power
+                } else {
+                    simplify_product(vec![Object::int(-1), power])
+                }
+            }
+            None => Object::Expression(Expression::Power(
+                Box::new(base.to_owned()),
+                Box::new(exp.to_owned()),
+            )),
+        },
+        (Object::Expression(Expression::Product(factors)), Object::Integer(_)) => simplify_product(
+            factors
+                .iter()
+                .map(|factor| simplify_power(factor.to_owned(), exp.to_owned()))
+                .collect(),
+        ),
+        (Object::Expression(Expression::Sum(_)), Object::Integer(_)) => expand_power(base, exp),
+        _ => Object::Expression(Expression::Power(Box::new(base), Box::new(exp))),
+    }
+}
+
+/// Integers are divided right away, but a remainder by zero is left as it
+/// is, like a division by zero.
+fn simplify_remainder(lhs: Object, rhs: Object) -> Object {
+    match (&lhs, &rhs) {
+        (Object::Integer(_), Object::Integer(_)) if !rhs.is_zero() => lhs.rem(&rhs).unwrap(),
+        _ => Object::Expression(Expression::Remainder(Box::new(lhs), Box::new(rhs))),
+    }
+}
+
+fn is_sum(obj: &Object) -> bool {
+    matches!(obj, Object::Expression(Expression::Sum(_)))
+}
+
+fn terms(obj: &Object) -> Vec<Object> {
+    match obj {
+        Object::Expression(Expression::Sum(terms)) => terms.to_owned(),
+        obj => vec![obj.to_owned()],
+    }
+}
+
+/// Multiplies every term of `lhs` with every term of `rhs`.
+fn distribute(lhs: &Object, rhs: &Object) -> Object {
+    let rhs = terms(rhs);
+
+    simplify_sum(
+        terms(lhs)
+            .iter()
+            .flat_map(|lhs| {
+                rhs.iter()
+                    .map(|rhs| simplify_product(vec![lhs.to_owned(), rhs.to_owned()]))
+            })
+            .collect(),
+    )
+}
+
+/// Multiplies a sum out `|exp|` times, so that powers of sums share the
+/// canonical form of the products they stand for.
+fn expand_power(base: Object, exp: Object) -> Object {
+    let exp = exp.as_rational().unwrap().to_integer();
+
+    let times = match exp.magnitude().to_u32() {
+        Some(times) => times,
+        None => {
+            return Object::Expression(Expression::Power(
+                Box::new(base),
+                Box::new(Object::from_rational(BigRational::from_integer(exp))),
+            ))
+        }
+    };
+
+    let expanded = (1..times).fold(base.to_owned(), |power, _| distribute(&power, &base));
+
+    if exp.is_negative() {
+        Object::Expression(Expression::Power(
+            Box::new(expanded),
+            Box::new(Object::int(-1)),
+        ))
+    } else {
+        expanded
+    }
+}
+
+fn exact_root(val: &BigInt, n: u32) -> Option<BigInt> {
+    if val.is_negative() {
+        return match n % 2 {
+            1 => exact_root(&-val, n).map(|root| -root),
+            _ => None,
+        };
+    }
+
+    let root = val.nth_root(n);
+
+    if Pow::pow(&root, n) == *val {
+        Some(root)
+    } else {
+        None
+    }
+}
+
+fn numeric_power(base: &Object, exp: &Object) -> Object {
+    let unevaluated = || {
+        Object::Expression(Expression::Power(
+            Box::new(base.to_owned()),
+            Box::new(exp.to_owned()),
+        ))
+    };
+
+    if base.is_zero() && exp.is_negative() {
+        return unevaluated();
+    }
+
+    match (base.as_rational(), exp.as_rational()) {
+        (Some(base), Some(exp)) if exp.is_integer() => {
+            Object::from_rational(Pow::pow(base, &exp.to_integer()))
+        }
+        (Some(base), Some(exp)) => {
+            let n = match exp.denom().to_u32() {
+                Some(n) => n,
+                None => return unevaluated(),
+            };
+
+            match (exact_root(base.numer(), n), exact_root(base.denom(), n)) {
+                (Some(numer), Some(denom)) => {
+                    Object::from_rational(Pow::pow(BigRational::new(numer, denom), exp.numer()))
+                }
+                _ => unevaluated(),
+            }
+        }
+        (None, Some(exp)) if exp.is_integer() => base
+            .pow(&Object::from_rational(exp))
+            .unwrap_or_else(unevaluated),
+        _ => unevaluated(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        object::{Decimal, Fraction},
+        testing::{int, symbol, x, y},
+    };
+
+    use super::*;
+
+    fn frac(numer: i32, denom: i32) -> Object {
+        Object::Fraction(Fraction::_new(numer, denom))
+    }
+
+    #[test]
+    fn collect_like_terms() {
+        let expr = x().sum(&x()).unwrap().sum(&y()).unwrap();
+
+        assert_eq!(
+            simplify(&expr),
+            Object::Expression(Expression::Sum(vec![
+                Object::Expression(Expression::Product(vec![int(2), x()])),
+                y(),
+            ]))
+        );
+    }
+
+    #[test]
+    fn fold_constants() {
+        let expr = int(1).sum(&x()).unwrap().sum(&frac(1, 2)).unwrap();
+
+        assert_eq!(simplify(&expr).to_string(), "x + 3 // 2");
+    }
+
+    #[test]
+    fn decimal_coefficients() {
+        let expr = int(2)
+            .product(&x())
+            .unwrap()
+            .sum(
+                &x().product(&Object::Decimal(Decimal::new("2", "0")))
+                    .unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(simplify(&expr).to_string(), "4.0*x");
+    }
+
+    #[test]
+    fn cancel_terms() {
+        let expr = x().substraction(&x()).unwrap();
+
+        assert_eq!(simplify(&expr), int(0));
+    }
+
+    #[test]
+    fn add_exponents() {
+        let expr = x()
+            .pow(&int(2))
+            .unwrap()
+            .product(&y())
+            .unwrap()
+            .product(&x().pow(&y()).unwrap())
+            .unwrap();
+
+        assert_eq!(simplify(&expr).to_string(), "x**(y + 2)*y");
+    }
+
+    #[test]
+    fn canonical_order() {
+        let lhs = x()
+            .pow(&int(2))
+            .unwrap()
+            .sum(&int(1))
+            .unwrap()
+            .sum(&y())
+            .unwrap();
+        let rhs = int(1)
+            .sum(&y())
+            .unwrap()
+            .sum(&x().pow(&int(2)).unwrap())
+            .unwrap();
+
+        assert_eq!(simplify(&lhs), simplify(&rhs));
+        assert_eq!(simplify(&lhs).to_string(), "x**2 + y + 1");
+    }
+
+    #[test]
+    fn distribute_coefficient() {
+        let expr = x()
+            .sum(&int(1))
+            .unwrap()
+            .product(&int(2))
+            .unwrap()
+            .substraction(&x())
+            .unwrap();
+
+        assert_eq!(simplify(&expr).to_string(), "x + 2");
+    }
+
+    #[test]
+    fn expand_products() {
+        let sum = x().sum(&int(1)).unwrap();
+        let expr = sum.product(&sum).unwrap();
+
+        assert_eq!(simplify(&expr).to_string(), "x**2 + 2*x + 1");
+    }
+
+    #[test]
+    fn expand_powers() {
+        let expr = x()
+            .sum(&y())
+            .unwrap()
+            .pow(&int(2))
+            .unwrap()
+            .substraction(&x().pow(&int(2)).unwrap())
+            .unwrap()
+            .substraction(&int(2).product(&x()).unwrap().product(&y()).unwrap())
+            .unwrap()
+            .substraction(&y().pow(&int(2)).unwrap())
+            .unwrap();
+
+        assert_eq!(simplify(&expr), int(0));
+    }
+
+    #[test]
+    fn expand_negative_powers() {
+        let expr = x().sum(&int(1)).unwrap().pow(&int(-2)).unwrap();
+
+        assert_eq!(simplify(&expr).to_string(), "1/(x**2 + 2*x + 1)");
+    }
+
+    #[test]
+    fn quotient_cancels() {
+        let expr = x()
+            .pow(&int(3))
+            .unwrap()
+            .over(&x().product(&x()).unwrap())
+            .unwrap();
+
+        assert_eq!(simplify(&expr), x());
+    }
+
+    #[test]
+    fn exact_numeric_powers() {
+        assert_eq!(simplify_power(int(2), int(-2)), frac(1, 4));
+        assert_eq!(simplify_power(frac(4, 9), frac(1, 2)), frac(2, 3));
+        assert_eq!(
+            simplify_power(int(2), frac(1, 2)),
+            Object::Expression(Expression::Power(Box::new(int(2)), Box::new(frac(1, 2))))
+        );
+    }
+
+    #[test]
+    fn integer_exponent_parity() {
+        let n = symbol("n", "Integer");
+        let even = int(2).product(&n).unwrap();
+
+        assert_eq!(simplify_power(int(-1), even.to_owned()), int(1));
+        assert_eq!(simplify_power(int(-1), even.sum(&int(1)).unwrap()), int(-1));
+        assert_eq!(
+            simplify_power(int(-2), even.to_owned()).to_string(),
+            "2**(2*n)"
+        );
+    }
+}
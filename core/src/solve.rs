@@ -0,0 +1,717 @@
+use std::{cmp::Ordering, collections::BTreeSet};
+
+use bigdecimal::{num_traits::Pow, One, Signed, Zero};
+use num_bigint::BigInt;
+use num_rational::BigRational;
+
+use crate::{
+    approximation, elementary,
+    exec::EvalError,
+    factor::factor_polynomial,
+    numeric,
+    numtheory::integer_factors,
+    object::{Decimal, Fraction, Integer, Object, Precision, Set, Symbol},
+    polynomial::Polynomial,
+    properties,
+    simplify::simplify,
+    symbolic::Expression,
+};
+
+fn rational(val: i32) -> BigRational {
+    BigRational::from_integer(val.into())
+}
+
+fn sum(terms: Vec<Object>) -> Object {
+    simplify(&Object::Expression(Expression::Sum(terms)))
+}
+
+fn product(factors: Vec<Object>) -> Object {
+    simplify(&Object::Expression(Expression::Product(factors)))
+}
+
+fn quotient(lhs: Object, rhs: Object) -> Object {
+    product(vec![
+        lhs,
+        Object::Expression(Expression::Power(Box::new(rhs), Box::new(Object::int(-1)))),
+    ])
+}
+
+/// The real `n`-th root of a rational, taking the perfect powers out of
+/// the radical: `radical(8, 2)` is `2*sqrt(2)`.
+fn radical(val: &BigRational, n: u32) -> Object {
+    if val.is_zero() {
+        return Object::int(0);
+    }
+
+    let denom = val.denom();
+    let radicand = (val.numer() * Pow::pow(denom, n - 1)).abs();
+
+    let mut outside = BigInt::one();
+    let mut inside = BigInt::one();
+    for (prime, multiplicity) in integer_factors(&radicand) {
+        outside *= Pow::pow(&prime, multiplicity / n as usize);
+        inside *= Pow::pow(&prime, multiplicity % n as usize);
+    }
+
+    if val.is_negative() {
+        outside = -outside;
+    }
+
+    let coefficient = Object::from_rational(BigRational::new(outside, denom.to_owned()));
+    if inside.is_one() {
+        return coefficient;
+    }
+
+    product(vec![
+        coefficient,
+        Object::Expression(Expression::Power(
+            Box::new(Object::Integer(Integer::from(inside))),
+            Box::new(Object::Fraction(Fraction::_new(1, n as i32))),
+        )),
+    ])
+}
+
+/// The real cube root of an expression whose sign is known.
+fn cube_root(val: Object, is_positive: bool) -> Object {
+    let third = Object::Fraction(Fraction::_new(1, 3));
+
+    if is_positive {
+        Object::Expression(Expression::Power(Box::new(val), Box::new(third)))
+    } else {
+        product(vec![
+            Object::int(-1),
+            Object::Expression(Expression::Power(
+                Box::new(product(vec![Object::int(-1), val])),
+                Box::new(third),
+            )),
+        ])
+    }
+}
+
+fn quadratic_roots(a: &BigRational, b: &BigRational, c: &BigRational) -> Vec<Object> {
+    let discriminant = b * b - rational(4) * a * c;
+    if discriminant.is_negative() {
+        return vec![];
+    }
+
+    let center = Object::from_rational(-b / (rational(2) * a));
+    let offset = product(vec![
+        Object::from_rational((rational(2) * a).recip()),
+        radical(&discriminant, 2),
+    ]);
+
+    vec![
+        sum(vec![
+            center.to_owned(),
+            product(vec![Object::int(-1), offset.to_owned()]),
+        ]),
+        sum(vec![center, offset]),
+    ]
+}
+
+/// Cardano's formula. It only gives radicals when the cubic has a single
+/// real root; otherwise they would go through complex numbers.
+fn cubic_roots(coefficients: &[BigRational]) -> Option<Vec<Object>> {
+    let [d, c, b, a] = coefficients else {
+        return None;
+    };
+
+    let p = (rational(3) * a * c - b * b) / (rational(3) * a * a);
+    let q = (rational(2) * b * b * b - rational(9) * a * b * c + rational(27) * a * a * d)
+        / (rational(27) * a * a * a);
+
+    let discriminant = &q * &q / rational(4) + &p * &p * &p / rational(27);
+    if !discriminant.is_positive() {
+        return None;
+    }
+
+    let center = -&q / rational(2);
+    let root = radical(&discriminant, 2);
+    let shift = Object::from_rational(-b / (rational(3) * a));
+
+    let (u, v) = match root.as_rational() {
+        Some(root) => (
+            radical(&(&center + &root), 3),
+            radical(&(&center - root), 3),
+        ),
+        None => {
+            let center_obj = Object::from_rational(center.to_owned());
+            let u = sum(vec![center_obj.to_owned(), root.to_owned()]);
+            let v = sum(vec![center_obj, product(vec![Object::int(-1), root])]);
+
+            (
+                cube_root(u, !center.is_negative() || p.is_positive()),
+                cube_root(v, center.is_positive() && p.is_negative()),
+            )
+        }
+    };
+
+    Some(vec![sum(vec![u, v, shift])])
+}
+
+/// Quartics of the form `a*x**4 + c*x**2 + e`, solved as quadratics in `x**2`.
+fn biquadratic_roots(coefficients: &[BigRational]) -> Option<Vec<Object>> {
+    let [e, d, c, b, a] = coefficients else {
+        return None;
+    };
+
+    if !b.is_zero() || !d.is_zero() {
+        return None;
+    }
+
+    let roots_sum = -c / a;
+    let roots_product = e / a;
+
+    let squares = quadratic_roots(a, c, e);
+    let is_positive = [
+        roots_sum.is_positive() && roots_product.is_positive(),
+        roots_sum.is_positive() || roots_product.is_negative(),
+    ];
+
+    let mut res = vec![];
+    for (square, is_positive) in squares.into_iter().zip(is_positive) {
+        if is_positive {
+            let root = elementary::apply("sqrt", &square);
+            res.push(product(vec![Object::int(-1), root.to_owned()]));
+            res.push(root);
+        }
+    }
+
+    Some(res)
+}
+
+/// The rational roots of a polynomial, from its linear factors.
+fn linear_roots(coefficients: &[BigRational]) -> Vec<BigRational> {
+    let var = Symbol::new("t".into(), "Real".into());
+    let poly = Polynomial::from_coefficients(&var, coefficients);
+
+    factor_polynomial(&poly)
+        .1
+        .into_iter()
+        .filter_map(
+            |(factor, _)| match factor.coefficients_in(&var).as_slice() {
+                [b, a] => Some(-b.constant_value()? / a.constant_value()?),
+                _ => None,
+            },
+        )
+        .collect()
+}
+
+/// The sign of `a + b*sqrt(n)`, for a positive `n`.
+fn radical_sign(a: &BigRational, b: &BigRational, n: &BigRational) -> Ordering {
+    let (a_sign, b_sign) = (a.cmp(&BigRational::zero()), b.cmp(&BigRational::zero()));
+
+    if a_sign == b_sign || b_sign == Ordering::Equal {
+        a_sign
+    } else if a_sign == Ordering::Equal {
+        b_sign
+    } else {
+        // The sign of the term with the larger absolute value wins.
+        match (a * a).cmp(&(b * b * n)) {
+            Ordering::Equal => Ordering::Equal,
+            Ordering::Greater => a_sign,
+            Ordering::Less => b_sign,
+        }
+    }
+}
+
+/// Ferrari's method. The depressed quartic `y**4 + p*y**2 + q*y + r` is
+/// split into two quadratics with a positive root `m` of its resolvent
+/// cubic, which has to be rational so that the radicals stay real.
+fn ferrari_roots(coefficients: &[BigRational]) -> Option<Vec<Object>> {
+    let [e, d, c, b, a] = coefficients else {
+        return None;
+    };
+
+    let (b, c, d, e) = (b / a, c / a, d / a, e / a);
+    let p = &c - rational(3) * &b * &b / rational(8);
+    let q = &d - &b * &c / rational(2) + &b * &b * &b / rational(8);
+    let r = &e - &b * &d / rational(4) + &b * &b * &c / rational(16)
+        - rational(3) * &b * &b * &b * &b / rational(256);
+    let shift = Object::from_rational(-&b / rational(4));
+
+    if q.is_zero() {
+        let depressed = [r, BigRational::zero(), p, BigRational::zero(), rational(1)];
+        let roots = biquadratic_roots(&depressed)?;
+        return Some(
+            roots
+                .into_iter()
+                .map(|root| sum(vec![root, shift.to_owned()]))
+                .collect(),
+        );
+    }
+
+    let resolvent = [
+        -&q * &q,
+        rational(2) * &p * &p - rational(8) * &r,
+        rational(8) * &p,
+        rational(8),
+    ];
+    let m = linear_roots(&resolvent)
+        .into_iter()
+        .find(|m| m.is_positive())?;
+
+    // The quadratics are `y**2 + sigma*s*y + p/2 + m - sigma*q/(2*s)`, where
+    // `s = sqrt(2*m)`. Their discriminants are `a + sigma*(q/m)*s`.
+    let two_m = rational(2) * &m;
+    let s = radical(&two_m, 2);
+    let a = rational(-2) * (&m + &p);
+
+    let mut res = vec![];
+    for sigma in [rational(1), rational(-1)] {
+        let b = &sigma * &q / &m;
+        let center = sum(vec![
+            shift.to_owned(),
+            product(vec![
+                Object::from_rational(-&sigma / rational(2)),
+                s.to_owned(),
+            ]),
+        ]);
+
+        match radical_sign(&a, &b, &two_m) {
+            Ordering::Less => (),
+            Ordering::Equal => res.push(center),
+            Ordering::Greater => {
+                let discriminant = sum(vec![
+                    Object::from_rational(a.to_owned()),
+                    product(vec![Object::from_rational(b), s.to_owned()]),
+                ]);
+                let offset = product(vec![
+                    Object::from_rational(BigRational::new(1.into(), 2.into())),
+                    elementary::apply("sqrt", &discriminant),
+                ]);
+
+                res.push(sum(vec![
+                    center.to_owned(),
+                    product(vec![Object::int(-1), offset.to_owned()]),
+                ]));
+                res.push(sum(vec![center, offset]));
+            }
+        }
+    }
+
+    Some(res)
+}
+
+/// Dense polynomials with rational coefficients, indexed by the power of the variable.
+type Dense = Vec<BigRational>;
+
+fn trimmed(mut poly: Dense) -> Dense {
+    while poly.last().is_some_and(Zero::is_zero) {
+        poly.pop();
+    }
+
+    poly
+}
+
+fn evaluate(poly: &[BigRational], point: &BigRational) -> BigRational {
+    poly.iter()
+        .rev()
+        .fold(BigRational::zero(), |acc, coefficient| {
+            acc * point + coefficient
+        })
+}
+
+fn remainder(dividend: &[BigRational], divisor: &[BigRational]) -> Dense {
+    let mut rest = dividend.to_vec();
+    let lead = divisor.last().unwrap();
+
+    while rest.len() >= divisor.len() {
+        let coefficient = rest.last().unwrap() / lead;
+        let shift = rest.len() - divisor.len();
+
+        for (i, divisor_coefficient) in divisor.iter().enumerate() {
+            rest[shift + i] -= &coefficient * divisor_coefficient;
+        }
+
+        rest.pop();
+        rest = trimmed(rest);
+    }
+
+    rest
+}
+
+fn derivative(poly: &[BigRational]) -> Dense {
+    poly.iter()
+        .enumerate()
+        .skip(1)
+        .map(|(exp, coefficient)| coefficient * rational(exp as i32))
+        .collect()
+}
+
+fn sturm_sequence(poly: &[BigRational]) -> Vec<Dense> {
+    let mut res = vec![poly.to_vec(), derivative(poly)];
+
+    while res.last().is_some_and(|last| last.len() > 1) {
+        let rest = remainder(&res[res.len() - 2], &res[res.len() - 1]);
+        if rest.is_empty() {
+            break;
+        }
+
+        res.push(rest.into_iter().map(|coefficient| -coefficient).collect());
+    }
+
+    res
+}
+
+fn sign_changes(sequence: &[Dense], point: &BigRational) -> usize {
+    let signs: Vec<bool> = sequence
+        .iter()
+        .map(|poly| evaluate(poly, point))
+        .filter(|val| !val.is_zero())
+        .map(|val| val.is_positive())
+        .collect();
+
+    signs.windows(2).filter(|pair| pair[0] != pair[1]).count()
+}
+
+/// Splits `(lower, upper]` until every interval holds a single root.
+fn isolate(
+    sequence: &[Dense],
+    lower: BigRational,
+    upper: BigRational,
+    intervals: &mut Vec<(BigRational, BigRational)>,
+) {
+    match sign_changes(sequence, &lower) - sign_changes(sequence, &upper) {
+        0 => (),
+        1 => intervals.push((lower, upper)),
+        _ => {
+            let middle = (&lower + &upper) / rational(2);
+            isolate(sequence, lower, middle.to_owned(), intervals);
+            isolate(sequence, middle, upper, intervals);
+        }
+    }
+}
+
+fn approximation(val: BigRational) -> Object {
+    Object::Decimal(Decimal::from(&Fraction::from(val)))
+}
+
+/// The smallest absolute value in an interval.
+fn magnitude(lower: &BigRational, upper: &BigRational) -> BigRational {
+    if lower.is_positive() {
+        lower.to_owned()
+    } else if upper.is_negative() {
+        -upper
+    } else {
+        BigRational::zero()
+    }
+}
+
+/// The real roots of a square-free polynomial, found with a Sturm sequence
+/// and refined by bisection.
+fn numeric_roots(poly: &[BigRational]) -> Vec<Object> {
+    let lead = poly.last().unwrap();
+    let bound = poly
+        .iter()
+        .map(|coefficient| (coefficient / lead).abs())
+        .max()
+        .unwrap()
+        + rational(1);
+
+    let sequence = sturm_sequence(poly);
+    let mut intervals = vec![];
+    isolate(&sequence, -&bound, bound, &mut intervals);
+
+    // Two more digits than the precision, relative to the size of the root
+    let digits = Precision::current().digits.get() as u32 + 2;
+    let tolerance = BigRational::new(BigInt::one(), Pow::pow(BigInt::from(10), digits));
+
+    intervals
+        .into_iter()
+        .map(|(mut lower, mut upper)| {
+            let upper_sign = evaluate(poly, &upper).is_positive();
+            if evaluate(poly, &upper).is_zero() {
+                return Object::from_rational(upper);
+            }
+
+            if lower.is_negative() && upper.is_positive() && poly[0].is_zero() {
+                return Object::int(0);
+            }
+
+            while &upper - &lower > magnitude(&lower, &upper) * &tolerance {
+                let middle = (&lower + &upper) / rational(2);
+                let val = evaluate(poly, &middle);
+
+                if val.is_zero() {
+                    return Object::from_rational(middle);
+                }
+
+                if val.is_positive() == upper_sign {
+                    upper = middle;
+                } else {
+                    lower = middle;
+                }
+            }
+
+            approximation((lower + upper) / rational(2))
+        })
+        .collect()
+}
+
+/// The real roots of an irreducible polynomial with rational coefficients.
+fn rational_roots(coefficients: &[BigRational]) -> Vec<Object> {
+    match coefficients {
+        [b, a] => vec![Object::from_rational(-b / a)],
+        [c, b, a] => quadratic_roots(a, b, c),
+        _ => cubic_roots(coefficients)
+            .or_else(|| biquadratic_roots(coefficients))
+            .or_else(|| ferrari_roots(coefficients))
+            .unwrap_or_else(|| numeric_roots(coefficients)),
+    }
+}
+
+/// The roots of a factor whose coefficients have other symbols. Only the
+/// linear and quadratic formulas apply.
+fn symbolic_roots(coefficients: &[Object]) -> Option<Vec<Object>> {
+    match coefficients {
+        [b, a] => Some(vec![quotient(
+            product(vec![Object::int(-1), b.to_owned()]),
+            a.to_owned(),
+        )]),
+        [c, b, a] => {
+            let discriminant = sum(vec![
+                product(vec![b.to_owned(), b.to_owned()]),
+                product(vec![Object::int(-4), a.to_owned(), c.to_owned()]),
+            ]);
+            let root = elementary::apply("sqrt", &discriminant);
+            let denominator = product(vec![Object::int(2), a.to_owned()]);
+            let minus_b = product(vec![Object::int(-1), b.to_owned()]);
+
+            Some(vec![
+                quotient(
+                    sum(vec![
+                        minus_b.to_owned(),
+                        product(vec![Object::int(-1), root.to_owned()]),
+                    ]),
+                    denominator.to_owned(),
+                ),
+                quotient(sum(vec![minus_b, root]), denominator),
+            ])
+        }
+        _ => None,
+    }
+}
+
+/// The real solutions of `poly = 0` for `var` that agree with its property.
+fn roots(poly: &Polynomial, var: &Symbol) -> Option<BTreeSet<Object>> {
+    let mut res = BTreeSet::new();
+
+    for (factor, _) in factor_polynomial(poly).1 {
+        if factor.degree_in(var).is_some_and(|degree| degree == 0) {
+            continue;
+        }
+
+        let coefficients = factor.coefficients_in(var);
+        let constants: Option<Vec<BigRational>> = coefficients
+            .iter()
+            .map(Polynomial::constant_value)
+            .collect();
+
+        let roots = match constants {
+            Some(constants) => rational_roots(&constants),
+            None => symbolic_roots(
+                &coefficients
+                    .iter()
+                    .map(Polynomial::to_expression)
+                    .collect::<Vec<_>>(),
+            )?,
+        };
+
+        res.extend(
+            roots
+                .into_iter()
+                .filter(|root| properties::satisfies(root, &var.property) != Some(false)),
+        );
+    }
+
+    Some(res)
+}
+
+pub fn solve(args: &[Object]) -> Result<Object, EvalError> {
+    let bad_argument =
+        |expected: &str, obj: &Object| EvalError::bad_argument("solve", expected, obj);
+
+    let var = match &args[1] {
+        Object::Symbol(var) => var,
+        obj => return Err(bad_argument("Symbol", obj)),
+    };
+
+    // An equation like `x = x` is decided before it gets here.
+    let (lhs, rhs) = match &args[0] {
+        Object::Expression(Expression::Equation(lhs, rhs)) => (&**lhs, &**rhs),
+        Object::Boolean(holds) => {
+            return match holds.value() {
+                Some(false) => Ok(Object::Set(Set::from(vec![]))),
+                _ => Err(EvalError::EverySolution(var.to_string())),
+            }
+        }
+        obj if obj.is_symbolic() => (obj, &Object::Integer(Integer::from(0))),
+        obj => return Err(bad_argument("Equation", obj)),
+    };
+
+    let mut inexact = false;
+    let poly = match (
+        Polynomial::from_object(&rationalized(lhs, &mut inexact)),
+        Polynomial::from_object(&rationalized(rhs, &mut inexact)),
+    ) {
+        (Some(lhs), Some(rhs)) => &lhs - &rhs,
+        _ => return Err(bad_argument("polynomial Equation", &args[0])),
+    };
+
+    if poly.degree_in(var).unwrap_or(0) == 0 {
+        return Err(bad_argument(&format!("Equation in `{var}`"), &args[0]));
+    }
+
+    match roots(&poly, var) {
+        // Decimals in the equation make decimal solutions.
+        Some(roots) if inexact => Ok(Object::Set(Set::from(
+            roots
+                .into_iter()
+                .map(|root| match numeric::value(&root) {
+                    Some(val) => Object::Decimal(Decimal::from(val)),
+                    None => root,
+                })
+                .collect::<Vec<_>>(),
+        ))),
+        Some(roots) => Ok(Object::Set(Set::from(roots))),
+        None => Err(bad_argument("Equation of degree at most 2", &args[0])),
+    }
+}
+
+/// `obj` with its decimals written as the fractions they are exactly, so
+/// that it can be made a polynomial. `inexact` tells whether there were any.
+fn rationalized(obj: &Object, inexact: &mut bool) -> Object {
+    match obj {
+        Object::Decimal(_) => {
+            *inexact = true;
+            Object::from_rational(approximation::exact(obj).unwrap())
+        }
+        Object::Expression(Expression::Sum(terms)) => Object::Expression(Expression::Sum(
+            terms
+                .iter()
+                .map(|term| rationalized(term, inexact))
+                .collect(),
+        )),
+        Object::Expression(Expression::Product(factors)) => {
+            Object::Expression(Expression::Product(
+                factors
+                    .iter()
+                    .map(|factor| rationalized(factor, inexact))
+                    .collect(),
+            ))
+        }
+        Object::Expression(Expression::Power(base, exp)) => Object::Expression(Expression::Power(
+            Box::new(rationalized(base, inexact)),
+            exp.to_owned(),
+        )),
+        obj => obj.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{int, x};
+
+    use super::*;
+
+    fn equation(lhs: Object, rhs: Object) -> Object {
+        Object::Expression(Expression::Equation(Box::new(lhs), Box::new(rhs)))
+    }
+
+    fn solutions(lhs: Object, rhs: Object) -> Vec<String> {
+        match solve(&[equation(lhs, rhs), x()]) {
+            Ok(Object::Set(set)) => set.set.iter().map(Object::to_string).collect(),
+            res => panic!("unexpected result: {res:?}"),
+        }
+    }
+
+    fn power(exp: i64) -> Object {
+        x().pow(&int(exp)).unwrap()
+    }
+
+    #[test]
+    fn rational_solutions() {
+        // 2x**2 = x + 1
+        let lhs = int(2).product(&power(2)).unwrap();
+        let rhs = x().sum(&int(1)).unwrap();
+
+        assert_eq!(solutions(lhs, rhs), vec!["1", "-1 // 2"]);
+    }
+
+    #[test]
+    fn quadratic_radicals() {
+        assert_eq!(solutions(power(2), int(8)), vec!["-2*sqrt(2)", "2*sqrt(2)"]);
+    }
+
+    #[test]
+    fn no_real_solutions() {
+        assert!(solutions(power(2), int(-1)).is_empty());
+    }
+
+    #[test]
+    fn biquadratic() {
+        // x**4 - 2 = 0 has two real roots, +- 2**(1/4)
+        let res = solutions(power(4), int(2));
+
+        assert_eq!(res.len(), 2);
+        assert!(res.iter().all(|root| root.contains("2**(1 // 4)")));
+    }
+
+    #[test]
+    fn numeric_solutions() {
+        // x**5 - x - 1 has a single real root
+        let lhs = power(5).substraction(&x()).unwrap();
+
+        assert_eq!(solutions(lhs, int(1)), vec!["1.167303978261418684256045899854842180720560371525489039140082449275651903429527053180685205049728673"]);
+    }
+
+    #[test]
+    fn ferrari() {
+        // x**4 + 4x - 1 has two real roots, and its resolvent cubic has the
+        // rational root 1
+        let lhs = power(4).sum(&int(4).product(&x()).unwrap()).unwrap();
+        let res = solutions(lhs, int(1));
+
+        assert_eq!(
+            res,
+            vec![
+                "-sqrt(2)/2 - sqrt(4*sqrt(2) - 2)/2",
+                "-sqrt(2)/2 + sqrt(4*sqrt(2) - 2)/2"
+            ]
+        );
+    }
+
+    #[test]
+    fn decimal_coefficients() {
+        let two = Object::Decimal(Decimal::new("2", "0"));
+        let res = solutions(power(2), two);
+
+        assert_eq!(res.len(), 2);
+        assert!(res.iter().all(|root| root.contains("1.414213562373095")));
+    }
+
+    #[test]
+    fn decided_equations() {
+        assert_eq!(
+            solve(&[Object::Boolean(false.into()), x()]),
+            Ok(Object::Set(Set::from(vec![])))
+        );
+        assert_eq!(
+            solve(&[Object::Boolean(true.into()), x()]),
+            Err(EvalError::EverySolution("x".into()))
+        );
+    }
+
+    #[test]
+    fn not_an_equation() {
+        assert_eq!(
+            solve(&[int(1), x()]),
+            Err(EvalError::BadArgument {
+                func: "solve".into(),
+                expected: "Equation".into(),
+                actual: "Integer".into(),
+            })
+        );
+    }
+}
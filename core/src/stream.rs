@@ -0,0 +1,278 @@
+use std::{fmt, iter::successors};
+
+use bigdecimal::{One, ToPrimitive, Zero};
+use num_bigint::BigInt;
+
+use crate::{
+    ast::ASTNode,
+    env::Environment,
+    error::{Error, Position},
+    exec::{self, truthy, EvalError},
+    object::{Function, InfixOperable, Integer, List, Object, PrefixOperable},
+};
+
+/// A sequence whose elements are only computed when they are needed, so
+/// it can have no end.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Stream {
+    /// The integers from a start, like `1..`.
+    From(Integer),
+    /// `x`, `f(x)`, `f(f(x))`, ...
+    Iterate(Box<Object>, Function),
+    /// `f(0)`, `f(1)`, `f(2)`, ...
+    Sequence(Function),
+    /// A list comprehension whose first generator goes over another stream.
+    Comprehension {
+        source: Box<Stream>,
+        element: Box<ASTNode>,
+        generators: Vec<(ASTNode, ASTNode, Option<ASTNode>)>,
+        env: Environment,
+    },
+    /// Another stream without its first elements.
+    Drop(usize, Box<Stream>),
+}
+
+/// The elements of something that can be iterated. Computing them can
+/// fail, since they may come from calling functions.
+pub type Elements = Box<dyn Iterator<Item = Result<Object, Error>>>;
+
+fn call(func: &mut Function, arg: Object) -> Result<Object, Error> {
+    func.call(&[arg], &mut Environment::default(), Position::new(0, 0))
+}
+
+impl Stream {
+    pub fn elements(&self) -> Elements {
+        match self {
+            Self::From(start) => {
+                let start = Object::Integer(start.to_owned());
+                Box::new(
+                    successors(start.as_rational().map(|val| val.to_integer()), |n| {
+                        Some(n + 1)
+                    })
+                    .map(|n| Ok(Object::int(n))),
+                )
+            }
+            Self::Iterate(seed, func) => {
+                let mut func = func.to_owned();
+                Box::new(successors(
+                    Some(Ok(*seed.to_owned())),
+                    move |prev| match prev {
+                        Ok(prev) => Some(call(&mut func, prev.to_owned())),
+                        Err(_) => None,
+                    },
+                ))
+            }
+            Self::Sequence(func) => {
+                let mut func = func.to_owned();
+                Box::new(
+                    successors(Some(BigInt::zero()), |n| Some(n + BigInt::one()))
+                        .map(move |n| call(&mut func, Object::int(n))),
+                )
+            }
+            Self::Comprehension {
+                source,
+                element,
+                generators,
+                env,
+            } => {
+                let (element, generators, mut env) =
+                    (element.to_owned(), generators.to_owned(), env.to_owned());
+
+                Box::new(source.elements().flat_map(move |val| {
+                    match val.and_then(|val| {
+                        exec::comprehension_step(val, &element, &generators, &mut env)
+                    }) {
+                        Ok(vals) => vals.into_iter().map(Ok).collect(),
+                        Err(err) => vec![Err(err)],
+                    }
+                }))
+            }
+            Self::Drop(count, stream) => Box::new(stream.elements().skip(*count)),
+        }
+    }
+}
+
+/// The elements of an object, if it can be iterated.
+pub fn elements(obj: Object) -> Option<Elements> {
+    match obj {
+        Object::Set(set) => Some(Box::new(set.set.into_iter().map(Ok))),
+        Object::List(list) => Some(Box::new(list.list.into_iter().map(Ok))),
+        Object::Range(range) => Some(Box::new(range.into_iter().map(Ok))),
+        Object::Stream(stream) => Some(stream.elements()),
+        _ => None,
+    }
+}
+
+impl InfixOperable for Stream {}
+impl PrefixOperable for Stream {}
+
+/// How many elements are shown when a stream is printed.
+const SHOWN_ELEMENTS: usize = 3;
+
+/// Streams of integers are printed as open ranges, like `6..`, and the
+/// others with their first elements, like `[1, 2, 4, ...]`.
+impl fmt::Display for Stream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::From(start) => write!(f, "{start}.."),
+            Self::Drop(count, stream) if matches!(**stream, Self::From(_)) => {
+                let Self::From(start) = &**stream else {
+                    unreachable!()
+                };
+
+                match start.sum(&Object::int(BigInt::from(*count))) {
+                    Some(start) => write!(f, "{start}.."),
+                    None => write!(f, "{stream}"),
+                }
+            }
+            _ => {
+                write!(f, "[")?;
+
+                for val in self.elements().take(SHOWN_ELEMENTS) {
+                    match val {
+                        Ok(val) => write!(f, "{val}, ")?,
+                        Err(_) => break,
+                    }
+                }
+
+                write!(f, "...]")
+            }
+        }
+    }
+}
+
+fn iterable(func: &str, obj: &Object) -> Result<Elements, EvalError> {
+    elements(obj.to_owned()).ok_or_else(|| EvalError::bad_argument(func, "iterable", obj))
+}
+
+fn count(func: &str, obj: &Object) -> Result<usize, EvalError> {
+    match obj {
+        Object::Integer(_) => obj.as_rational().unwrap().to_integer().to_usize(),
+        _ => None,
+    }
+    .ok_or_else(|| EvalError::bad_argument(func, "nonnegative Integer", obj))
+}
+
+fn function(func: &str, obj: &Object) -> Result<Function, EvalError> {
+    match obj {
+        Object::Function(f) => Ok(f.to_owned()),
+        obj => Err(EvalError::bad_argument(func, "Function", obj)),
+    }
+}
+
+fn inner(err: Error) -> EvalError {
+    EvalError::Inner(Box::new(err))
+}
+
+/// The first `n` elements, as a list.
+pub fn take(args: &[Object]) -> Result<Object, EvalError> {
+    let n = count("take", &args[1])?;
+    let vals = iterable("take", &args[0])?
+        .take(n)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(inner)?;
+
+    Ok(Object::List(List::from(vals)))
+}
+
+/// Everything but the first `n` elements. Streams stay lazy.
+pub fn drop(args: &[Object]) -> Result<Object, EvalError> {
+    let n = count("drop", &args[1])?;
+
+    match &args[0] {
+        Object::Stream(Stream::Drop(skipped, stream)) => Ok(Object::Stream(Stream::Drop(
+            skipped.saturating_add(n),
+            stream.to_owned(),
+        ))),
+        Object::Stream(stream) => Ok(Object::Stream(Stream::Drop(n, Box::new(stream.to_owned())))),
+        obj => {
+            let vals = iterable("drop", obj)?
+                .skip(n)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(inner)?;
+
+            Ok(Object::List(List::from(vals)))
+        }
+    }
+}
+
+/// The elements before the first one that doesn't satisfy a predicate, as
+/// a list.
+pub fn take_while(args: &[Object]) -> Result<Object, EvalError> {
+    let mut pred = function("takeWhile", &args[1])?;
+    let mut vals = vec![];
+
+    for val in iterable("takeWhile", &args[0])? {
+        let val = val.map_err(inner)?;
+        if !truthy(&call(&mut pred, val.to_owned()).map_err(inner)?)? {
+            break;
+        }
+        vals.push(val);
+    }
+
+    Ok(Object::List(List::from(vals)))
+}
+
+pub fn iterate(args: &[Object]) -> Result<Object, EvalError> {
+    let func = function("iterate", &args[0])?;
+
+    Ok(Object::Stream(Stream::Iterate(
+        Box::new(args[1].to_owned()),
+        func,
+    )))
+}
+
+pub fn sequence(args: &[Object]) -> Result<Object, EvalError> {
+    Ok(Object::Stream(Stream::Sequence(function(
+        "sequence", &args[0],
+    )?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::object::ExternFunction;
+
+    use super::*;
+
+    fn double(args: &[Object]) -> Result<Object, EvalError> {
+        Ok(args[0].product(&Object::Integer(Integer::from(2))).unwrap())
+    }
+
+    fn first(stream: Stream, n: usize) -> String {
+        take(&[Object::Stream(stream), Object::Integer(Integer::from(n))])
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn lazy_elements() {
+        let naturals = Stream::From(Integer::from(1));
+        let double = Function::Extern(ExternFunction::new(double, 1));
+
+        assert_eq!(first(naturals.clone(), 3), "[1, 2, 3]");
+        assert_eq!(
+            first(
+                Stream::Iterate(Box::new(Object::Integer(Integer::from(1))), double.clone()),
+                4
+            ),
+            "[1, 2, 4, 8]"
+        );
+        assert_eq!(first(Stream::Sequence(double), 3), "[0, 2, 4]");
+        assert_eq!(first(Stream::Drop(2, Box::new(naturals)), 2), "[3, 4]");
+    }
+
+    #[test]
+    fn display() {
+        let naturals = Stream::From(Integer::from(1));
+        let double = Function::Extern(ExternFunction::new(double, 1));
+        let powers = Stream::Iterate(Box::new(Object::Integer(Integer::from(1))), double);
+
+        assert_eq!(naturals.to_string(), "1..");
+        assert_eq!(Stream::Drop(5, Box::new(naturals)).to_string(), "6..");
+        assert_eq!(powers.to_string(), "[1, 2, 4, ...]");
+        assert_eq!(
+            Stream::Drop(2, Box::new(powers)).to_string(),
+            "[4, 8, 16, ...]"
+        );
+    }
+}
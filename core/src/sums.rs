@@ -0,0 +1,669 @@
+use std::collections::BTreeMap;
+
+use bigdecimal::{One, Signed, Zero};
+use num_bigint::BigInt;
+use num_rational::BigRational;
+
+use crate::{
+    calculus::depends_on,
+    exec::EvalError,
+    factor::factor_polynomial,
+    object::{Integer, Object, Symbol},
+    polynomial::Polynomial,
+    simplify::simplify,
+    symbolic::{self, Expression},
+};
+
+fn sum(terms: Vec<Object>) -> Object {
+    simplify(&Object::Expression(Expression::Sum(terms)))
+}
+
+fn product(factors: Vec<Object>) -> Object {
+    simplify(&Object::Expression(Expression::Product(factors)))
+}
+
+fn power(base: Object, exp: Object) -> Object {
+    simplify(&Object::Expression(Expression::Power(
+        Box::new(base),
+        Box::new(exp),
+    )))
+}
+
+fn negation(obj: Object) -> Object {
+    product(vec![Object::int(-1), obj])
+}
+
+fn quotient(lhs: Object, rhs: Object) -> Object {
+    product(vec![lhs, power(rhs, Object::int(-1))])
+}
+
+/// Expands polynomial results, so `(n + 1)*n/2` shows up as `n**2/2 + n/2`.
+fn expanded(obj: Object) -> Object {
+    match Polynomial::from_object(&obj) {
+        Some(poly) => poly.to_expression(),
+        None => obj,
+    }
+}
+
+fn at(expr: &Object, var: &Symbol, val: &Object) -> Object {
+    let values = BTreeMap::from([(Object::Symbol(var.to_owned()), val.to_owned())]);
+    symbolic::substitute(expr, &values)
+}
+
+/// A term of a sum or a product, which fails when it divides by zero.
+fn term(expr: &Object, var: &Symbol, val: &Object) -> Result<Object, EvalError> {
+    let values = BTreeMap::from([(Object::Symbol(var.to_owned()), val.to_owned())]);
+    symbolic::evaluate(expr, &values)
+}
+
+fn binomial(n: usize, k: usize) -> BigRational {
+    let mut res = BigRational::one();
+
+    for i in 0..k {
+        res = res * BigRational::from_integer((n - i).into())
+            / BigRational::from_integer((i + 1).into());
+    }
+
+    res
+}
+
+/// The Bernoulli numbers up to `B_n`, with `B_1 = -1/2`.
+fn bernoulli(n: usize) -> Vec<BigRational> {
+    let mut res: Vec<BigRational> = vec![BigRational::one()];
+
+    for m in 1..=n {
+        let total = (0..m).fold(BigRational::zero(), |acc, i| {
+            acc + binomial(m + 1, i) * &res[i]
+        });
+
+        res.push(-total / BigRational::from_integer((m + 1).into()));
+    }
+
+    res
+}
+
+/// The coefficients of the polynomial `m -> 0**j + 1**j + ... + (m - 1)**j`,
+/// indexed by the power of `m`.
+fn power_sum(j: usize) -> Vec<BigRational> {
+    let numbers = bernoulli(j);
+    let mut res = vec![BigRational::zero(); j + 2];
+
+    for (i, number) in numbers.iter().enumerate() {
+        res[j + 1 - i] = binomial(j + 1, i) * number / BigRational::from_integer((j + 1).into());
+    }
+
+    res
+}
+
+fn evaluate(coefficients: &[BigRational], point: &Object) -> Object {
+    sum(coefficients
+        .iter()
+        .enumerate()
+        .map(|(exp, coefficient)| {
+            product(vec![
+                Object::from_rational(coefficient.to_owned()),
+                power(point.to_owned(), Object::int(exp as i64)),
+            ])
+        })
+        .collect())
+}
+
+/// Faulhaber's formula, applied to every power of `var`.
+fn polynomial_sum(poly: &Polynomial, var: &Symbol, start: &Object, end: &Object) -> Object {
+    let terms = poly
+        .coefficients_in(var)
+        .iter()
+        .enumerate()
+        .map(|(j, coefficient)| {
+            let sums = power_sum(j);
+
+            product(vec![
+                coefficient.to_expression(),
+                sum(vec![evaluate(&sums, end), negation(evaluate(&sums, start))]),
+            ])
+        })
+        .collect();
+
+    sum(terms)
+}
+
+/// Splits an expression into a numerator and a denominator, both polynomials.
+fn rational_function(expr: &Object) -> Option<(Polynomial, Polynomial)> {
+    let one = || Polynomial::constant(BigRational::one());
+
+    match expr {
+        Object::Expression(Expression::Sum(terms)) => {
+            terms
+                .iter()
+                .try_fold((Polynomial::default(), one()), |(numer, denom), term| {
+                    let (term_numer, term_denom) = rational_function(term)?;
+                    Some((
+                        &(&numer * &term_denom) + &(&term_numer * &denom),
+                        &denom * &term_denom,
+                    ))
+                })
+        }
+        Object::Expression(Expression::Product(factors)) => {
+            factors
+                .iter()
+                .try_fold((one(), one()), |(numer, denom), factor| {
+                    let (factor_numer, factor_denom) = rational_function(factor)?;
+                    Some((&numer * &factor_numer, &denom * &factor_denom))
+                })
+        }
+        Object::Expression(Expression::Power(base, exp)) if exp.is_negative() => {
+            let exp = exp.as_rational().filter(BigRational::is_integer)?;
+            let exp = u32::try_from(-exp.to_integer()).ok()?;
+            let (numer, denom) = rational_function(base)?;
+
+            Some((denom.power(exp), numer.power(exp)))
+        }
+        obj => Some((Polynomial::from_object(obj)?, one())),
+    }
+}
+
+/// The roots of a polynomial in `var` with rational coefficients, when it
+/// splits into linear factors, and its leading coefficient.
+fn linear_roots(poly: &Polynomial, var: &Symbol) -> Option<(BigRational, Vec<BigRational>)> {
+    let lead = poly.coefficients_in(var).last()?.constant_value()?;
+    let mut roots = vec![];
+
+    for (factor, multiplicity) in factor_polynomial(poly).1 {
+        match factor.coefficients_in(var).as_slice() {
+            [constant, coefficient] => {
+                let root = -constant.constant_value()? / coefficient.constant_value()?;
+                roots.extend(std::iter::repeat_n(root, multiplicity));
+            }
+            _ => return None,
+        }
+    }
+
+    Some((lead, roots))
+}
+
+/// Roots grouped by their fractional part, since only the ones that differ
+/// by an integer cancel each other.
+fn classes<T: Clone>(roots: &[(BigRational, T)]) -> BTreeMap<BigRational, Vec<(BigRational, T)>> {
+    let mut res: BTreeMap<BigRational, Vec<(BigRational, T)>> = BTreeMap::new();
+
+    for (root, val) in roots {
+        res.entry(root - root.floor())
+            .or_default()
+            .push((root.to_owned(), val.to_owned()));
+    }
+
+    res
+}
+
+/// Sums `g(from) + ... + g(from + length - 1)`.
+fn finite_sum(g: impl Fn(&Object) -> Object, from: &Object, length: &BigInt) -> Object {
+    let mut terms = vec![];
+    let mut offset = BigInt::zero();
+
+    while &offset < length {
+        terms.push(g(&sum(vec![
+            from.to_owned(),
+            Object::Integer(Integer::from(offset.to_owned())),
+        ])));
+        offset += 1;
+    }
+
+    sum(terms)
+}
+
+/// Telescoping sums of proper rational functions, through their partial
+/// fractions `A/(k - r)`.
+fn telescoping_sum(
+    numer: &Polynomial,
+    denom: &Polynomial,
+    var: &Symbol,
+    start: &Object,
+    end: &Object,
+) -> Option<Object> {
+    let (_, roots) = linear_roots(denom, var)?;
+    let derivative = denom.derivative(var).to_expression();
+    let numer = numer.to_expression();
+
+    let mut fractions = vec![];
+    for (i, root) in roots.iter().enumerate() {
+        if roots[..i].contains(root) {
+            return None;
+        }
+
+        let point = Object::from_rational(root.to_owned());
+        fractions.push((
+            root.to_owned(),
+            quotient(at(&numer, var, &point), at(&derivative, var, &point)),
+        ));
+    }
+
+    let mut terms = vec![];
+    for (_, fractions) in classes(&fractions) {
+        let total = sum(fractions.iter().map(|(_, val)| val.to_owned()).collect());
+        if !total.is_zero() {
+            return None;
+        }
+
+        let lowest = fractions.iter().map(|(root, _)| root).min()?.to_owned();
+        let highest = fractions.iter().map(|(root, _)| root).max()?.to_owned();
+        let shift = Object::from_rational(&highest - &lowest);
+        let g = |point: &Object| {
+            quotient(
+                Object::int(1),
+                sum(vec![point.to_owned(), Object::from_rational(-&lowest)]),
+            )
+        };
+
+        for (root, coefficient) in fractions {
+            let length = (&highest - &root).to_integer();
+            let upper = finite_sum(
+                g,
+                &sum(vec![end.to_owned(), negation(shift.to_owned())]),
+                &length,
+            );
+            let lower = finite_sum(
+                g,
+                &sum(vec![start.to_owned(), negation(shift.to_owned())]),
+                &length,
+            );
+
+            terms.push(product(vec![
+                coefficient,
+                sum(vec![upper, negation(lower)]),
+            ]));
+        }
+    }
+
+    Some(sum(terms))
+}
+
+/// Splits `c*q**(alpha*k + beta)` into `c*q**beta` and `q**alpha`.
+fn geometric_term(expr: &Object, var: &Symbol) -> Option<(Object, Object)> {
+    let factors = match expr {
+        Object::Expression(Expression::Product(factors)) => factors.to_owned(),
+        expr => vec![expr.to_owned()],
+    };
+
+    let mut coefficient = vec![];
+    let mut ratio = vec![];
+
+    for factor in factors {
+        match &factor {
+            factor if !depends_on(factor, var) => coefficient.push(factor.to_owned()),
+            Object::Expression(Expression::Power(base, exp)) if !depends_on(base, var) => {
+                let exp = Polynomial::from_object(exp)?;
+                match exp.coefficients_in(var).as_slice() {
+                    [beta, alpha] => {
+                        coefficient.push(power(*base.to_owned(), beta.to_expression()));
+                        ratio.push(power(*base.to_owned(), alpha.to_expression()));
+                    }
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        }
+    }
+
+    if ratio.is_empty() {
+        return None;
+    }
+
+    Some((product(coefficient), product(ratio)))
+}
+
+fn geometric_sum(expr: &Object, var: &Symbol, start: &Object, end: &Object) -> Option<Object> {
+    let (coefficient, ratio) = geometric_term(expr, var)?;
+
+    if ratio.is_one() {
+        return Some(product(vec![
+            coefficient,
+            sum(vec![end.to_owned(), negation(start.to_owned())]),
+        ]));
+    }
+
+    Some(product(vec![
+        coefficient,
+        quotient(
+            sum(vec![
+                power(ratio.to_owned(), end.to_owned()),
+                negation(power(ratio.to_owned(), start.to_owned())),
+            ]),
+            sum(vec![ratio, Object::int(-1)]),
+        ),
+    ]))
+}
+
+fn rational_sum(expr: &Object, var: &Symbol, start: &Object, end: &Object) -> Option<Object> {
+    let (numer, denom) = rational_function(expr)?;
+
+    if let Some(denom) = denom.constant_value() {
+        return Some(polynomial_sum(
+            &numer.scale(&denom.recip()),
+            var,
+            start,
+            end,
+        ));
+    }
+
+    if denom.variables().iter().any(|symbol| symbol != var) {
+        return None;
+    }
+
+    let (quotient, rest) = numer.div_rem(&denom);
+
+    Some(sum(vec![
+        polynomial_sum(&quotient, var, start, end),
+        telescoping_sum(&rest, &denom, var, start, end)?,
+    ]))
+}
+
+/// The sum of `expr` for `var` in `start..end`, when it has a closed form.
+fn sum_closed_form(expr: &Object, var: &Symbol, start: &Object, end: &Object) -> Option<Object> {
+    let terms = match expr {
+        Object::Expression(Expression::Sum(terms)) => terms.to_owned(),
+        expr => vec![expr.to_owned()],
+    };
+
+    let (geometric, rest): (Vec<Object>, Vec<Object>) = terms
+        .into_iter()
+        .partition(|term| geometric_term(term, var).is_some());
+
+    let mut res = vec![];
+    for term in geometric {
+        res.push(geometric_sum(&term, var, start, end)?);
+    }
+
+    if !rest.is_empty() {
+        res.push(rational_sum(&sum(rest), var, start, end)?);
+    }
+
+    Some(expanded(sum(res)))
+}
+
+/// Products `(k - s)/(k - r)` where `s - r` is an integer, which cancel out
+/// except for a few factors at the ends.
+fn telescoping_product(
+    numer: &Polynomial,
+    denom: &Polynomial,
+    var: &Symbol,
+    start: &Object,
+    end: &Object,
+) -> Option<Object> {
+    let (numer_lead, numer_roots) = linear_roots(numer, var)?;
+    let (denom_lead, denom_roots) = linear_roots(denom, var)?;
+
+    let mut roots: Vec<(BigRational, bool)> =
+        numer_roots.into_iter().map(|root| (root, true)).collect();
+    roots.extend(denom_roots.into_iter().map(|root| (root, false)));
+
+    let mut factors = vec![power(
+        Object::from_rational(numer_lead / denom_lead),
+        sum(vec![end.to_owned(), negation(start.to_owned())]),
+    )];
+
+    for (_, roots) in classes(&roots) {
+        let numer_roots: Vec<&BigRational> = roots
+            .iter()
+            .filter(|(_, is_numer)| *is_numer)
+            .map(|(root, _)| root)
+            .collect();
+        let denom_roots: Vec<&BigRational> = roots
+            .iter()
+            .filter(|(_, is_numer)| !is_numer)
+            .map(|(root, _)| root)
+            .collect();
+
+        if numer_roots.len() != denom_roots.len() {
+            return None;
+        }
+
+        for (s, r) in numer_roots.into_iter().zip(denom_roots) {
+            // (k - s)/(k - r) = h(k - d)/h(k), with h(k) = k - r.
+            let h = |point: &Object| sum(vec![point.to_owned(), Object::from_rational(-r)]);
+            let finite_product = |from: &Object, length: &BigInt| {
+                let mut factors = vec![];
+                let mut offset = BigInt::zero();
+
+                while &offset < length {
+                    factors.push(h(&sum(vec![
+                        from.to_owned(),
+                        Object::Integer(Integer::from(offset.to_owned())),
+                    ])));
+                    offset += 1;
+                }
+
+                product(factors)
+            };
+
+            let d = (s - r).to_integer();
+            let length = d.abs();
+            let shifted =
+                |point: &Object| sum(vec![point.to_owned(), Object::Integer(Integer::from(-&d))]);
+
+            let (upper, lower) = if d > BigInt::zero() {
+                (
+                    finite_product(&shifted(start), &length),
+                    finite_product(&shifted(end), &length),
+                )
+            } else {
+                (finite_product(end, &length), finite_product(start, &length))
+            };
+
+            factors.push(quotient(upper, lower));
+        }
+    }
+
+    Some(product(factors))
+}
+
+/// The product of `expr` for `var` in `start..end`, when it has a closed form.
+fn product_closed_form(
+    expr: &Object,
+    var: &Symbol,
+    start: &Object,
+    end: &Object,
+) -> Option<Object> {
+    let factors = match expr {
+        Object::Expression(Expression::Product(factors)) => factors.to_owned(),
+        expr => vec![expr.to_owned()],
+    };
+
+    let length = sum(vec![end.to_owned(), negation(start.to_owned())]);
+    let mut res = vec![];
+    let mut rest = vec![];
+
+    for factor in factors {
+        match &factor {
+            factor if !depends_on(factor, var) => {
+                res.push(power(factor.to_owned(), length.to_owned()))
+            }
+            Object::Expression(Expression::Power(base, exp)) if !depends_on(base, var) => res.push(
+                power(*base.to_owned(), sum_closed_form(exp, var, start, end)?),
+            ),
+            _ => rest.push(factor),
+        }
+    }
+
+    if !rest.is_empty() {
+        let (numer, denom) = rational_function(&product(rest))?;
+        res.push(telescoping_product(&numer, &denom, var, start, end)?);
+    }
+
+    Some(product(res))
+}
+
+fn bounds(func: &str, obj: &Object) -> Result<(Object, Object), EvalError> {
+    match obj {
+        Object::Expression(Expression::Range(start, end)) => {
+            Ok((*start.to_owned(), *end.to_owned()))
+        }
+        obj => Err(EvalError::bad_argument(func, "Range", obj)),
+    }
+}
+
+type ClosedForm = fn(&Object, &Symbol, &Object, &Object) -> Option<Object>;
+
+/// Shared by `summation` and `product`: adds up or multiplies the terms one
+/// by one when the bounds are integers, and tries the closed form when they
+/// are symbolic.
+fn reduce(
+    func: &str,
+    args: &[Object],
+    closed_form: ClosedForm,
+    combine: fn(Vec<Object>) -> Object,
+) -> Result<Object, EvalError> {
+    let bad_argument = |expected: &str, obj: &Object| EvalError::bad_argument(func, expected, obj);
+
+    let expr = match &args[0] {
+        obj if obj.is_numeric() || obj.is_symbolic() => simplify(obj),
+        obj => return Err(bad_argument("Expression", obj)),
+    };
+
+    let var = match &args[1] {
+        Object::Symbol(var) => var,
+        obj => return Err(bad_argument("Symbol", obj)),
+    };
+
+    if let Object::Range(range) = &args[2] {
+        return range
+            .to_owned()
+            .map(|val| term(&expr, var, &val))
+            .collect::<Result<_, _>>()
+            .map(combine);
+    }
+
+    let (start, end) = bounds(func, &args[2])?;
+
+    Ok(match closed_form(&expr, var, &start, &end) {
+        Some(res) => res,
+        None => symbolic::call(
+            func,
+            vec![expr, Object::Symbol(var.to_owned()), args[2].to_owned()],
+        ),
+    })
+}
+
+pub fn summation(args: &[Object]) -> Result<Object, EvalError> {
+    reduce("summation", args, sum_closed_form, sum)
+}
+
+pub fn product_of(args: &[Object]) -> Result<Object, EvalError> {
+    reduce("product", args, product_closed_form, product)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        object::Range,
+        testing::{int, symbol},
+    };
+
+    use super::*;
+
+    fn k() -> Symbol {
+        Symbol::new("k".into(), "Integer".into())
+    }
+
+    fn n() -> Object {
+        symbol("n", "Natural")
+    }
+
+    fn var() -> Object {
+        Object::Symbol(k())
+    }
+
+    fn symbolic_range(start: Object, end: Object) -> Object {
+        Object::Expression(Expression::Range(Box::new(start), Box::new(end)))
+    }
+
+    #[test]
+    fn power_sums() {
+        // 0 + 1 + ... + (m - 1) = m**2/2 - m/2
+        assert_eq!(
+            power_sum(1),
+            vec![
+                BigRational::zero(),
+                BigRational::new((-1).into(), 2.into()),
+                BigRational::new(1.into(), 2.into())
+            ]
+        );
+    }
+
+    #[test]
+    fn polynomial_summation() {
+        let res = summation(&[
+            var().pow(&int(2)).unwrap(),
+            var(),
+            symbolic_range(int(1), n().sum(&int(1)).unwrap()),
+        ]);
+
+        assert_eq!(res.unwrap().to_string(), "n**3/3 + n**2/2 + n/6");
+    }
+
+    #[test]
+    fn geometric_summation() {
+        let res = summation(&[
+            int(2).pow(&var()).unwrap(),
+            var(),
+            symbolic_range(int(0), n()),
+        ]);
+
+        assert_eq!(res.unwrap().to_string(), "2**n - 1");
+    }
+
+    #[test]
+    fn telescoping_summation() {
+        // 1/(k*(k + 1)) = 1/k - 1/(k + 1)
+        let expr = int(1)
+            .over(&var().product(&var().sum(&int(1)).unwrap()).unwrap())
+            .unwrap();
+        let res = summation(&[expr, var(), symbolic_range(int(1), n())]);
+
+        assert_eq!(res.unwrap().to_string(), "-1/n + 1");
+    }
+
+    #[test]
+    fn concrete_bounds() {
+        let expr = int(1).over(&var()).unwrap();
+        let res = summation(&[expr, var(), Object::Range(Range::_new(1, 4))]);
+
+        assert_eq!(res.unwrap().to_string(), "11 // 6");
+    }
+
+    #[test]
+    fn empty_range() {
+        let empty = || Object::Range(Range::_new(5, 3));
+
+        assert_eq!(summation(&[var(), var(), empty()]), Ok(int(0)));
+        assert_eq!(
+            summation(&[int(2).pow(&var()).unwrap(), var(), empty()]),
+            Ok(int(0))
+        );
+        assert_eq!(product_of(&[int(2), var(), empty()]), Ok(int(1)));
+    }
+
+    #[test]
+    fn concrete_division_by_zero() {
+        let expr = int(1).over(&var()).unwrap();
+        let res = summation(&[expr, var(), Object::Range(Range::_new(0, 3))]);
+
+        assert_eq!(res, Err(EvalError::DenominatorZero));
+    }
+
+    #[test]
+    fn telescoping_product() {
+        // (k + 1)/k from 1 to n - 1 is n
+        let expr = var().sum(&int(1)).unwrap().over(&var()).unwrap();
+        let res = product_of(&[expr, var(), symbolic_range(int(1), n())]);
+
+        assert_eq!(res.unwrap(), n());
+    }
+
+    #[test]
+    fn unevaluated() {
+        let expr = int(1).over(&var()).unwrap();
+        let res = summation(&[expr, var(), symbolic_range(int(1), n())]);
+
+        assert_eq!(res.unwrap().to_string(), "summation(1/k, k, 1..n)");
+    }
+}
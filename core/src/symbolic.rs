@@ -0,0 +1,957 @@
+use std::{collections::BTreeMap, fmt};
+
+use bigdecimal::One;
+
+use crate::{
+    elementary,
+    exec::EvalError,
+    numeric,
+    object::{Bool, Decimal, Fraction, InfixOperable, Integer, Object, PrefixOperable, Range},
+    polynomial,
+    properties::{self, Sign},
+    simplify::simplify,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Expression {
+    Sum(Vec<Object>),
+    Product(Vec<Object>),
+    Power(Box<Object>, Box<Object>),
+    Remainder(Box<Object>, Box<Object>),
+    Call(String, Vec<Object>),
+    Equation(Box<Object>, Box<Object>),
+    Inequation(Box<Object>, Box<Object>),
+    Range(Box<Object>, Box<Object>),
+}
+
+impl Expression {
+    /// Equations can't be used as operands of arithmetic operations.
+    fn operand(&self) -> Option<Object> {
+        match self {
+            Expression::Equation(..) | Expression::Inequation(..) | Expression::Range(..) => None,
+            expr => Some(Object::Expression(expr.to_owned())),
+        }
+    }
+}
+
+fn is_operand(obj: &Object) -> bool {
+    match obj {
+        Object::Expression(
+            Expression::Equation(..) | Expression::Inequation(..) | Expression::Range(..),
+        ) => false,
+        obj => matches!(
+            obj,
+            Object::Integer(_)
+                | Object::Decimal(_)
+                | Object::Fraction(_)
+                | Object::Complex(_)
+                | Object::Modular(_)
+                | Object::Symbol(_)
+                | Object::Expression(_)
+        ),
+    }
+}
+
+/// Whether `obj` is a number that only operates with some other numbers,
+/// like `2i`, which doesn't with `1 mod 3`.
+fn is_complex_or_modular(obj: &Object) -> bool {
+    matches!(obj, Object::Complex(_) | Object::Modular(_))
+}
+
+fn constants(obj: &Object) -> Vec<Object> {
+    match obj {
+        Object::Expression(
+            Expression::Sum(objs) | Expression::Product(objs) | Expression::Call(_, objs),
+        ) => objs.iter().flat_map(constants).collect(),
+        Object::Expression(
+            Expression::Power(lhs, rhs)
+            | Expression::Remainder(lhs, rhs)
+            | Expression::Equation(lhs, rhs)
+            | Expression::Inequation(lhs, rhs)
+            | Expression::Range(lhs, rhs),
+        ) => [constants(lhs), constants(rhs)].concat(),
+        Object::Symbol(_) => vec![],
+        obj => vec![obj.to_owned()],
+    }
+}
+
+/// Whether the numbers in `objs` can be added and multiplied with each
+/// other, which simplifying their expressions together needs.
+fn constants_operate(objs: &[&Object]) -> bool {
+    let constants: Vec<Object> = objs.iter().flat_map(|obj| constants(obj)).collect();
+
+    constants
+        .iter()
+        .filter(|obj| is_complex_or_modular(obj))
+        .all(|lhs| {
+            constants
+                .iter()
+                .all(|rhs| lhs.sum(rhs).is_some() && lhs.product(rhs).is_some())
+        })
+}
+
+/// Whether `lhs` and `rhs` can be the operands of a symbolic operation.
+fn operable(lhs: &Object, rhs: &Object) -> bool {
+    is_operand(rhs) && constants_operate(&[lhs, rhs])
+}
+
+fn minus_one() -> Object {
+    Object::Integer(Integer::from(-1))
+}
+
+pub fn negation(val: Object) -> Object {
+    if val.is_symbolic() {
+        Object::Expression(Expression::Product(vec![minus_one(), val]))
+    } else {
+        val.inverse().unwrap_or(val)
+    }
+}
+
+/// Decimals are approximations, so an expression without variables that
+/// is operated with one is approximated too, like `2.0*pi`.
+fn approximated(lhs: &Object, rhs: &Object) -> Option<(Object, Object)> {
+    if ![lhs, rhs]
+        .iter()
+        .any(|obj| matches!(obj, Object::Decimal(_)))
+    {
+        return None;
+    }
+
+    let approximate = |obj: &Object| match obj {
+        Object::Decimal(_) => Some(obj.to_owned()),
+        obj => Some(Object::Decimal(Decimal::from(numeric::value(obj)?))),
+    };
+
+    Some((approximate(lhs)?, approximate(rhs)?))
+}
+
+/// Operations with a symbolic operand are simplified as they are built,
+/// so equal expressions compare equal in sets and dictionaries.
+pub fn sum(lhs: Object, rhs: &Object) -> Option<Object> {
+    if let Some((lhs, rhs)) = approximated(&lhs, rhs) {
+        lhs.sum(&rhs)
+    } else if let Object::Polynomial(_) = rhs {
+        polynomial::coerced(lhs)?.sum(rhs)
+    } else if operable(&lhs, rhs) {
+        Some(simplify(&Object::Expression(Expression::Sum(vec![
+            lhs,
+            rhs.to_owned(),
+        ]))))
+    } else {
+        None
+    }
+}
+
+pub fn substraction(lhs: Object, rhs: &Object) -> Option<Object> {
+    if let Some((lhs, rhs)) = approximated(&lhs, rhs) {
+        lhs.substraction(&rhs)
+    } else if let Object::Polynomial(_) = rhs {
+        polynomial::coerced(lhs)?.substraction(rhs)
+    } else if operable(&lhs, rhs) {
+        Some(simplify(&Object::Expression(Expression::Sum(vec![
+            lhs,
+            negation(rhs.to_owned()),
+        ]))))
+    } else {
+        None
+    }
+}
+
+pub fn product(lhs: Object, rhs: &Object) -> Option<Object> {
+    if let Some((lhs, rhs)) = approximated(&lhs, rhs) {
+        lhs.product(&rhs)
+    } else if let Object::Polynomial(_) = rhs {
+        polynomial::coerced(lhs)?.product(rhs)
+    } else if let Object::Matrix(matrix) = rhs {
+        matrix.scaled(&lhs).map(Object::Matrix)
+    } else if operable(&lhs, rhs) {
+        Some(simplify(&Object::Expression(Expression::Product(vec![
+            lhs,
+            rhs.to_owned(),
+        ]))))
+    } else {
+        None
+    }
+}
+
+pub fn over(lhs: Object, rhs: &Object) -> Option<Object> {
+    if let Some((lhs, rhs)) = approximated(&lhs, rhs) {
+        lhs.over(&rhs)
+    } else if let Object::Polynomial(_) = rhs {
+        polynomial::coerced(lhs)?.over(rhs)
+    } else if operable(&lhs, rhs) {
+        let inverse = Expression::Power(Box::new(rhs.to_owned()), Box::new(minus_one()));
+        Some(simplify(&Object::Expression(Expression::Product(vec![
+            lhs,
+            Object::Expression(inverse),
+        ]))))
+    } else {
+        None
+    }
+}
+
+pub fn pow(lhs: Object, rhs: &Object) -> Option<Object> {
+    if let Some((lhs, rhs)) = approximated(&lhs, rhs) {
+        lhs.pow(&rhs)
+    } else if operable(&lhs, rhs) {
+        Some(simplify(&Object::Expression(Expression::Power(
+            Box::new(lhs),
+            Box::new(rhs.to_owned()),
+        ))))
+    } else {
+        None
+    }
+}
+
+pub fn remainder(lhs: Object, rhs: &Object) -> Option<Object> {
+    if operable(&lhs, rhs) {
+        Some(simplify(&Object::Expression(Expression::Remainder(
+            Box::new(lhs),
+            Box::new(rhs.to_owned()),
+        ))))
+    } else {
+        None
+    }
+}
+
+pub fn call(name: &str, args: Vec<Object>) -> Object {
+    Object::Expression(Expression::Call(name.to_string(), args))
+}
+
+/// Whether two operands are equal, when it can be told from their
+/// difference: it simplifies to a number, or the properties of its symbols
+/// give it a sign, like `n + 1` for a `Natural` `n`.
+fn are_equal(lhs: &Object, rhs: &Object) -> Option<bool> {
+    let difference = simplify(&Object::Expression(Expression::Sum(vec![
+        lhs.to_owned(),
+        negation(rhs.to_owned()),
+    ])));
+
+    match properties::sign(&difference)? {
+        Sign::Zero => Some(true),
+        Sign::Negative | Sign::Positive => Some(false),
+        Sign::NonPositive | Sign::NonNegative => None,
+    }
+}
+
+/// Compares two operands. It is decided right away when `are_equal` can
+/// tell, and kept as an equation otherwise.
+pub fn equation(lhs: Object, rhs: Object) -> Option<Object> {
+    if !is_operand(&lhs) || !is_operand(&rhs) || !constants_operate(&[&lhs, &rhs]) {
+        return None;
+    }
+
+    if let Some(equal) = are_equal(&lhs, &rhs) {
+        Some(Object::Boolean(equal.into()))
+    } else {
+        Some(Object::Expression(Expression::Equation(
+            Box::new(lhs),
+            Box::new(rhs),
+        )))
+    }
+}
+
+/// The negation of `equation`, for `/=`.
+pub fn inequation(lhs: Object, rhs: Object) -> Option<Object> {
+    if !is_operand(&lhs) || !is_operand(&rhs) || !constants_operate(&[&lhs, &rhs]) {
+        return None;
+    }
+
+    if let Some(equal) = are_equal(&lhs, &rhs) {
+        Some(Object::Boolean((!equal).into()))
+    } else {
+        Some(Object::Expression(Expression::Inequation(
+            Box::new(lhs),
+            Box::new(rhs),
+        )))
+    }
+}
+
+/// A range with symbolic bounds. It becomes a regular range once both
+/// bounds are integers.
+pub fn range(start: Object, end: Object) -> Object {
+    match (start, end) {
+        (Object::Integer(start), Object::Integer(end)) => Object::Range(Range::new(&start, &end)),
+        (start, end) => Object::Expression(Expression::Range(Box::new(start), Box::new(end))),
+    }
+}
+
+/// Decides a comparison from the sign of `lhs - rhs`, as far as the
+/// properties of their symbols tell. An undecided comparison is an unknown
+/// boolean.
+fn compare(lhs: Object, rhs: &Object, decide: fn(Sign) -> Option<bool>) -> Option<Object> {
+    if !is_operand(&lhs) || !is_operand(rhs) {
+        return None;
+    }
+
+    if [&lhs, rhs]
+        .into_iter()
+        .flat_map(constants)
+        .any(|obj| is_complex_or_modular(&obj))
+    {
+        return None;
+    }
+
+    let sign = properties::sign(&simplify(&Object::Expression(Expression::Sum(vec![
+        lhs,
+        negation(rhs.to_owned()),
+    ]))));
+
+    Some(Object::Boolean(match sign.and_then(decide) {
+        Some(val) => val.into(),
+        None => Bool::unknown(),
+    }))
+}
+
+pub fn less(lhs: Object, rhs: &Object) -> Option<Object> {
+    compare(lhs, rhs, |sign| match sign {
+        Sign::Negative => Some(true),
+        Sign::Zero | Sign::NonNegative | Sign::Positive => Some(false),
+        Sign::NonPositive => None,
+    })
+}
+
+pub fn less_equal(lhs: Object, rhs: &Object) -> Option<Object> {
+    compare(lhs, rhs, |sign| match sign {
+        Sign::Negative | Sign::NonPositive | Sign::Zero => Some(true),
+        Sign::Positive => Some(false),
+        Sign::NonNegative => None,
+    })
+}
+
+pub fn greater(lhs: Object, rhs: &Object) -> Option<Object> {
+    compare(lhs, rhs, |sign| match sign {
+        Sign::Positive => Some(true),
+        Sign::Zero | Sign::NonPositive | Sign::Negative => Some(false),
+        Sign::NonNegative => None,
+    })
+}
+
+pub fn greater_equal(lhs: Object, rhs: &Object) -> Option<Object> {
+    compare(lhs, rhs, |sign| match sign {
+        Sign::Positive | Sign::NonNegative | Sign::Zero => Some(true),
+        Sign::Negative => Some(false),
+        Sign::NonPositive => None,
+    })
+}
+
+fn replace(expr: &Object, values: &BTreeMap<Object, Object>) -> Object {
+    let replace_all =
+        |objs: &[Object]| -> Vec<Object> { objs.iter().map(|obj| replace(obj, values)).collect() };
+
+    match expr {
+        Object::Symbol(_) => values.get(expr).unwrap_or(expr).to_owned(),
+        Object::Expression(Expression::Sum(terms)) => {
+            Object::Expression(Expression::Sum(replace_all(terms)))
+        }
+        Object::Expression(Expression::Product(factors)) => {
+            Object::Expression(Expression::Product(replace_all(factors)))
+        }
+        Object::Expression(Expression::Power(base, exp)) => Object::Expression(Expression::Power(
+            Box::new(replace(base, values)),
+            Box::new(replace(exp, values)),
+        )),
+        Object::Expression(Expression::Remainder(lhs, rhs)) => {
+            Object::Expression(Expression::Remainder(
+                Box::new(replace(lhs, values)),
+                Box::new(replace(rhs, values)),
+            ))
+        }
+        Object::Expression(Expression::Call(name, args)) => match replace_all(args).as_slice() {
+            [arg] => elementary::apply(name, arg),
+            args => call(name, args.to_vec()),
+        },
+        Object::Expression(Expression::Equation(lhs, rhs)) => {
+            Object::Expression(Expression::Equation(
+                Box::new(replace(lhs, values)),
+                Box::new(replace(rhs, values)),
+            ))
+        }
+        Object::Expression(Expression::Inequation(lhs, rhs)) => {
+            Object::Expression(Expression::Inequation(
+                Box::new(replace(lhs, values)),
+                Box::new(replace(rhs, values)),
+            ))
+        }
+        obj => obj.to_owned(),
+    }
+}
+
+/// Replaces the symbols of `expr` by the values in `values` and simplifies
+/// the result, so binding every symbol to a number gives a number.
+pub fn substitute(expr: &Object, values: &BTreeMap<Object, Object>) -> Object {
+    simplify(&replace(expr, values))
+}
+
+/// Whether `expr` divides by zero somewhere, like `x/0`, which simplifying
+/// leaves as it is.
+fn divides_by_zero(expr: &Object) -> bool {
+    match expr {
+        Object::Expression(Expression::Power(base, exp)) => {
+            (base.is_zero() && exp.is_negative()) || divides_by_zero(base) || divides_by_zero(exp)
+        }
+        Object::Expression(Expression::Remainder(lhs, rhs)) => {
+            rhs.is_zero() || divides_by_zero(lhs) || divides_by_zero(rhs)
+        }
+        Object::Expression(
+            Expression::Sum(objs) | Expression::Product(objs) | Expression::Call(_, objs),
+        ) => objs.iter().any(divides_by_zero),
+        Object::Expression(
+            Expression::Equation(lhs, rhs)
+            | Expression::Inequation(lhs, rhs)
+            | Expression::Range(lhs, rhs),
+        ) => divides_by_zero(lhs) || divides_by_zero(rhs),
+        _ => false,
+    }
+}
+
+/// Like `substitute`, but fails when the values make the expression divide
+/// by zero.
+pub fn evaluate(expr: &Object, values: &BTreeMap<Object, Object>) -> Result<Object, EvalError> {
+    let res = substitute(expr, values);
+
+    if divides_by_zero(&res) {
+        Err(EvalError::DenominatorZero)
+    } else {
+        Ok(res)
+    }
+}
+
+pub fn subs(args: &[Object]) -> Result<Object, EvalError> {
+    let bad_argument =
+        |expected: &str, obj: &Object| EvalError::bad_argument("subs", expected, obj);
+
+    let values = match &args[1] {
+        Object::Dictionary(dict) => &dict.dict,
+        obj => return Err(bad_argument("Dictionary", obj)),
+    };
+
+    let mut operands = vec![&args[0]];
+
+    for (key, value) in values {
+        if !matches!(key, Object::Symbol(_)) {
+            return Err(bad_argument("Symbol", key));
+        }
+
+        operands.push(value);
+
+        if !is_operand(value) || !constants_operate(&operands) {
+            return Err(bad_argument("Number", value));
+        }
+    }
+
+    evaluate(&args[0], values)
+}
+
+impl InfixOperable for Expression {
+    fn sum(&self, other: &Object) -> Option<Object> {
+        sum(self.operand()?, other)
+    }
+
+    fn substraction(&self, other: &Object) -> Option<Object> {
+        substraction(self.operand()?, other)
+    }
+
+    fn product(&self, other: &Object) -> Option<Object> {
+        product(self.operand()?, other)
+    }
+
+    fn over(&self, other: &Object) -> Option<Object> {
+        over(self.operand()?, other)
+    }
+
+    fn pow(&self, other: &Object) -> Option<Object> {
+        pow(self.operand()?, other)
+    }
+
+    fn rem(&self, other: &Object) -> Option<Object> {
+        remainder(self.operand()?, other)
+    }
+
+    fn less(&self, other: &Object) -> Option<Object> {
+        less(self.operand()?, other)
+    }
+
+    fn less_equal(&self, other: &Object) -> Option<Object> {
+        less_equal(self.operand()?, other)
+    }
+
+    fn greater(&self, other: &Object) -> Option<Object> {
+        greater(self.operand()?, other)
+    }
+
+    fn greater_equal(&self, other: &Object) -> Option<Object> {
+        greater_equal(self.operand()?, other)
+    }
+}
+
+impl PrefixOperable for Expression {
+    fn inverse(&self) -> Option<Object> {
+        Some(negation(self.operand()?))
+    }
+}
+
+/// Whether `obj` is written with a leading minus, like `-2` or `-2i`.
+fn has_minus_sign(obj: &Object) -> bool {
+    match obj {
+        Object::Complex(z) => z.re.is_zero() && z.im.is_negative(),
+        obj => obj.is_negative(),
+    }
+}
+
+fn negative_coefficient(obj: &Object) -> bool {
+    match obj {
+        Object::Expression(Expression::Product(factors)) => {
+            factors.first().is_some_and(has_minus_sign)
+        }
+        obj => has_minus_sign(obj),
+    }
+}
+
+fn without_sign(obj: &Object) -> Object {
+    match obj {
+        Object::Expression(Expression::Product(factors)) => {
+            let mut factors = factors.to_owned();
+            let coefficient = negation(factors.remove(0));
+
+            if !coefficient.is_one() {
+                factors.insert(0, coefficient);
+            }
+
+            match factors.len() {
+                1 => factors.remove(0),
+                _ => Object::Expression(Expression::Product(factors)),
+            }
+        }
+        obj => negation(obj.to_owned()),
+    }
+}
+
+fn reciprocal_exponent(obj: &Object) -> Option<Object> {
+    match obj {
+        Object::Expression(Expression::Power(base, exp)) if exp.is_negative() => {
+            let exp = negation(*exp.to_owned());
+
+            if exp.is_one() {
+                Some(*base.to_owned())
+            } else {
+                Some(Object::Expression(Expression::Power(
+                    base.to_owned(),
+                    Box::new(exp),
+                )))
+            }
+        }
+        _ => None,
+    }
+}
+
+fn is_sum(obj: &Object) -> bool {
+    matches!(obj, Object::Expression(Expression::Sum(_)))
+}
+
+fn is_product(obj: &Object) -> bool {
+    matches!(obj, Object::Expression(Expression::Product(_)))
+}
+
+fn is_power(obj: &Object) -> bool {
+    matches!(obj, Object::Expression(Expression::Power(..)))
+}
+
+fn is_remainder(obj: &Object) -> bool {
+    matches!(obj, Object::Expression(Expression::Remainder(..)))
+}
+
+/// Whether `obj` is a number written with an operator, like `1 + 2i` or
+/// `1 mod 3`.
+fn is_compound_number(obj: &Object) -> bool {
+    match obj {
+        Object::Complex(z) => !z.re.is_zero(),
+        obj => matches!(obj, Object::Modular(_)),
+    }
+}
+
+fn parenthesized(obj: &Object, needs_parens: bool) -> String {
+    if needs_parens {
+        format!("({obj})")
+    } else {
+        obj.to_string()
+    }
+}
+
+fn factor(obj: &Object, is_first: bool) -> String {
+    let needs_parens = is_sum(obj)
+        || is_remainder(obj)
+        || is_compound_number(obj)
+        || matches!(obj, Object::Fraction(_))
+        || (!is_first && has_minus_sign(obj))
+        || (is_product(obj) && negative_coefficient(obj));
+
+    parenthesized(obj, needs_parens)
+}
+
+fn fmt_factors(factors: &[Object]) -> String {
+    match factors {
+        [] => "1".into(),
+        [first, rest @ ..] if first.is_minus_one() && !rest.is_empty() => {
+            let rest_str = fmt_factors(rest);
+            if prints_as_power(&rest[0]) || has_minus_sign(&rest[0]) {
+                format!("-({rest_str})")
+            } else {
+                format!("-{rest_str}")
+            }
+        }
+        _ => factors
+            .iter()
+            .enumerate()
+            .map(|(i, obj)| factor(obj, i == 0))
+            .collect::<Vec<_>>()
+            .join("*"),
+    }
+}
+
+fn fmt_product(factors: &[Object]) -> String {
+    let mut numerator = vec![];
+    let mut denominator = vec![];
+
+    for obj in factors {
+        match (reciprocal_exponent(obj), obj) {
+            (Some(obj), _) => denominator.push(obj),
+            (None, Object::Fraction(Fraction { val })) => {
+                let numer = Object::Integer(Integer::from(val.numer().to_owned()));
+                if !numer.is_one() {
+                    numerator.push(numer);
+                }
+
+                denominator.push(Object::Integer(Integer::from(val.denom().to_owned())));
+            }
+            (None, obj) => numerator.push(obj.to_owned()),
+        }
+    }
+
+    match denominator.as_slice() {
+        [] => fmt_factors(&numerator),
+        [single] if !is_product(single) && !single.is_negative() => {
+            format!("{}/{}", fmt_factors(&numerator), factor(single, true))
+        }
+        _ => format!(
+            "{}/({})",
+            fmt_factors(&numerator),
+            fmt_factors(&denominator)
+        ),
+    }
+}
+
+fn fmt_sum(terms: &[Object]) -> String {
+    let mut res = String::new();
+
+    let term = |obj: &Object| parenthesized(obj, matches!(obj, Object::Modular(_)));
+
+    for (i, obj) in terms.iter().enumerate() {
+        if i == 0 {
+            res.push_str(&term(obj));
+        } else if negative_coefficient(obj) {
+            res.push_str(" - ");
+            res.push_str(&term(&without_sign(obj)));
+        } else {
+            res.push_str(" + ");
+            res.push_str(&term(obj));
+        }
+    }
+
+    res
+}
+
+fn is_square_root(exp: &Object) -> bool {
+    match exp {
+        Object::Fraction(Fraction { val }) => val.numer().is_one() && *val.denom() == 2.into(),
+        _ => false,
+    }
+}
+
+/// Whether `obj` is written with `**`, which binds looser than a unary
+/// minus.
+fn prints_as_power(obj: &Object) -> bool {
+    match obj {
+        Object::Expression(Expression::Power(_, exp)) => !is_square_root(exp) && !exp.is_negative(),
+        _ => false,
+    }
+}
+
+fn fmt_power(base: &Object, exp: &Object) -> String {
+    if is_square_root(exp) {
+        return format!("sqrt({base})");
+    }
+
+    let base_parens = is_sum(base)
+        || is_product(base)
+        || is_power(base)
+        || is_remainder(base)
+        || has_minus_sign(base)
+        || matches!(
+            base,
+            Object::Fraction(_) | Object::Decimal(_) | Object::Complex(_) | Object::Modular(_)
+        );
+
+    let exp_parens = match exp {
+        Object::Symbol(_) | Object::Expression(Expression::Call(..)) => false,
+        Object::Integer(_) => exp.is_negative(),
+        _ => true,
+    };
+
+    format!(
+        "{}**{}",
+        parenthesized(base, base_parens),
+        parenthesized(exp, exp_parens)
+    )
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expression::Sum(terms) => write!(f, "{}", fmt_sum(terms)),
+            Expression::Product(factors) => write!(f, "{}", fmt_product(factors)),
+            Expression::Power(_, exp) if exp.is_negative() => {
+                write!(f, "{}", fmt_product(&[Object::Expression(self.to_owned())]))
+            }
+            Expression::Power(base, exp) => write!(f, "{}", fmt_power(base, exp)),
+            Expression::Remainder(lhs, rhs) => {
+                let rhs_parens = is_sum(rhs)
+                    || is_product(rhs)
+                    || is_remainder(rhs)
+                    || is_compound_number(rhs)
+                    || matches!(**rhs, Object::Fraction(_));
+
+                write!(
+                    f,
+                    "{} % {}",
+                    parenthesized(lhs, is_sum(lhs) || is_compound_number(lhs)),
+                    parenthesized(rhs, rhs_parens)
+                )
+            }
+            Expression::Call(name, args) => {
+                let args = args
+                    .iter()
+                    .map(|arg| arg.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                write!(f, "{name}({args})")
+            }
+            Expression::Equation(lhs, rhs) => write!(f, "{lhs} = {rhs}"),
+            Expression::Inequation(lhs, rhs) => write!(f, "{lhs} /= {rhs}"),
+            Expression::Range(start, end) => write!(f, "{start}..{end}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        complex::Complex,
+        object::Modular,
+        testing::{int, x, y},
+    };
+
+    use super::*;
+
+    #[test]
+    fn symbol_plus_integer() {
+        assert_eq!(
+            x().sum(&int(1)),
+            Some(Object::Expression(Expression::Sum(vec![x(), int(1)])))
+        );
+    }
+
+    #[test]
+    fn integer_times_symbol() {
+        assert_eq!(
+            int(2).product(&x()),
+            Some(Object::Expression(Expression::Product(vec![int(2), x()])))
+        );
+    }
+
+    #[test]
+    fn non_numeric_operand() {
+        assert_eq!(x().sum(&Object::String("foo".into())), None);
+    }
+
+    #[test]
+    fn symbolic_remainder() {
+        assert_eq!(x().rem(&int(2)).unwrap().to_string(), "x % 2");
+        assert_eq!(int(2).rem(&x()).unwrap().to_string(), "2 % x");
+        assert_eq!(
+            x().rem(&int(3))
+                .unwrap()
+                .product(&int(2))
+                .unwrap()
+                .to_string(),
+            "2*(x % 3)"
+        );
+    }
+
+    #[test]
+    fn complex_operand() {
+        let two_i = Object::Complex(Complex::new(int(0), int(2)));
+
+        assert_eq!(x().product(&two_i).unwrap().to_string(), "2i*x");
+        assert_eq!(two_i.product(&x()), x().product(&two_i));
+        assert_eq!(x().substraction(&two_i).unwrap().to_string(), "x - 2i");
+    }
+
+    #[test]
+    fn modular_operand() {
+        let one = Object::Modular(Modular::new(&Integer::from(1), &Integer::from(3)));
+        let other = Object::Modular(Modular::new(&Integer::from(1), &Integer::from(4)));
+        let sum = x().sum(&one).unwrap();
+
+        assert_eq!(sum.to_string(), "x + (1 mod 3)");
+        assert_eq!(sum.sum(&other), None);
+        assert_eq!(
+            sum.product(&Object::Complex(Complex::new(int(0), int(1)))),
+            None
+        );
+    }
+
+    #[test]
+    fn display_polynomial() {
+        let expr = x()
+            .pow(&int(2))
+            .unwrap()
+            .product(&int(3))
+            .unwrap()
+            .substraction(&x())
+            .unwrap()
+            .sum(&int(1))
+            .unwrap();
+
+        assert_eq!(expr.to_string(), "3*x**2 - x + 1");
+    }
+
+    #[test]
+    fn display_quotient() {
+        let expr = x().over(&y().sum(&int(1)).unwrap()).unwrap();
+
+        assert_eq!(expr.to_string(), "x/(y + 1)");
+    }
+
+    #[test]
+    fn display_negation() {
+        let expr = x().pow(&int(2)).unwrap().inverse().unwrap();
+
+        assert_eq!(expr.to_string(), "-(x**2)");
+    }
+
+    #[test]
+    fn display_negated_root() {
+        let root = x().pow(&Object::Fraction(Fraction::_new(1, 2))).unwrap();
+
+        assert_eq!(root.inverse().unwrap().to_string(), "-sqrt(x)");
+        assert_eq!(
+            x().sum(&int(1)).unwrap().inverse().unwrap().to_string(),
+            "-(x + 1)"
+        );
+    }
+
+    #[test]
+    fn display_call() {
+        let expr = call("f", vec![x(), int(2)]).pow(&y()).unwrap();
+
+        assert_eq!(expr.to_string(), "f(x, 2)**y");
+    }
+
+    #[test]
+    fn substitute_numbers() {
+        let expr = x().pow(&int(2)).unwrap().sum(&y()).unwrap();
+        let values = BTreeMap::from([(x(), int(2)), (y(), Object::Fraction(Fraction::_new(1, 3)))]);
+
+        assert_eq!(
+            substitute(&expr, &values),
+            Object::Fraction(Fraction::_new(13, 3))
+        );
+    }
+
+    #[test]
+    fn substitute_expression() {
+        let expr = x().product(&y()).unwrap();
+        let values = BTreeMap::from([(x(), y().sum(&int(1)).unwrap())]);
+
+        assert_eq!(substitute(&expr, &values).to_string(), "y**2 + y");
+    }
+
+    #[test]
+    fn substitute_elementary_call() {
+        let expr = call("sin", vec![x()]).sum(&int(1)).unwrap();
+        let values = BTreeMap::from([(x(), int(0))]);
+
+        assert_eq!(substitute(&expr, &values), int(1));
+    }
+
+    #[test]
+    fn substitute_remainder() {
+        let expr = x().rem(&y()).unwrap();
+        let values = BTreeMap::from([(x(), int(7)), (y(), int(3))]);
+
+        assert_eq!(substitute(&expr, &values), int(1));
+        assert!(evaluate(&expr, &BTreeMap::from([(y(), int(0))])).is_err());
+    }
+
+    #[test]
+    fn evaluate_division_by_zero() {
+        let expr = x().over(&y()).unwrap();
+        let values = BTreeMap::from([(y(), int(0))]);
+
+        assert_eq!(evaluate(&expr, &values), Err(EvalError::DenominatorZero));
+    }
+
+    #[test]
+    fn display_fraction_coefficient() {
+        let expr = Object::Fraction(Fraction::_new(-3, 2))
+            .product(&x())
+            .unwrap();
+
+        assert_eq!(expr.to_string(), "-3*x/2");
+    }
+
+    #[test]
+    fn symbolic_inequation() {
+        assert_eq!(
+            x().neq(&int(1)),
+            Some(Object::Expression(Expression::Inequation(
+                Box::new(x()),
+                Box::new(int(1))
+            )))
+        );
+        assert_eq!(x().neq(&x()), Some(Object::Boolean(false.into())));
+        assert_eq!(x().sum(&int(1)).unwrap().neq(&x()), Some(true.into()));
+    }
+
+    #[test]
+    fn symbolic_equation() {
+        let expr = x().pow(&int(2)).unwrap();
+
+        assert_eq!(
+            equation(expr.to_owned(), int(4)),
+            Some(Object::Expression(Expression::Equation(
+                Box::new(expr.to_owned()),
+                Box::new(int(4))
+            )))
+        );
+        assert_eq!(
+            equation(expr.to_owned(), x().product(&x()).unwrap()),
+            Some(Object::Boolean(true.into()))
+        );
+        assert_eq!(
+            equation(expr.to_owned(), int(4)).unwrap().sum(&int(1)),
+            None
+        );
+    }
+
+    #[test]
+    fn undecided_comparison() {
+        let unknown = Some(Object::Boolean(Bool::unknown()));
+
+        assert_eq!(x().less(&int(1)), unknown);
+        assert_eq!(x().greater_equal(&x()), Some(true.into()));
+        assert_eq!(x().sum(&int(1)).unwrap().greater(&x()), Some(true.into()));
+    }
+}
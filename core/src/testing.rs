@@ -0,0 +1,20 @@
+//! Objects that the unit tests of several modules share.
+
+use crate::object::{Object, Symbol};
+
+/// A symbol like the one `let name: property` declares.
+pub fn symbol(name: &str, property: &str) -> Object {
+    Object::Symbol(Symbol::new(name.into(), property.into()))
+}
+
+pub fn x() -> Object {
+    symbol("x", "Real")
+}
+
+pub fn y() -> Object {
+    symbol("y", "Real")
+}
+
+pub fn int(val: i64) -> Object {
+    Object::int(val)
+}
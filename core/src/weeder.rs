@@ -3,7 +3,7 @@ use crate::{
     cst::{
         CSTNode, CSTNodeKind, ComprehensionKind, DeclarationKind, InfixOperator, PrefixOperator,
     },
-    error::Error,
+    error::{Error, Position},
     lexer::Radix,
 };
 
@@ -11,6 +11,7 @@ use crate::{
 pub enum WeederError {
     BadDeclaration,
     BadDot,
+    BadGenerator,
     BadImportOrigin,
     BadImportSymbol,
     BadSymbolicDeclaration,
@@ -18,6 +19,8 @@ pub enum WeederError {
     BadAnonFunctionLHS,
     BadAnonFunctionParameter,
     MemoizedNonFunctionDeclaration,
+    MisplacedGuard,
+    MisplacedWhere,
     MutableFunctionDeclaration,
     PlainImportNotImplemented,
 }
@@ -34,6 +37,7 @@ pub fn rewrite(node: CSTNode) -> WeederResult<ASTNode> {
         CSTNodeKind::If(cond, positive, negative) => _if(*cond, *positive, *negative),
         CSTNodeKind::Infix(op, lhs, rhs) => infix(op, *lhs, *rhs),
         CSTNodeKind::Integer(dec, radix) => integer(dec, radix),
+        CSTNodeKind::Imaginary(dec) => imaginary(dec, node.position),
         CSTNodeKind::Prefix(op, val) => prefix(op, *val),
         CSTNodeKind::Cons(first, tail) => cons(*first, *tail),
         CSTNodeKind::String(str) => string(str),
@@ -50,13 +54,21 @@ pub fn rewrite(node: CSTNode) -> WeederResult<ASTNode> {
         CSTNodeKind::ImportFrom { source, values } => import_from(*source, *values),
         CSTNodeKind::Comprehension {
             element,
-            variable,
-            iterator,
+            value,
+            generators,
             kind,
-        } => comprehension(*element, variable, *iterator, kind),
+        } => comprehension(*element, value.map(|val| *val), generators, kind),
         CSTNodeKind::Block(exprs) => block(exprs),
         CSTNodeKind::Declaration(node, kind) => declaration(*node, kind),
+        CSTNodeKind::LetIn { declaration, body } => Ok(ASTNodeKind::Local {
+            declarations: vec![rewrite(*declaration)?],
+            body: Box::new(rewrite(*body)?),
+        }),
         CSTNodeKind::Case { expr, pairs } => case(*expr, pairs),
+        CSTNodeKind::WithPrecision { digits, body } => Ok(ASTNodeKind::WithPrecision {
+            digits: Box::new(rewrite(*digits)?),
+            body: Box::new(rewrite(*body)?),
+        }),
     }?;
 
     Ok(ASTNode::new(tp, node.position))
@@ -83,21 +95,41 @@ fn char(chr: char) -> WeederResult<ASTNodeKind> {
 
 fn comprehension(
     element: CSTNode,
-    variable: String,
-    iterator: CSTNode,
+    value: Option<CSTNode>,
+    generators: Vec<CSTNode>,
     kind: ComprehensionKind,
 ) -> WeederResult<ASTNodeKind> {
     let element = Box::new(rewrite(element)?);
-    let iterator = Box::new(rewrite(iterator)?);
+    let value = value.map(rewrite).transpose()?.map(Box::new);
+    let generators = generators
+        .into_iter()
+        .map(generator)
+        .collect::<WeederResult<_>>()?;
 
     Ok(ASTNodeKind::Comprehension {
         element,
-        variable,
-        iterator,
+        value,
+        generators,
         kind,
     })
 }
 
+/// Separates a generator like `(a, b) in pairs if a < b` into its pattern,
+/// its iterable and its guard.
+fn generator(node: CSTNode) -> WeederResult<(ASTNode, ASTNode, Option<ASTNode>)> {
+    let position = node.position;
+    let (node, guard) = split_guard(node);
+
+    match node.kind {
+        CSTNodeKind::Infix(InfixOperator::In, pattern, iterable) => Ok((
+            rewrite(*pattern)?,
+            rewrite(*iterable)?,
+            guard.map(rewrite).transpose()?,
+        )),
+        _ => Err(Error::new(WeederError::BadGenerator.into(), position)),
+    }
+}
+
 fn decimal(int: String, dec: String) -> WeederResult<ASTNodeKind> {
     Ok(ASTNodeKind::Decimal { int, dec })
 }
@@ -129,6 +161,12 @@ fn fraction(numer: CSTNode, denom: CSTNode) -> WeederResult<ASTNodeKind> {
     Ok(ASTNodeKind::Fraction { numer, denom })
 }
 
+fn modular(val: CSTNode, modulus: CSTNode) -> WeederResult<ASTNodeKind> {
+    let val = Box::new(rewrite(val)?);
+    let modulus = Box::new(rewrite(modulus)?);
+    Ok(ASTNodeKind::Modular { val, modulus })
+}
+
 fn _if(cond: CSTNode, positive: CSTNode, negative: CSTNode) -> WeederResult<ASTNodeKind> {
     let cond = Box::new(rewrite(cond)?);
     let positive = Box::new(rewrite(positive)?);
@@ -183,6 +221,24 @@ fn infix(cst_op: InfixOperator, lhs: CSTNode, rhs: CSTNode) -> WeederResult<ASTN
             function(params, rhs)
         }
         InfixOperator::Division => infix_node(ast::InfixOperator::Division, lhs, rhs),
+        // Prefixes bind tighter than the dot, so `-1.5` is parsed as `(-1).5`
+        InfixOperator::Dot if is_prefixed_integer(&lhs) => {
+            let CSTNodeKind::Prefix(op, val) = lhs.kind else {
+                unreachable!()
+            };
+            let position = Position::new(
+                val.position.start,
+                rhs.position.start + rhs.position.length - val.position.start,
+            );
+
+            prefix(
+                op,
+                CSTNode::new(
+                    CSTNodeKind::Infix(InfixOperator::Dot, val, Box::new(rhs)),
+                    position,
+                ),
+            )
+        }
         InfixOperator::Dot => match (rewrite(lhs)?, rewrite(rhs)?) {
             (
                 ASTNode {
@@ -202,6 +258,33 @@ fn infix(cst_op: InfixOperator, lhs: CSTNode, rhs: CSTNode) -> WeederResult<ASTN
                     position: _,
                 },
             ) => decimal(int, dec),
+            (
+                ASTNode {
+                    kind:
+                        ASTNodeKind::Integer {
+                            literal: int,
+                            radix: Radix::Decimal,
+                        },
+                    position: _,
+                },
+                ASTNode {
+                    kind: ASTNodeKind::Imaginary(val),
+                    position,
+                },
+            ) => match *val {
+                ASTNode {
+                    kind:
+                        ASTNodeKind::Integer {
+                            literal: dec,
+                            radix: Radix::Decimal,
+                        },
+                    position: _,
+                } => Ok(ASTNodeKind::Imaginary(Box::new(ASTNode::new(
+                    decimal(int, dec)?,
+                    position,
+                )))),
+                _ => Err(Error::new(WeederError::BadDot.into(), position)),
+            },
             (
                 first_arg,
                 ASTNode {
@@ -224,6 +307,7 @@ fn infix(cst_op: InfixOperator, lhs: CSTNode, rhs: CSTNode) -> WeederResult<ASTN
         InfixOperator::Less => infix_node(ast::InfixOperator::Less, lhs, rhs),
         InfixOperator::LessEqual => infix_node(ast::InfixOperator::LessEqual, lhs, rhs),
         InfixOperator::LogicAnd => infix_node(ast::InfixOperator::LogicAnd, lhs, rhs),
+        InfixOperator::Modular => modular(lhs, rhs),
         InfixOperator::Or => infix_node(ast::InfixOperator::Or, lhs, rhs),
         InfixOperator::Rem => infix_node(ast::InfixOperator::Rem, lhs, rhs),
         InfixOperator::NotEquality => infix_node(ast::InfixOperator::NotEquality, lhs, rhs),
@@ -235,6 +319,8 @@ fn infix(cst_op: InfixOperator, lhs: CSTNode, rhs: CSTNode) -> WeederResult<ASTN
         InfixOperator::Element => container_element(lhs, rhs),
         InfixOperator::Assignment => assignment(lhs, rhs),
         InfixOperator::Constraint => pattern(lhs, rhs),
+        InfixOperator::Guard => Err(Error::new(WeederError::MisplacedGuard.into(), rhs.position)),
+        InfixOperator::Where => Err(Error::new(WeederError::MisplacedWhere.into(), rhs.position)),
     }
 }
 
@@ -254,6 +340,10 @@ fn container_element(container: CSTNode, element: CSTNode) -> WeederResult<ASTNo
     })
 }
 
+fn is_prefixed_integer(node: &CSTNode) -> bool {
+    matches!(&node.kind, CSTNodeKind::Prefix(_, val) if matches!(val.kind, CSTNodeKind::Integer(_, Radix::Decimal)))
+}
+
 fn infix_node(op: ast::InfixOperator, lhs: CSTNode, rhs: CSTNode) -> WeederResult<ASTNodeKind> {
     let lhs = Box::new(rewrite(lhs)?);
     let rhs = Box::new(rewrite(rhs)?);
@@ -267,27 +357,40 @@ fn integer(dec: String, radix: Radix) -> WeederResult<ASTNodeKind> {
     })
 }
 
+fn imaginary(dec: String, position: Position) -> WeederResult<ASTNodeKind> {
+    let val = ASTNode::new(integer(dec, Radix::Decimal)?, position);
+    Ok(ASTNodeKind::Imaginary(Box::new(val)))
+}
+
 fn declaration(node: CSTNode, kind: DeclarationKind) -> WeederResult<ASTNodeKind> {
     match node {
+        CSTNode {
+            kind: CSTNodeKind::Infix(InfixOperator::Where, node, bindings),
+            ..
+        } => where_(*node, *bindings, kind),
         CSTNode {
             kind: CSTNodeKind::Infix(InfixOperator::Assignment, left, right),
             ..
         } => match (destructure_call(&left), kind) {
             (Some((name, params)), DeclarationKind::Inmutable) => {
                 let params = rewrite_vec(params)?;
+                let guard = rewrite_guard(*left)?.map(Box::new);
                 let result = Box::new(rewrite(*right)?);
                 Ok(ASTNodeKind::Declaration(Declaration::Function {
                     name,
                     params,
+                    guard,
                     result,
                 }))
             }
             (Some((name, params)), DeclarationKind::InmutableMemoized) => {
                 let params = rewrite_vec(params)?;
+                let guard = rewrite_guard(*left)?.map(Box::new);
                 let result = Box::new(rewrite(*right)?);
                 Ok(ASTNodeKind::Declaration(Declaration::MemoizedFunction {
                     name,
                     params,
+                    guard,
                     result,
                 }))
             }
@@ -341,18 +444,91 @@ fn declaration(node: CSTNode, kind: DeclarationKind) -> WeederResult<ASTNodeKind
     }
 }
 
+/// A declaration whose value is computed with some local `bindings`.
+fn where_(node: CSTNode, bindings: CSTNode, kind: DeclarationKind) -> WeederResult<ASTNodeKind> {
+    let declarations = match bindings.kind {
+        CSTNodeKind::Block(exprs) => exprs.into_iter().map(binding).collect(),
+        _ => binding(bindings).map(|decl| vec![decl]),
+    }?;
+
+    let mut decl = match declaration(node, kind)? {
+        ASTNodeKind::Declaration(decl) => decl,
+        _ => unreachable!(),
+    };
+
+    let value = match &mut decl {
+        Declaration::Function { result, .. } | Declaration::MemoizedFunction { result, .. } => {
+            result
+        }
+        Declaration::Inmutable { right, .. } | Declaration::Mutable { right, .. } => right,
+        Declaration::Symbolic { .. } => {
+            return Err(Error::new(
+                WeederError::MisplacedWhere.into(),
+                declarations[0].position,
+            ))
+        }
+    };
+
+    let position = value.position;
+    let body = std::mem::replace(
+        value,
+        Box::new(ASTNode::new(ASTNodeKind::Wildcard, position)),
+    );
+    **value = ASTNode::new(ASTNodeKind::Local { declarations, body }, position);
+
+    Ok(ASTNodeKind::Declaration(decl))
+}
+
+/// A binding in a `where`, which is declared like with `let` unless it
+/// says otherwise.
+fn binding(node: CSTNode) -> WeederResult<ASTNode> {
+    match node.kind {
+        CSTNodeKind::Declaration(..) => rewrite(node),
+        CSTNodeKind::Infix(InfixOperator::Assignment, ..) => {
+            let position = node.position;
+            declaration(node, DeclarationKind::Inmutable).map(|kind| ASTNode::new(kind, position))
+        }
+        _ => Err(Error::new(
+            WeederError::BadDeclaration.into(),
+            node.position,
+        )),
+    }
+}
+
 fn case(expr: CSTNode, pairs: Vec<(CSTNode, CSTNode)>) -> WeederResult<ASTNodeKind> {
     let expr = Box::new(rewrite(expr)?);
-    let pairs: WeederResult<Vec<(ASTNode, ASTNode)>> =
-        pairs.into_iter().map(rewrite_pair).collect();
-    Ok(ASTNodeKind::Case {
-        expr,
-        pairs: pairs?,
-    })
+    let arms: WeederResult<Vec<(ASTNode, Option<ASTNode>, ASTNode)>> = pairs
+        .into_iter()
+        .map(|(left, right)| {
+            let (pattern, guard) = split_guard(left);
+            Ok((
+                rewrite(pattern)?,
+                guard.map(rewrite).transpose()?,
+                rewrite(right)?,
+            ))
+        })
+        .collect();
+    Ok(ASTNodeKind::Case { expr, arms: arms? })
+}
+
+/// Separates a pattern like `n if n > 0` from its guard.
+fn split_guard(node: CSTNode) -> (CSTNode, Option<CSTNode>) {
+    match node.kind {
+        CSTNodeKind::Infix(InfixOperator::Guard, pattern, guard) => (*pattern, Some(*guard)),
+        _ => (node, None),
+    }
+}
+
+fn rewrite_guard(node: CSTNode) -> WeederResult<Option<ASTNode>> {
+    split_guard(node).1.map(rewrite).transpose()
 }
 
 fn destructure_call(node: &CSTNode) -> Option<(String, Vec<CSTNode>)> {
     match node {
+        CSTNode {
+            kind: CSTNodeKind::Infix(InfixOperator::Guard, call, _),
+            ..
+        } => destructure_call(call),
         CSTNode {
             kind: CSTNodeKind::Infix(InfixOperator::Call, left, right),
             ..
@@ -481,15 +657,16 @@ fn import_from(source: CSTNode, values: CSTNode) -> WeederResult<ASTNodeKind> {
 #[cfg(test)]
 mod tests {
     use crate::{
-        ast,
+        ast::{self, ASTNode, ASTNodeKind, Declaration},
         cst::{
             self,
             tests::{dec_integer, dummy_pos, pattern, symbol},
-            InfixOperator,
+            ComprehensionKind, InfixOperator, PrefixOperator,
         },
+        error::{Error, Position},
     };
 
-    use super::rewrite;
+    use super::{rewrite, WeederError};
 
     #[test]
     fn inlined_function() {
@@ -576,6 +753,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn negative_decimal() {
+        let node = cst::infix(
+            InfixOperator::Dot,
+            cst::prefix(
+                PrefixOperator::Minus,
+                dec_integer("1", Position::new(1, 1)),
+                Position::new(0, 2),
+            ),
+            dec_integer("5", Position::new(3, 1)),
+            Position::new(0, 4),
+        );
+
+        assert_eq!(
+            rewrite(node),
+            Ok(ast::tests::prefix(
+                PrefixOperator::Minus,
+                ast::tests::decimal("1", "5", Position::new(1, 3)),
+                Position::new(0, 4)
+            ))
+        );
+    }
+
+    #[test]
+    fn imaginary_decimal() {
+        let node = cst::infix(
+            InfixOperator::Dot,
+            dec_integer("1", dummy_pos()),
+            cst::tests::imaginary("5", dummy_pos()),
+            dummy_pos(),
+        );
+
+        assert_eq!(
+            rewrite(node),
+            Ok(ast::tests::imaginary(
+                ast::tests::decimal("1", "5", dummy_pos()),
+                dummy_pos()
+            ))
+        );
+    }
+
     #[test]
     fn container_element() {
         let node = cst::infix(
@@ -664,4 +882,112 @@ mod tests {
             )),
         );
     }
+
+    #[test]
+    fn let_guarded_function() {
+        let node = cst::tests::let_(
+            cst::infix(
+                InfixOperator::Guard,
+                cst::infix(
+                    InfixOperator::Call,
+                    symbol("f", dummy_pos()),
+                    cst::tuple(vec![symbol("x", dummy_pos())], dummy_pos()),
+                    dummy_pos(),
+                ),
+                symbol("x", dummy_pos()),
+                dummy_pos(),
+            ),
+            Some(cst::tests::dec_integer("1", dummy_pos())),
+            dummy_pos(),
+        );
+
+        assert_eq!(
+            rewrite(node),
+            Ok(ASTNode::new(
+                ASTNodeKind::Declaration(Declaration::Function {
+                    name: "f".into(),
+                    params: vec![ast::tests::symbol("x", dummy_pos())],
+                    guard: Some(Box::new(ast::tests::symbol("x", dummy_pos()))),
+                    result: Box::new(ast::tests::dec_integer("1", dummy_pos())),
+                }),
+                dummy_pos()
+            )),
+        );
+    }
+
+    #[test]
+    fn misplaced_guard() {
+        let node = cst::infix(
+            InfixOperator::Guard,
+            symbol("x", dummy_pos()),
+            symbol("y", dummy_pos()),
+            dummy_pos(),
+        );
+
+        assert_eq!(
+            rewrite(node),
+            Err(Error::new(WeederError::MisplacedGuard.into(), dummy_pos())),
+        );
+    }
+
+    #[test]
+    fn bad_generator() {
+        let node = cst::comprehension(
+            symbol("x", dummy_pos()),
+            None,
+            vec![dec_integer("3", dummy_pos())],
+            ComprehensionKind::List,
+            dummy_pos(),
+        );
+
+        assert_eq!(
+            rewrite(node),
+            Err(Error::new(WeederError::BadGenerator.into(), dummy_pos())),
+        );
+    }
+
+    #[test]
+    fn where_binding() {
+        let node = cst::tests::let_(
+            cst::infix(
+                InfixOperator::Where,
+                cst::infix(
+                    InfixOperator::Assignment,
+                    symbol("b", dummy_pos()),
+                    symbol("a", dummy_pos()),
+                    dummy_pos(),
+                ),
+                cst::infix(
+                    InfixOperator::Assignment,
+                    symbol("a", dummy_pos()),
+                    dec_integer("1", dummy_pos()),
+                    dummy_pos(),
+                ),
+                dummy_pos(),
+            ),
+            None,
+            dummy_pos(),
+        );
+
+        let local = ASTNode::new(
+            ASTNodeKind::Local {
+                declarations: vec![ast::tests::let_(
+                    ast::tests::symbol("a", dummy_pos()),
+                    ast::tests::dec_integer("1", dummy_pos()),
+                    dummy_pos(),
+                )],
+                body: Box::new(ast::tests::symbol("a", dummy_pos())),
+            },
+            dummy_pos(),
+        );
+
+        assert_eq!(
+            rewrite(node),
+            Ok(ast::tests::let_(
+                ast::tests::symbol("b", dummy_pos()),
+                local,
+                dummy_pos()
+            )),
+        );
+    }
 }
@@ -1,9 +1,12 @@
+use std::num::NonZeroU64;
+
 use komodo::{
     builtin::standard_env,
-    env::ExecContext,
+    env::{EnvResponse, Environment, ExecContext},
     error::{Error, Position},
     exec::EvalError,
-    run::run,
+    object::{Precision, Rounding},
+    run::{run, ImportError},
 };
 
 #[test]
@@ -19,3 +22,839 @@ fn failed_assertion() {
         )),
     );
 }
+
+#[test]
+fn simplify_collects_terms() {
+    let code = "
+let x: Real
+let y: Real
+assert(simplify(x*y + y*x - 2*x*y) = 0)
+assert(simplify(x**2 * x / x**3) = 1)
+assert(simplify(2*(x + 1) - x) = x + 2)
+assert(x + 0 = x)
+assert({x, x + 0, x*1} = {x})
+assert(simplify(2*x + 3*x + x*2.0) = 7.0*x)
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn expansion() {
+    let code = "
+let x: Real
+let y: Real
+assert((x + 1)**2 = x**2 + 2*x + 1)
+assert(simplify((x + y)**2 - x**2 - 2*x*y - y**2) = 0)
+assert({(x + 1)*(x + 1), x**2 + 2*x + 1} = {x**2 + 2*x + 1})
+assert({(x - 1)*(x + 1) => 1} = {x**2 - 1 => 1})
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn differentiation() {
+    let code = "
+let x: Real
+assert(diff(x**3 + 2*x, x) = simplify(3*x**2 + 2))
+assert(diff(sin(x**2), x) = simplify(2*x*cos(x**2)))
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn substitution() {
+    let code = "
+let x: Real
+let y: Real
+assert(subs(x**2 + y, {x => 2, y => 1//3}) = 13//3)
+assert(subs(x*y, {x => 0.5, y => 4}) = 2.0)
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+    assert!(run("subs(x/y, {y => 0})", &mut env).is_err());
+    assert!(run("subs(x + y**(-2), {y => 0.0})", &mut env).is_err());
+}
+
+#[test]
+fn mixed_symbolic_operands() {
+    let code = "
+let x: Real
+assert(subs(x % 2, {x => 7}) = 1)
+assert(subs(2 % x, {x => 3}) = 2)
+assert(subs(x * 2i, {x => 3}) = 6i)
+assert(subs(x + (1 mod 3), {x => 4}) = 2 mod 3)
+assert(x * 2i + x * 3i = 5i * x)
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+    assert!(run("x + (1 mod 3) + (1 mod 4)", &mut env).is_err());
+    assert!(run("subs(x + (1 mod 3), {x => 2i})", &mut env).is_err());
+}
+
+#[test]
+fn polynomial_division() {
+    let code = "
+let x: Real
+let p := poly(x**3 - 2*x + 1)
+assert(p / (x - 1) = poly(x**2 + x - 1))
+assert(p % (x - 1) = poly(0))
+assert(gcd(p, poly(x**2 - 1)) = poly(x - 1))
+assert(coeffs(p) = [1, 0, -2, 1])
+assert(p(2) = 5)
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn factorization() {
+    let code = "
+let x: Real
+assert(factor(360) = [(2, 3), (3, 2), (5, 1)])
+assert(factor(poly(x**2 - 1)) = [(poly(x - 1), 1), (poly(x + 1), 1)])
+assert(factor(x**3 - 2*x**2 + x) = [(x - 1, 2), (x, 1)])
+let y: Real
+assert(factor(x*y**2 - x) = [(y - 1, 1), (y + 1, 1), (x, 1)])
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn multivariate_factor() {
+    let code = "let x: Real\nlet y: Real\nfactor(x**2 - y**2)";
+    let mut env = standard_env(ExecContext::default());
+
+    assert_eq!(
+        run(code, &mut env),
+        Err(Error::new(
+            EvalError::MultivariateFactor("x**2 - y**2".into()).into(),
+            Position::new(24, 19)
+        )),
+    );
+}
+
+#[test]
+fn factor_zero() {
+    let code = "factor(0)";
+    let mut env = standard_env(ExecContext::default());
+
+    assert_eq!(
+        run(code, &mut env),
+        Err(Error::new(
+            EvalError::FactorZero.into(),
+            Position::new(0, 9)
+        )),
+    );
+}
+
+#[test]
+fn equation_solving() {
+    let code = "
+let x: Real
+let a: Real
+assert(x + x = 2*x)
+assert(solve(x**2 - x = 2, x) = {-1, 2})
+assert(solve(x**2 = 2, x) = {sqrt(2), -sqrt(2)})
+assert(solve(a*x = 1, x) = {simplify(1/a)})
+assert(solve(x + 1 = x + 2, x) = {})
+assert(solve(2.0*x = 1, x) = {0.5})
+assert(subs(x /= 1, {x => 1}) = false)
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn symbol_properties() {
+    let code = "
+let k: Integer
+let n: Natural
+assert((-1)**(2*k) = 1)
+assert({(-1)**(2*k)} = {1})
+assert(n >= 0)
+assert(!(n = -1))
+assert(n**2 + 1 /= 0)
+assert(n**2 + 1 > 0)
+assert(solve(n**2 = 4, n) = {2})
+let p: Positive
+let q: Negative
+assert(p > 0)
+assert(p*q < 0)
+assert(q**2 + p > 0)
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn sums_and_products() {
+    let code = "
+let k: Integer
+let n: Natural
+assert(summation(k, k, 1..101) = 5050)
+assert(summation(k, k, 0..n) = n**2/2 - n/2)
+assert(summation(2**k, k, 0..n) = 2**n - 1)
+assert(product(k, k, 1..6) = 120)
+assert(product((k + 1)/k, k, 1..n) = n)
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn limits_and_series() {
+    let code = "
+let x: Real
+assert(limit(sin(x)/x, x, 0) = 1)
+assert(limit((1 - cos(x))/x**2, x, 0) = 1//2)
+assert(limit((3*x**2 + x)/(x**2 - 1), x, infinity) = 3)
+assert(limit(x**2, x, -infinity) = infinity)
+assert(series(exp(x), x, 0, 3) = x**3/6 + x**2/2 + x + 1)
+assert(series(1/(1 - x), x, 0, 2) = x**2 + x + 1)
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn decimal_precision() {
+    let code = "
+setPrecision(30)
+assert(precision() = 30)
+assert(1/3.0 = 0.333333333333333333333333333333)
+setPrecision(4, \"Floor\")
+assert(2/3.0 = 0.6666)
+assert(-2/3.0 + 0.6667 = 0.0)
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn numeric_solutions_precision() {
+    let code = "
+let x: Real
+assert((with precision 20 do solve(x**5 - x = 1, x)) = {1.1673039782614186843})
+assert((with precision 50 do solve(x**5 - x = 1, x)) = {1.1673039782614186842560458998548421807205603715255})
+assert((with precision 5 do solve(x**5 - x = 1000, x)) = {3.9842})
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn scoped_precision() {
+    let code = "
+assert((with precision 5 do 1/3.0) = 0.33333)
+assert(precision() = 100)
+let x := with precision 4 do
+    setPrecision(2)
+    1/3.0
+assert(x = 0.33)
+assert(precision() = 100)
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn precision_per_context() {
+    let digits = NonZeroU64::new(5).unwrap();
+    let mut short = standard_env(
+        ExecContext::default().with_precision(Precision::new(digits, Rounding::HalfEven)),
+    );
+    let mut long = standard_env(ExecContext::default());
+
+    assert!(run("setPrecision(10)", &mut long).is_ok());
+    assert!(run("assert(1/3.0 = 0.33333)", &mut short).is_ok());
+    assert!(run("assert(1/3.0 = 0.3333333333)", &mut long).is_ok());
+}
+
+#[test]
+fn module_context() {
+    let dir = std::env::temp_dir().join("komodo_module_context");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("thirds.komodo"), "let third := 1/3.0\n").unwrap();
+
+    let mut env = standard_env(ExecContext::new(dir));
+    let code = "
+setPrecision(5)
+from thirds import (third)
+assert(third = 0.33333)
+";
+
+    assert!(run(code, &mut env).is_ok());
+    assert!(matches!(
+        run("from missing import (third)", &mut env),
+        Err(Error(err, _)) if err == ImportError::ModuleNotFound("missing".into()).into()
+    ));
+}
+
+#[test]
+fn precision_after_import() {
+    let code = "
+setPrecision(5)
+from numtheory import (gcd)
+from cmath import (sqrt)
+from utils import (map)
+assert(1/3.0 = 0.33333)
+assert(precision() = 5)
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn elementary_functions() {
+    let code = "
+setPrecision(20)
+assert(sqrt(16//9) = 4//3)
+assert(sqrt(2.0) = 1.4142135623730950488)
+assert(2**0.5 = sqrt(2.0))
+assert(exp(1.0) = 2.7182818284590452354)
+assert(ln(10.0) = 2.3025850929940456840)
+assert(1.0*pi = 3.1415926535897932385)
+assert(sin(pi/6) = 1//2)
+assert(cos(pi) = -1)
+assert(atan(1) = pi/4)
+assert(atan(-sqrt(3)) = -pi/3)
+assert(-1.5 + 1 = -0.5)
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn logarithm_domain() {
+    let code = "ln(-1.0)";
+    let mut env = standard_env(ExecContext::default());
+
+    assert_eq!(
+        run(code, &mut env),
+        Err(Error::new(
+            EvalError::OutOfDomain {
+                func: "ln".into(),
+                arg: "-1.0".into(),
+            }
+            .into(),
+            Position::new(0, code.len())
+        )),
+    );
+}
+
+#[test]
+fn interval_logarithm_domain() {
+    let code = "ln(interval(-1, 1))";
+    let mut env = standard_env(ExecContext::default());
+
+    assert_eq!(
+        run(code, &mut env),
+        Err(Error::new(
+            EvalError::OutOfDomain {
+                func: "ln".into(),
+                arg: "interval(-1, 1)".into(),
+            }
+            .into(),
+            Position::new(0, code.len())
+        )),
+    );
+}
+
+#[test]
+fn number_theory_module() {
+    let code = "
+from numtheory import (isPrime, lcm, modpow, modinv, totient, divisors, jacobi)
+assert(isPrime(2**89 - 1))
+assert(!isPrime(2**67 - 1))
+assert(lcm(4, 6) = 12)
+assert(modpow(2, 100, 101) = 1)
+assert(modinv(3, 7) = 5)
+assert(totient(100) = 40)
+assert(divisors(28) = [1, 2, 4, 7, 14, 28])
+assert(jacobi(2, 15) = 1)
+assert(factor(2**67 - 1) = [(193707721, 1), (761838257287, 1)])
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn complex_numbers() {
+    let code = "
+let z := 3 + 4i
+assert(z*conj(z) = 25)
+assert(re(z) = 3 && im(z) = 4)
+assert(1i*1i = -1)
+assert({2, 2 + 0i} = {2})
+assert((1 + 2i)/(3 - 1i) = 1//10 + 7//10*1i)
+assert(sqrt(z) = 2 + 1i)
+assert(2.5i + 1 = 1.0 + 2.5i)
+assert(1i**1i = toDecimal(exp(-pi/2)))
+from cmath import (sqrt, pow)
+assert(sqrt(-4) = 2i)
+assert(pow(-4, 1//2) = 2i)
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn complex_division_by_zero() {
+    let code = "(1 + 2i) / 0i";
+    let mut env = standard_env(ExecContext::default());
+
+    assert_eq!(
+        run(code, &mut env),
+        Err(Error::new(
+            EvalError::DenominatorZero.into(),
+            Position::new(0, code.len())
+        )),
+    );
+}
+
+#[test]
+fn modular_integers() {
+    let code = "
+let a := 3 mod 7
+assert(a*5 = 1 mod 7)
+assert(1/a = 5 mod 7)
+assert(a**6 = Mod(7)(1))
+assert(2**100 mod 7 = 2 mod 7)
+let residue(x mod _) := x
+assert(residue(a - 4) = 6)
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn non_invertible_modular() {
+    let code = "1 / (2 mod 8)";
+    let mut env = standard_env(ExecContext::default());
+
+    assert_eq!(
+        run(code, &mut env),
+        Err(Error::new(
+            EvalError::NonInvertible("2 mod 8".into()).into(),
+            Position::new(0, 12)
+        )),
+    );
+}
+
+#[test]
+fn non_invertible_power() {
+    let code = "(2 mod 8) ** -1";
+    let mut env = standard_env(ExecContext::default());
+
+    assert_eq!(
+        run(code, &mut env),
+        Err(Error::new(
+            EvalError::NonInvertible("2 mod 8".into()).into(),
+            Position::new(0, code.len())
+        )),
+    );
+}
+
+#[test]
+fn bad_modular_call() {
+    let code = "let a := 1\nMod(7)(1//2)";
+    let mut env = standard_env(ExecContext::default());
+
+    assert_eq!(
+        run(code, &mut env),
+        Err(Error::new(
+            EvalError::BadModular {
+                val_kind: "Fraction".into(),
+                modulus_kind: "Integer".into(),
+            }
+            .into(),
+            Position::new(11, 12)
+        )),
+    );
+}
+
+#[test]
+fn linear_algebra() {
+    let code = "
+let a := matrix([[2, 1], [5, 3]])
+assert(det(a) = 1)
+assert(inverse(a) = matrix([[3, -1], [-5, 2]]))
+assert(a * inverse(a) = identity(2))
+assert(a**2 = a*a)
+assert(transpose(a) = matrix([[2, 5], [1, 3]]))
+let b := matrix([[1, 2, 3], [2, 4, 6]])
+assert(rank(b) = 1)
+assert(rref(b) = matrix([[1, 2, 3], [0, 0, 0]]))
+assert(b * nullspace(b)[0] = matrix([0, 0]))
+let x: Real
+assert(det(matrix([[x, 1], [1, x]])) = x**2 - 1)
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn fraction_decimal_conversion() {
+    let code = "
+assert(toDecimal(1 // 3, 5) = 0.33333)
+assert(toDecimal(pi, 10) = 3.141592654)
+assert(toFraction(0.125) = 1 // 8)
+assert(toFraction(pi, 1000) = 355 // 113)
+assert(toFraction(3.14159, 10) = 22 // 7)
+assert(continuedFraction(415 // 93) = [4, 2, 6, 7])
+assert(continuedFraction(sqrt(2), 5) = [1, 2, 2, 2, 2])
+assert(continuedFraction(pi, 4) = [3, 7, 15, 1])
+assert(convergents([4, 2, 6, 7]) = [4, 9 // 2, 58 // 13, 415 // 93])
+assert(convergents(pi, 3) = [3, 22 // 7, 333 // 106])
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn integer_digits() {
+    let code = "
+assert(toBase(0xff, 16) = \"ff\")
+assert(toBase(10, 2) = \"1010\")
+assert(digits(2**10) = [1, 0, 2, 4])
+assert(fromDigits(digits(12345, 7), 7) = 12345)
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn interval_arithmetic() {
+    let code = "
+let x := interval(exp(pi*sqrt(163)))
+assert(x > 262537412640768743)
+assert(x < 262537412640768744)
+assert(pi in interval(3.14, 3.15))
+assert((interval(1, 2) < 1.5) = unknown)
+assert((!(interval(1, 2) < 1.5)) = unknown)
+assert((interval(1, 2) = interval(1, 2)) = unknown)
+assert(interval(1, 1) = 1)
+assert(interval(1, 2) /= 3)
+assert((interval(1, 2) < 1.5 && false) = false)
+assert((interval(1, 2) < 1.5 || true) = true)
+assert(interval(1, 2) + 1 <= 3)
+assert(upper(sin(interval(1, 2))) = 1.0)
+setPrecision(5)
+let third := interval(1 // 3)
+assert(lower(third) = 0.33333)
+assert(upper(third) = 0.33334)
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn unknown_condition() {
+    let code = "if interval(1, 2) < 1.5 then 1 else 2";
+    let mut env = standard_env(ExecContext::default());
+
+    assert_eq!(
+        run(code, &mut env),
+        Err(Error::new(
+            EvalError::UnknownCondition.into(),
+            Position::new(3, 20)
+        )),
+    );
+}
+
+#[test]
+fn undecided_comparison() {
+    let code = "let x: Real\nif x < 1 then 1 else 2";
+    let mut env = standard_env(ExecContext::default());
+
+    assert_eq!(
+        run(code, &mut env),
+        Err(Error::new(
+            EvalError::UnknownCondition.into(),
+            Position::new(15, 5)
+        )),
+    );
+}
+
+#[test]
+fn undecided_equation() {
+    let code = "let x: Real\nif x = 1 then 1 else 2";
+    let mut env = standard_env(ExecContext::default());
+
+    assert_eq!(
+        run(code, &mut env),
+        Err(Error::new(
+            EvalError::UnknownCondition.into(),
+            Position::new(15, 5)
+        )),
+    );
+    assert_eq!(
+        run("assert(x /= 1)", &mut env).map_err(|err| err.0),
+        Err(EvalError::UnknownCondition.into())
+    );
+}
+
+#[test]
+fn guards() {
+    let code = "
+let sign(n) if n > 0 := 1
+let sign(n) if n < 0 := -1
+let sign(_) := 0
+assert([sign(k) for k in -2..3] = [-1, -1, 0, 1, 1])
+let parity(x) :=
+    case x do
+        n if n % 2 = 0 => \"even\"
+        _ => \"odd\"
+assert(parity(4) = \"even\")
+assert(parity(7) = \"odd\")
+let positive(n) if n > 0 := n
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+    assert!(run("positive(-1)", &mut env).is_err());
+    assert!(run("let x := 1 if true", &mut env).is_err());
+}
+
+#[test]
+fn local_bindings() {
+    let code = "
+let hyp(x, y) := let s := x**2 + y**2 in sqrt(s)
+assert(hyp(3, 4) = 5)
+let roots(a, b, c) := {(-b + d) / (2*a), (-b - d) / (2*a)} where
+    disc := b**2 - 4*a*c
+    d := sqrt(disc)
+assert(roots(1, -3, 2) = {1, 2})
+let total := sq(3) + sq(4) where sq(n) := n * n
+assert(total = 25)
+let member := (2 in {1, 2})
+assert(member)
+let b := 2 in {1, 2}
+assert(b)
+assert((let a := 1 in a + 1) = 2)
+let inner() :=
+    let c := 3 in {1, 2}
+    c
+assert(!inner())
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+    assert!(run("s", &mut env).is_err());
+    assert!(run("sq(2)", &mut env).is_err());
+    assert!(run("println(1 where a := 2)", &mut env).is_err());
+}
+
+#[test]
+fn tail_calls() {
+    let code = "
+let count(0, acc) := acc
+let count(n, acc) := count(n - 1, acc + 1)
+assert(count(20000, 0) = 20000)
+let isEven(0) := true
+let isEven(n) := isOdd(n - 1)
+let isOdd(0) := false
+let isOdd(n) := isEven(n - 1)
+assert(isOdd(10001))
+let countdown(n) :=
+    case n do
+        0 => \"done\"
+        _ => if n > 0 then countdown(n - 1) else countdown(n + 1)
+assert(countdown(10000) = \"done\")
+assert(countdown(-10000) = \"done\")
+from utils import sum
+assert(sum([k for k in 1..2001]) = 2001000)
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn recursion_depth() {
+    let code = "
+let depth(0) := 0
+let depth(n) := 1 + depth(n - 1)
+";
+    let mut env = standard_env(ExecContext::default());
+    run(code, &mut env).unwrap();
+
+    assert!(run("assert(depth(20000) = 20000)", &mut env).is_ok());
+
+    let mut env = standard_env(ExecContext::default().with_recursion_limit(100));
+    run(code, &mut env).unwrap();
+
+    assert!(matches!(
+        run("depth(100)", &mut env),
+        Err(Error(err, _)) if err == EvalError::RecursionDepthExceeded.into()
+    ));
+    assert!(run("assert(depth(99) = 99)", &mut env).is_ok());
+}
+
+#[test]
+fn memoized_tail_calls() {
+    let code = "
+from random import (randInt)
+let memoize roll(n) := randInt(0..1000000)
+let rollTwice(n) := roll(n)
+let first := rollTwice(1)
+assert(roll(1) = first)
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn memoized_functions_as_keys() {
+    let code = "
+let memoize fib(0) := 0
+let memoize fib(1) := 1
+let memoize fib(n) := fib(n - 1) + fib(n - 2)
+let fns := {fib}
+let names := {fib => \"fib\"}
+assert(fib(20) = 6765)
+assert(fib in fns)
+assert(names[fib] = \"fib\")
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn shadowing() {
+    let code = include_str!("../../examples/shadowing.komodo");
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn nested_comprehensions() {
+    let code = "
+from numtheory import (gcd)
+let coprime := [(a, b) for a in 1..5, b in a..5 if gcd(a, b) = 1]
+assert(coprime = [(1, 1), (1, 2), (1, 3), (1, 4), (2, 3), (3, 4)])
+assert({a * b for a in 1..3, b in 1..3} = {1, 2, 4})
+assert({k => k**2 for k in 0..4 if k /= 2} = {0 => 0, 1 => 1, 3 => 9})
+assert([a + b for (a, b) in [(1, 2), (3, 4)]] = [3, 7])
+assert([x for [x, _] in [[1, 2], [3], [5, 6]]] = [1, 5])
+assert(take([(a, b) for a in 1.., b in 1..a if a % b = 0], 4) = [(2, 1), (3, 1), (4, 1), (4, 2)])
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+    assert!(run("{k => k for k in 1..}", &mut env).is_err());
+}
+
+#[test]
+fn lazy_streams() {
+    let code = "
+let squares := [n**2 for n in 1..]
+assert(take(squares, 4) = [1, 4, 9, 16])
+assert(take(drop(squares, 2), 2) = [9, 16])
+assert(takeWhile(iterate(x -> 2*x, 1), x -> x < 10) = [1, 2, 4, 8])
+assert(take(sequence(n -> n + 1), 3) = [1, 2, 3])
+assert(drop([1, 2, 3], 1) = [2, 3])
+var total := 0
+for n in takeWhile(squares, x -> x < 50) do
+    total := total + n
+assert(total = 140)
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+    assert!(run("{n for n in 1..}", &mut env).is_err());
+    assert!(run("take(1 // 2.., 3)", &mut env).is_err());
+    assert!(run("[x for x in [1, 2], y in 1..]", &mut env).is_err());
+    assert!(run("take([(x, y) for x in 1..3, y in 1..], 2)", &mut env).is_err());
+    assert!(run("take([(x, y) for x in 1.., y in 1..], 2)", &mut env).is_err());
+}
+
+#[test]
+fn random_module() {
+    let code = "
+from random import (seed, randInt, choice, sample, shuffle, randFraction)
+seed(5)
+let a := [randInt(0..100) for k in 0..10]
+seed(5)
+let b := [randInt(0..100) for k in 0..10]
+assert(a = b)
+assert(randInt(3..4) = 3)
+assert(choice({7}) = 7)
+assert(sample({1, 2, 3}, 3) = {1, 2, 3})
+assert(shuffle([1, 1, 1]) = [1, 1, 1])
+assert(randFraction(1) = 0)
+";
+    let mut env = standard_env(ExecContext::default());
+
+    assert!(run(code, &mut env).is_ok());
+}
+
+#[test]
+fn seeded_context() {
+    let code = "
+from random import (randInt)
+let x := randInt(0..2**64)
+";
+    let value = || {
+        let mut env = standard_env(ExecContext::default().with_seed(3));
+        run(code, &mut env).unwrap();
+
+        match env.get("x") {
+            EnvResponse::Inmutable(obj) => obj.to_owned(),
+            _ => panic!("x should be defined"),
+        }
+    };
+
+    assert_eq!(value(), value());
+}
+
+#[test]
+fn generator_per_context() {
+    let code = "
+from random import (randInt)
+let x := randInt(0..2**64)
+";
+    let value = |env: &mut Environment| {
+        run(code, env).unwrap();
+
+        match env.get("x") {
+            EnvResponse::Inmutable(obj) => obj.to_owned(),
+            _ => panic!("x should be defined"),
+        }
+    };
+
+    let mut first = standard_env(ExecContext::default().with_seed(3));
+    let mut second = standard_env(ExecContext::default().with_seed(3));
+
+    assert_eq!(value(&mut first), value(&mut second));
+}